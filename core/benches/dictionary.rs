@@ -0,0 +1,96 @@
+//! Dictionary benchmark: HashSet / sorted array (before) vs compiled trie (after)
+//!
+//! Builds the old structures from the same word files, then compares memory,
+//! startup (build) time and lookup latency. Lookups go through the old path
+//! (`to_lowercase()` into a String, then hash) and the new one
+//! (`Trie::contains_lowercase`, lowercasing char by char).
+//!
+//! Run: cargo bench --bench dictionary
+
+use std::collections::HashSet;
+use std::hint::black_box;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+use gonhanh_core::data::trie::Trie;
+use gonhanh_core::data::{dictionary, english_dict, telex_doubles};
+
+const VI: &str = include_str!("../src/data/dictionaries/vi.dic");
+const ENGLISH: &str = include_str!("../src/data/english_dict_merged.txt");
+const TELEX_DOUBLES: &str = include_str!("../src/data/dictionaries/telex_doubles.txt");
+
+/// Passes over the query list per measurement
+const ROUNDS: usize = 20;
+
+/// Approximate heap of a `HashSet<&str>` (hashbrown: buckets of one pointer
+/// pair plus one control byte, at most 7/8 full)
+fn hashset_heap(set: &HashSet<&str>) -> usize {
+    let buckets = (set.capacity() * 8 / 7 + 1).next_power_of_two();
+    buckets * (size_of::<&str>() + 1)
+}
+
+/// Hits as listed, hits in uppercase, and misses
+fn queries(words: &[&str]) -> Vec<String> {
+    words
+        .iter()
+        .flat_map(|w| [w.to_string(), w.to_uppercase(), format!("{}q", w)])
+        .collect()
+}
+
+/// Average time per lookup of `f` over the queries
+fn time_per_lookup(queries: &[String], f: impl Fn(&str) -> bool) -> Duration {
+    let start = Instant::now();
+    let mut found = 0;
+    for _ in 0..ROUNDS {
+        for q in queries {
+            found += f(black_box(q)) as usize;
+        }
+    }
+    black_box(found);
+    start.elapsed() / (ROUNDS * queries.len()) as u32
+}
+
+fn report(name: &str, before: (usize, Duration, Duration), trie: &Trie, after: Duration) {
+    let (heap, build, lookup) = before;
+    println!("{}", name);
+    println!(
+        "  before: {:>7} B heap, {:>8.2?} build, {:>6.0?}/lookup",
+        heap, build, lookup
+    );
+    println!(
+        "  after:  {:>7} B heap, {:>7} B static, no build, {:>6.0?}/lookup",
+        0,
+        trie.size_bytes(),
+        after
+    );
+}
+
+fn bench_set(name: &str, text: &'static str, skip: usize, trie: &Trie) {
+    let words: Vec<&str> = text.lines().skip(skip).filter(|w| !w.is_empty()).collect();
+    let start = Instant::now();
+    let set: HashSet<&str> = words.iter().copied().collect();
+    let build = start.elapsed();
+    let queries = queries(&words);
+    let before = time_per_lookup(&queries, |q| set.contains(q.to_lowercase().as_str()));
+    let after = time_per_lookup(&queries, |q| trie.contains_lowercase(q.chars()));
+    report(name, (hashset_heap(&set), build, before), trie, after);
+}
+
+fn main() {
+    bench_set("vi.dic", VI, 1, &dictionary::VI);
+    bench_set("english_dict_merged.txt", ENGLISH, 0, &english_dict::WORDS);
+
+    // telex_doubles was a static sorted array searched with binary_search
+    let words: Vec<&str> = TELEX_DOUBLES.lines().collect();
+    let queries = queries(&words);
+    let before = time_per_lookup(&queries, |q| words.binary_search(&q).is_ok());
+    let after = time_per_lookup(&queries, |q| telex_doubles::WORDS.contains(q));
+    let array = words.len() * size_of::<&str>() + TELEX_DOUBLES.len();
+    println!("telex_doubles (before: {} B static array + strings)", array);
+    report(
+        "",
+        (0, Duration::ZERO, before),
+        &telex_doubles::WORDS,
+        after,
+    );
+}
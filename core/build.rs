@@ -0,0 +1,147 @@
+//! Build script: compile the word lists into tries (see `src/data/trie.rs`)
+//!
+//! Each list is built as a minimized trie (DAWG: shared prefixes and shared
+//! suffixes) over UTF-8 bytes and written to `OUT_DIR`, where the data
+//! modules `include_bytes!` it. No dependencies, so the format is hand-rolled.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// (source file, output file, skip first line: `.dic` word count)
+const LISTS: &[(&str, &str, bool)] = &[
+    ("src/data/dictionaries/vi.dic", "vi.trie", true),
+    ("src/data/dictionaries/keep.dic", "keep.trie", true),
+    ("src/data/english_dict_merged.txt", "english.trie", false),
+    (
+        "src/data/dictionaries/telex_doubles.txt",
+        "telex_doubles.trie",
+        false,
+    ),
+];
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR");
+    for &(source, output, skip_header) in LISTS {
+        println!("cargo:rerun-if-changed={}", source);
+        let text = fs::read_to_string(source).unwrap_or_else(|e| panic!("{}: {}", source, e));
+        let mut words: Vec<&str> = text
+            .lines()
+            .skip(skip_header as usize)
+            .filter(|line| !line.is_empty())
+            .collect();
+        words.sort_unstable();
+        words.dedup();
+        let bytes = Dawg::build(&words).serialize(words.len());
+        fs::write(Path::new(&out_dir).join(output), bytes).expect("write trie");
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
+
+/// Node while building: final flag and edges (label, node index) by label
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+struct Node {
+    is_final: bool,
+    edges: Vec<(u8, usize)>,
+}
+
+struct Dawg {
+    nodes: Vec<Node>,
+}
+
+impl Dawg {
+    /// Incremental construction from sorted words (Daciuk et al. 2000):
+    /// once a word is added, the part of the previous word's path not shared
+    /// with it can no longer change, so its nodes are merged with equal
+    /// registered nodes right away.
+    fn build(sorted: &[&str]) -> Self {
+        let mut dawg = Dawg {
+            nodes: vec![Node::default()],
+        };
+        let mut register: HashMap<Node, usize> = HashMap::new();
+        // Path of the previous word not yet minimized: (parent, label, child)
+        let mut unchecked: Vec<(usize, u8, usize)> = Vec::new();
+        let mut previous: &[u8] = &[];
+        for word in sorted {
+            let word = word.as_bytes();
+            let common = word
+                .iter()
+                .zip(previous)
+                .take_while(|(a, b)| a == b)
+                .count();
+            dawg.minimize(&mut register, &mut unchecked, common);
+            let mut node = unchecked.last().map_or(0, |&(_, _, child)| child);
+            for &byte in &word[common..] {
+                let child = dawg.nodes.len();
+                dawg.nodes.push(Node::default());
+                dawg.nodes[node].edges.push((byte, child));
+                unchecked.push((node, byte, child));
+                node = child;
+            }
+            dawg.nodes[node].is_final = true;
+            previous = word;
+        }
+        dawg.minimize(&mut register, &mut unchecked, 0);
+        dawg
+    }
+
+    fn minimize(
+        &mut self,
+        register: &mut HashMap<Node, usize>,
+        unchecked: &mut Vec<(usize, u8, usize)>,
+        down_to: usize,
+    ) {
+        while unchecked.len() > down_to {
+            let (parent, label, child) = unchecked.pop().unwrap();
+            match register.get(&self.nodes[child]) {
+                Some(&same) => {
+                    let edge = self.nodes[parent].edges.last_mut().unwrap();
+                    debug_assert_eq!(edge.0, label);
+                    edge.1 = same;
+                }
+                None => {
+                    register.insert(self.nodes[child].clone(), child);
+                }
+            }
+        }
+    }
+
+    /// Layout: word count (u32 LE), root offset (u32 LE), then nodes, each
+    /// `[final: u8][edge count: u8][labels..][targets: u24 LE..]`, children
+    /// before parents.
+    fn serialize(&self, word_count: usize) -> Vec<u8> {
+        let mut out = vec![0u8; 8];
+        let mut offsets: Vec<Option<u32>> = vec![None; self.nodes.len()];
+        // Iterative post-order from the root
+        let mut stack = vec![(0usize, false)];
+        while let Some((node, children_done)) = stack.pop() {
+            if offsets[node].is_some() {
+                continue;
+            }
+            if !children_done {
+                stack.push((node, true));
+                for &(_, child) in self.nodes[node].edges.iter().rev() {
+                    if offsets[child].is_none() {
+                        stack.push((child, false));
+                    }
+                }
+                continue;
+            }
+            let n = &self.nodes[node];
+            assert!(n.edges.len() <= u8::MAX as usize, "too many edges");
+            offsets[node] = Some(out.len() as u32);
+            out.push(n.is_final as u8);
+            out.push(n.edges.len() as u8);
+            out.extend(n.edges.iter().map(|&(label, _)| label));
+            for &(_, child) in &n.edges {
+                let target = offsets[child].expect("child written first");
+                assert!(target < 1 << 24, "trie too large for u24 offsets");
+                out.extend_from_slice(&target.to_le_bytes()[..3]);
+            }
+        }
+        out[0..4].copy_from_slice(&(word_count as u32).to_le_bytes());
+        out[4..8].copy_from_slice(&offsets[0].unwrap().to_le_bytes());
+        out
+    }
+}
@@ -0,0 +1,404 @@
+/*
+ * Gõ Nhanh core C API
+ *
+ * GENERATED by core/src/abi.rs - do not edit by hand.
+ * Regenerate: cd core && GONHANH_UPDATE_HEADER=1 cargo test --lib abi
+ */
+
+#ifndef GONHANH_H
+#define GONHANH_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#define GONHANH_ABI_VERSION 3
+#define GONHANH_MAX_CHARS 256
+
+#define IME_ACTION_NONE 0
+#define IME_ACTION_SEND 1
+#define IME_ACTION_RESTORE 2
+#define IME_ACTION_SUGGEST 3
+#define IME_FLAG_KEY_CONSUMED 0x01
+#define IME_FLAG_DELETE_TAIL 0x02
+
+#define IME_DICT_VIETNAMESE 0
+#define IME_DICT_KEEP 1
+#define IME_DICT_ENGLISH 2
+#define IME_DICT_NEVER_RESTORE 3
+
+#ifdef __cplusplus
+#define GONHANH_STATIC_ASSERT(c, m) static_assert(c, m)
+#else
+#define GONHANH_STATIC_ASSERT(c, m) _Static_assert(c, m)
+#endif
+
+/* Opaque engine handle (see ime_engine_new) */
+typedef struct ImeEngine ImeEngine;
+
+/* Key processing result (free with ime_free) */
+typedef struct ImeResult {
+    uint32_t chars[256];
+    uint8_t action;
+    uint8_t backspace;
+    uint8_t count;
+    uint8_t flags;
+} ImeResult;
+
+GONHANH_STATIC_ASSERT(sizeof(ImeResult) == 1028, "ImeResult size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImeResult, chars) == 0, "ImeResult.chars offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImeResult *)0)->chars) == 1024, "ImeResult.chars size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImeResult, action) == 1024, "ImeResult.action offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImeResult *)0)->action) == 1, "ImeResult.action size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImeResult, backspace) == 1025, "ImeResult.backspace offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImeResult *)0)->backspace) == 1, "ImeResult.backspace size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImeResult, count) == 1026, "ImeResult.count offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImeResult *)0)->count) == 1, "ImeResult.count size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImeResult, flags) == 1027, "ImeResult.flags offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImeResult *)0)->flags) == 1, "ImeResult.flags size mismatch with Rust core");
+
+/* Preedit mode result (free with ime_free_preedit) */
+typedef struct ImePreedit {
+    uint32_t preedit[256];
+    uint32_t commit[256];
+    uint32_t suggestion[256];
+    uint16_t preedit_len;
+    uint16_t cursor;
+    uint16_t commit_len;
+    uint16_t suggestion_len;
+    uint16_t suggestion_backspace;
+    uint8_t delete_before;
+    uint8_t flags;
+} ImePreedit;
+
+GONHANH_STATIC_ASSERT(sizeof(ImePreedit) == 3084, "ImePreedit size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImePreedit, preedit) == 0, "ImePreedit.preedit offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImePreedit *)0)->preedit) == 1024, "ImePreedit.preedit size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImePreedit, commit) == 1024, "ImePreedit.commit offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImePreedit *)0)->commit) == 1024, "ImePreedit.commit size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImePreedit, suggestion) == 2048, "ImePreedit.suggestion offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImePreedit *)0)->suggestion) == 1024, "ImePreedit.suggestion size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImePreedit, preedit_len) == 3072, "ImePreedit.preedit_len offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImePreedit *)0)->preedit_len) == 2, "ImePreedit.preedit_len size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImePreedit, cursor) == 3074, "ImePreedit.cursor offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImePreedit *)0)->cursor) == 2, "ImePreedit.cursor size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImePreedit, commit_len) == 3076, "ImePreedit.commit_len offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImePreedit *)0)->commit_len) == 2, "ImePreedit.commit_len size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImePreedit, suggestion_len) == 3078, "ImePreedit.suggestion_len offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImePreedit *)0)->suggestion_len) == 2, "ImePreedit.suggestion_len size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImePreedit, suggestion_backspace) == 3080, "ImePreedit.suggestion_backspace offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImePreedit *)0)->suggestion_backspace) == 2, "ImePreedit.suggestion_backspace size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImePreedit, delete_before) == 3082, "ImePreedit.delete_before offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImePreedit *)0)->delete_before) == 1, "ImePreedit.delete_before size mismatch with Rust core");
+GONHANH_STATIC_ASSERT(offsetof(ImePreedit, flags) == 3083, "ImePreedit.flags offset mismatch with Rust core");
+GONHANH_STATIC_ASSERT(sizeof(((ImePreedit *)0)->flags) == 1, "ImePreedit.flags size mismatch with Rust core");
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Initialize the IME engine. */
+void ime_init(void);
+
+/* Get the ABI version of this library. */
+uint32_t ime_abi_version(void);
+
+/* Get `sizeof(Result)` as laid out by this library. */
+uint32_t ime_result_size(void);
+
+/* Get `sizeof(Preedit)` as laid out by this library. See `ime_result_size`. */
+uint32_t ime_preedit_size(void);
+
+/* Process a key event and return the result. */
+ImeResult *ime_key(uint16_t key, bool caps, bool ctrl);
+
+/* Process a key event with extended parameters. */
+ImeResult *ime_key_ext(uint16_t key, bool caps, bool ctrl, bool shift);
+
+/* Process a key event in preedit mode. */
+ImePreedit *ime_key_preedit(uint16_t key, bool caps, bool ctrl, bool shift);
+
+/* Process a key event with the actual Unicode character. */
+ImeResult *ime_key_with_char(uint16_t key, bool caps, bool ctrl, bool shift, uint32_t char_code);
+
+/* Process a character produced by the OS keyboard layout. */
+ImeResult *ime_char(uint32_t char_code, bool ctrl);
+
+/* Set the input method. */
+void ime_method(uint8_t method);
+
+/* Register a custom input method from a keymap spec and switch to it. */
+char *ime_method_custom(const char *spec);
+
+/* Free a string returned by the engine. */
+void ime_free_string(char *s);
+
+/* Enable or disable the engine. */
+void ime_enabled(bool enabled);
+
+/* Set whether to skip w→ư shortcut in Telex mode. */
+void ime_skip_w_shortcut(bool skip);
+
+/* Set whether bracket shortcuts are enabled: ] → ư, [ → ơ (Issue #159) */
+void ime_bracket_shortcut(bool enabled);
+
+/* Set whether ESC key restores raw ASCII input. */
+void ime_esc_restore(bool enabled);
+
+/* Set whether to enable free tone placement (skip validation). */
+void ime_free_tone(bool enabled);
+
+/* Set whether to use modern orthography for tone placement. */
+void ime_modern(bool modern);
+
+/* Enable/disable English auto-restore (experimental feature). */
+void ime_english_auto_restore(bool enabled);
+
+/* Enable/disable auto-capitalize after sentence-ending punctuation. */
+void ime_auto_capitalize(bool enabled);
+
+/* Enable/disable foreign consonants (z, w, j, f) as valid initial consonants. */
+void ime_allow_foreign_consonants(bool enabled);
+
+/* Enable/disable suggestions for misspelled words. */
+void ime_suggest_on_invalid(bool enabled);
+
+/* Set output charset. */
+void ime_charset(uint8_t charset);
+
+/* Set options from a configuration text. */
+char *ime_configure(const char *config);
+
+/* Get all options as configuration text, one `key = value` per line. */
+char *ime_get_config(void);
+
+/* Clear the input buffer. */
+void ime_clear(void);
+
+/* Clear everything including word history. */
+void ime_clear_all(void);
+
+/* Get the full composed buffer as UTF-32 codepoints. */
+int64_t ime_get_buffer(uint32_t *out, int64_t max_len);
+
+/* Save the typing state: the word in progress, word history and pending */
+int64_t ime_snapshot(uint8_t *out, int64_t max_len);
+
+/* Restore the typing state saved by `ime_snapshot`. */
+bool ime_restore_snapshot(const uint8_t *data, int64_t len);
+
+/* Start recording a keystroke trace. */
+void ime_trace_start(void);
+
+/* Stop recording and get the trace. */
+char *ime_trace_stop(void);
+
+/* Free a result pointer returned by `ime_key`. */
+void ime_free(ImeResult *r);
+
+/* Free a preedit result returned by `ime_key_preedit`. */
+void ime_free_preedit(ImePreedit *p);
+
+/* Add a shortcut to the engine. */
+void ime_add_shortcut(const char *trigger, const char *replacement);
+
+/* Remove a shortcut from the engine. */
+void ime_remove_shortcut(const char *trigger);
+
+/* Clear all shortcuts from the engine. */
+void ime_clear_shortcuts(void);
+
+/* Restore buffer from a Vietnamese word string. */
+void ime_restore_word(const char *word);
+
+/* Restore buffer from the word around the caret. */
+uint32_t ime_restore_surrounding(const char *before, const char *after);
+
+/* Add a word (or "re-"/"-ing" affix rule) to a user dictionary */
+bool ime_dict_add(uint8_t list, const char *word);
+
+/* Remove a word from a dictionary, hiding it if it is built in. */
+bool ime_dict_remove(uint8_t list, const char *word);
+
+/* Add every word of a dictionary text (one word per line). */
+int64_t ime_dict_load(uint8_t list, const char *content);
+
+/* Add every word of a dictionary file. See `ime_dict_load`. */
+int64_t ime_dict_load_file(uint8_t list, const char *path);
+
+/* Drop all user changes to a dictionary, restoring the built-in words. */
+void ime_dict_clear(uint8_t list);
+
+/* Enable/disable learning from the user's corrections. */
+void ime_learn_auto_restore(bool enabled);
+
+/* Set the corrections needed before a word is always (or never) restored. */
+void ime_learn_threshold(uint8_t threshold);
+
+/* Export the learned table as bytes. */
+int64_t ime_learned_export(uint8_t *out, int64_t max_len);
+
+/* Replace the learned table with bytes from `ime_learned_export`. */
+bool ime_learned_import(const uint8_t *data, int64_t len);
+
+/* Forget everything learned. */
+void ime_learned_clear(void);
+
+/* Save the current settings (including shortcuts) as a profile. */
+bool ime_profile_save(const char *name);
+
+/* Remove a profile; apps assigned to it go back to the default profile. */
+bool ime_profile_remove(const char *name);
+
+/* Assign an app to a profile (the profile may be saved later). */
+bool ime_profile_assign(const char *app, const char *name);
+
+/* Drop an app's assignment: it goes back to the default profile. */
+bool ime_profile_unassign(const char *app);
+
+/* Switch to an app's profile. Call when the focused app changes. */
+bool ime_profile_switch_app(const char *app);
+
+/* Remove all profiles and app assignments. Current settings are kept. */
+void ime_profile_clear(void);
+
+/* Create a new engine instance. */
+ImeEngine *ime_engine_new(void);
+
+/* Free an engine instance created by `ime_engine_new`. */
+void ime_engine_free(ImeEngine *handle);
+
+/* Process a key event on an engine instance. See `ime_key`. */
+ImeResult *ime_engine_key(ImeEngine *handle, uint16_t key, bool caps, bool ctrl);
+
+/* Process a key event with shift state on an engine instance. See `ime_key_ext`. */
+ImeResult *ime_engine_key_ext(ImeEngine *handle, uint16_t key, bool caps, bool ctrl, bool shift);
+
+/* Process a key event in preedit mode on an engine instance. See `ime_key_preedit`. */
+ImePreedit *ime_engine_key_preedit(ImeEngine *handle, uint16_t key, bool caps, bool ctrl, bool shift);
+
+/* Process a key event with the actual Unicode character on an engine instance. */
+ImeResult *ime_engine_key_with_char(ImeEngine *handle, uint16_t key, bool caps, bool ctrl, bool shift, uint32_t char_code);
+
+/* Process a layout-produced character on an engine instance. See `ime_char`. */
+ImeResult *ime_engine_char(ImeEngine *handle, uint32_t char_code, bool ctrl);
+
+/* Set the input method on an engine instance (0=Telex, 1=VNI, 2=VIQR). */
+void ime_engine_method(ImeEngine *handle, uint8_t method);
+
+/* Register a custom input method on an engine instance. See `ime_method_custom`. */
+char *ime_engine_method_custom(ImeEngine *handle, const char *spec);
+
+/* Enable or disable an engine instance. See `ime_enabled`. */
+void ime_engine_enabled(ImeEngine *handle, bool enabled);
+
+/* Set whether to skip w→ư shortcut on an engine instance. See `ime_skip_w_shortcut`. */
+void ime_engine_skip_w_shortcut(ImeEngine *handle, bool skip);
+
+/* Set bracket shortcuts on an engine instance. See `ime_bracket_shortcut`. */
+void ime_engine_bracket_shortcut(ImeEngine *handle, bool enabled);
+
+/* Set ESC restore on an engine instance. See `ime_esc_restore`. */
+void ime_engine_esc_restore(ImeEngine *handle, bool enabled);
+
+/* Set free tone placement on an engine instance. See `ime_free_tone`. */
+void ime_engine_free_tone(ImeEngine *handle, bool enabled);
+
+/* Set modern tone placement on an engine instance. See `ime_modern`. */
+void ime_engine_modern(ImeEngine *handle, bool modern);
+
+/* Set English auto-restore on an engine instance. See `ime_english_auto_restore`. */
+void ime_engine_english_auto_restore(ImeEngine *handle, bool enabled);
+
+/* Set auto-capitalize on an engine instance. See `ime_auto_capitalize`. */
+void ime_engine_auto_capitalize(ImeEngine *handle, bool enabled);
+
+/* Set foreign consonants on an engine instance. See `ime_allow_foreign_consonants`. */
+void ime_engine_allow_foreign_consonants(ImeEngine *handle, bool enabled);
+
+/* Set suggestions on an engine instance. See `ime_suggest_on_invalid`. */
+void ime_engine_suggest_on_invalid(ImeEngine *handle, bool enabled);
+
+/* Set output charset on an engine instance. See `ime_charset`. */
+void ime_engine_charset(ImeEngine *handle, uint8_t charset);
+
+/* Set options of an engine instance from a configuration text. */
+char *ime_engine_configure(ImeEngine *handle, const char *config);
+
+/* Get all options of an engine instance. See `ime_get_config`. */
+char *ime_engine_get_config(ImeEngine *handle);
+
+/* Clear the input buffer of an engine instance. See `ime_clear`. */
+void ime_engine_clear(ImeEngine *handle);
+
+/* Clear everything including word history of an engine instance. See `ime_clear_all`. */
+void ime_engine_clear_all(ImeEngine *handle);
+
+/* Get the composed buffer of an engine instance. See `ime_get_buffer`. */
+int64_t ime_engine_get_buffer(ImeEngine *handle, uint32_t *out, int64_t max_len);
+
+/* Save the typing state of an engine instance. See `ime_snapshot`. */
+int64_t ime_engine_snapshot(ImeEngine *handle, uint8_t *out, int64_t max_len);
+
+/* Restore the typing state of an engine instance. See `ime_restore_snapshot`. */
+bool ime_engine_restore_snapshot(ImeEngine *handle, const uint8_t *data, int64_t len);
+
+/* Start recording a keystroke trace on an engine instance. See */
+void ime_engine_trace_start(ImeEngine *handle);
+
+/* Stop recording on an engine instance and get the trace. See */
+char *ime_engine_trace_stop(ImeEngine *handle);
+
+/* Add a shortcut to an engine instance. See `ime_add_shortcut`. */
+void ime_engine_add_shortcut(ImeEngine *handle, const char *trigger, const char *replacement);
+
+/* Remove a shortcut from an engine instance. See `ime_remove_shortcut`. */
+void ime_engine_remove_shortcut(ImeEngine *handle, const char *trigger);
+
+/* Clear all shortcuts of an engine instance. See `ime_clear_shortcuts`. */
+void ime_engine_clear_shortcuts(ImeEngine *handle);
+
+/* Restore an engine instance's buffer from a word. See `ime_restore_word`. */
+void ime_engine_restore_word(ImeEngine *handle, const char *word);
+
+/* Restore an engine instance's buffer from the word around the caret. */
+uint32_t ime_engine_restore_surrounding(ImeEngine *handle, const char *before, const char *after);
+
+/* Set learning on an engine instance. See `ime_learn_auto_restore`. */
+void ime_engine_learn_auto_restore(ImeEngine *handle, bool enabled);
+
+/* Set the learning threshold of an engine instance. See `ime_learn_threshold`. */
+void ime_engine_learn_threshold(ImeEngine *handle, uint8_t threshold);
+
+/* Export the learned table of an engine instance. See `ime_learned_export`. */
+int64_t ime_engine_learned_export(ImeEngine *handle, uint8_t *out, int64_t max_len);
+
+/* Import a learned table into an engine instance. See `ime_learned_import`. */
+bool ime_engine_learned_import(ImeEngine *handle, const uint8_t *data, int64_t len);
+
+/* Forget everything an engine instance learned. See `ime_learned_clear`. */
+void ime_engine_learned_clear(ImeEngine *handle);
+
+/* Save the settings of an engine instance as a profile. See `ime_profile_save`. */
+bool ime_engine_profile_save(ImeEngine *handle, const char *name);
+
+/* Remove a profile of an engine instance. See `ime_profile_remove`. */
+bool ime_engine_profile_remove(ImeEngine *handle, const char *name);
+
+/* Assign an app to a profile of an engine instance. See `ime_profile_assign`. */
+bool ime_engine_profile_assign(ImeEngine *handle, const char *app, const char *name);
+
+/* Drop an app's assignment on an engine instance. See `ime_profile_unassign`. */
+bool ime_engine_profile_unassign(ImeEngine *handle, const char *app);
+
+/* Switch an engine instance to an app's profile. See `ime_profile_switch_app`. */
+bool ime_engine_profile_switch_app(ImeEngine *handle, const char *app);
+
+/* Remove all profiles of an engine instance. See `ime_profile_clear`. */
+void ime_engine_profile_clear(ImeEngine *handle);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GONHANH_H */
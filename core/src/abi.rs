@@ -0,0 +1,435 @@
+//! C ABI description and header generation
+//!
+//! The checked-in C header (`core/include/gonhanh.h`) is generated from the
+//! Rust definitions in this crate:
+//! - `Result` / `Preedit` layouts come from `size_of` / `offset_of!` on the real structs
+//! - Function prototypes are parsed from the `extern "C"` exports in `lib.rs`
+//!
+//! A unit test compares the generated text with the checked-in file, so any
+//! layout or signature change fails CI until the header is regenerated:
+//!
+//! ```sh
+//! GONHANH_UPDATE_HEADER=1 cargo test --lib abi
+//! ```
+//!
+//! Platforms should compare `ime_abi_version()` and `ime_result_size()` with
+//! `GONHANH_ABI_VERSION` and `sizeof(ImeResult)` at startup.
+
+use crate::data::user_dict::WordList;
+use crate::engine::{
+    buffer::MAX, preedit::Preedit, Action, Result, FLAG_DELETE_TAIL, FLAG_KEY_CONSUMED,
+};
+use std::fmt::Write;
+use std::mem::{offset_of, size_of};
+
+/// ABI version of the FFI surface
+///
+/// Bump when `Result`/`Preedit` layout changes, an existing export changes
+/// signature, or a result can carry a new value (`Action::Suggest`: 2,
+/// `Preedit` suggestions: 3). Adding new exports does not require a bump.
+pub const ABI_VERSION: u32 = 3;
+
+/// Header path relative to the crate root
+pub const HEADER_PATH: &str = "include/gonhanh.h";
+
+/// FFI source parsed for function prototypes
+const LIB_SOURCE: &str = include_str!("lib.rs");
+
+/// C view of one struct field (`len` > 0 for arrays)
+struct Field {
+    name: &'static str,
+    c_type: &'static str,
+    len: usize,
+    offset: usize,
+    size: usize,
+}
+
+/// `Result` fields in declaration order
+fn result_fields() -> [Field; 5] {
+    [
+        Field {
+            name: "chars",
+            c_type: "uint32_t",
+            len: MAX,
+            offset: offset_of!(Result, chars),
+            size: size_of::<[u32; MAX]>(),
+        },
+        Field {
+            name: "action",
+            c_type: "uint8_t",
+            len: 0,
+            offset: offset_of!(Result, action),
+            size: size_of::<u8>(),
+        },
+        Field {
+            name: "backspace",
+            c_type: "uint8_t",
+            len: 0,
+            offset: offset_of!(Result, backspace),
+            size: size_of::<u8>(),
+        },
+        Field {
+            name: "count",
+            c_type: "uint8_t",
+            len: 0,
+            offset: offset_of!(Result, count),
+            size: size_of::<u8>(),
+        },
+        Field {
+            name: "flags",
+            c_type: "uint8_t",
+            len: 0,
+            offset: offset_of!(Result, flags),
+            size: size_of::<u8>(),
+        },
+    ]
+}
+
+/// `Preedit` fields in declaration order
+fn preedit_fields() -> [Field; 10] {
+    [
+        Field {
+            name: "preedit",
+            c_type: "uint32_t",
+            len: MAX,
+            offset: offset_of!(Preedit, preedit),
+            size: size_of::<[u32; MAX]>(),
+        },
+        Field {
+            name: "commit",
+            c_type: "uint32_t",
+            len: MAX,
+            offset: offset_of!(Preedit, commit),
+            size: size_of::<[u32; MAX]>(),
+        },
+        Field {
+            name: "suggestion",
+            c_type: "uint32_t",
+            len: MAX,
+            offset: offset_of!(Preedit, suggestion),
+            size: size_of::<[u32; MAX]>(),
+        },
+        Field {
+            name: "preedit_len",
+            c_type: "uint16_t",
+            len: 0,
+            offset: offset_of!(Preedit, preedit_len),
+            size: size_of::<u16>(),
+        },
+        Field {
+            name: "cursor",
+            c_type: "uint16_t",
+            len: 0,
+            offset: offset_of!(Preedit, cursor),
+            size: size_of::<u16>(),
+        },
+        Field {
+            name: "commit_len",
+            c_type: "uint16_t",
+            len: 0,
+            offset: offset_of!(Preedit, commit_len),
+            size: size_of::<u16>(),
+        },
+        Field {
+            name: "suggestion_len",
+            c_type: "uint16_t",
+            len: 0,
+            offset: offset_of!(Preedit, suggestion_len),
+            size: size_of::<u16>(),
+        },
+        Field {
+            name: "suggestion_backspace",
+            c_type: "uint16_t",
+            len: 0,
+            offset: offset_of!(Preedit, suggestion_backspace),
+            size: size_of::<u16>(),
+        },
+        Field {
+            name: "delete_before",
+            c_type: "uint8_t",
+            len: 0,
+            offset: offset_of!(Preedit, delete_before),
+            size: size_of::<u8>(),
+        },
+        Field {
+            name: "flags",
+            c_type: "uint8_t",
+            len: 0,
+            offset: offset_of!(Preedit, flags),
+            size: size_of::<u8>(),
+        },
+    ]
+}
+
+/// Map a Rust FFI type (as written in `lib.rs`) to its C spelling
+fn c_type(rust: &str) -> &'static str {
+    match rust.split_whitespace().collect::<String>().as_str() {
+        "" | "()" => "void",
+        "bool" => "bool",
+        "u8" => "uint8_t",
+        "u16" => "uint16_t",
+        "u32" => "uint32_t",
+        "i64" => "int64_t",
+        "*mutu8" => "uint8_t *",
+        "*constu8" => "const uint8_t *",
+        "*mutu32" => "uint32_t *",
+        "*constu32" => "const uint32_t *",
+        "*conststd::os::raw::c_char" => "const char *",
+        "*mutstd::os::raw::c_char" => "char *",
+        "*mutResult" => "ImeResult *",
+        "*mutPreedit" => "ImePreedit *",
+        "*mutEngine" => "ImeEngine *",
+        other => panic!("abi: no C mapping for FFI type `{}`", other),
+    }
+}
+
+/// Exported function parsed from `lib.rs`
+struct Export {
+    summary: String,
+    name: String,
+    params: Vec<(String, String)>,
+    ret: String,
+}
+
+/// Parse every `extern "C" fn` export in `src`
+fn parse_exports(src: &str) -> Vec<Export> {
+    const MARKER: &str = "extern \"C\" fn ";
+    let mut exports = Vec::new();
+    let mut rest = src;
+    let mut consumed = 0;
+
+    while let Some(idx) = rest.find(MARKER) {
+        let start = consumed + idx;
+        let after = &src[start + MARKER.len()..];
+        let open = after.find('(').expect("abi: export without parameter list");
+        let close = after.find(')').expect("abi: unterminated parameter list");
+        let body = after.find('{').expect("abi: export without body");
+
+        let name = after[..open].trim().to_string();
+        let params = after[open + 1..close]
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                let (n, t) = p.split_once(':').expect("abi: parameter without type");
+                (n.trim().to_string(), t.trim().to_string())
+            })
+            .collect();
+        let ret = after[close + 1..body]
+            .trim()
+            .trim_start_matches("->")
+            .trim()
+            .to_string();
+
+        exports.push(Export {
+            summary: doc_summary(&src[..start]),
+            name,
+            params,
+            ret,
+        });
+
+        consumed = start + MARKER.len();
+        rest = &src[consumed..];
+    }
+    exports
+}
+
+/// First `///` line of the doc block directly above an item
+fn doc_summary(before: &str) -> String {
+    let mut summary = None;
+    for line in before.lines().rev().skip(1) {
+        let line = line.trim();
+        if let Some(doc) = line.strip_prefix("///") {
+            summary = Some(doc.trim().to_string());
+        } else if !line.starts_with("#[") {
+            break;
+        }
+    }
+    summary.unwrap_or_default()
+}
+
+/// Generate the C header text
+pub fn c_header() -> String {
+    let mut h = format!(
+        r#"/*
+ * Gõ Nhanh core C API
+ *
+ * GENERATED by core/src/abi.rs - do not edit by hand.
+ * Regenerate: cd core && GONHANH_UPDATE_HEADER=1 cargo test --lib abi
+ */
+
+#ifndef GONHANH_H
+#define GONHANH_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#define GONHANH_ABI_VERSION {abi}
+#define GONHANH_MAX_CHARS {max}
+
+#define IME_ACTION_NONE {none}
+#define IME_ACTION_SEND {send}
+#define IME_ACTION_RESTORE {restore}
+#define IME_ACTION_SUGGEST {suggest}
+#define IME_FLAG_KEY_CONSUMED 0x{consumed:02X}
+#define IME_FLAG_DELETE_TAIL 0x{delete_tail:02X}
+
+#define IME_DICT_VIETNAMESE {dict_vi}
+#define IME_DICT_KEEP {dict_keep}
+#define IME_DICT_ENGLISH {dict_en}
+#define IME_DICT_NEVER_RESTORE {dict_never}
+
+#ifdef __cplusplus
+#define GONHANH_STATIC_ASSERT(c, m) static_assert(c, m)
+#else
+#define GONHANH_STATIC_ASSERT(c, m) _Static_assert(c, m)
+#endif
+
+/* Opaque engine handle (see ime_engine_new) */
+typedef struct ImeEngine ImeEngine;
+"#,
+        abi = ABI_VERSION,
+        max = MAX,
+        none = Action::None as u8,
+        send = Action::Send as u8,
+        restore = Action::Restore as u8,
+        suggest = Action::Suggest as u8,
+        consumed = FLAG_KEY_CONSUMED,
+        delete_tail = FLAG_DELETE_TAIL,
+        dict_vi = WordList::Vietnamese as u8,
+        dict_keep = WordList::Keep as u8,
+        dict_en = WordList::English as u8,
+        dict_never = WordList::NeverRestore as u8,
+    );
+
+    write_struct(
+        &mut h,
+        "Key processing result (free with ime_free)",
+        "ImeResult",
+        &result_fields(),
+        size_of::<Result>(),
+    );
+    write_struct(
+        &mut h,
+        "Preedit mode result (free with ime_free_preedit)",
+        "ImePreedit",
+        &preedit_fields(),
+        size_of::<Preedit>(),
+    );
+
+    h.push_str("\n#ifdef __cplusplus\nextern \"C\" {\n#endif\n");
+    for e in parse_exports(LIB_SOURCE) {
+        h.push('\n');
+        if !e.summary.is_empty() {
+            let _ = writeln!(h, "/* {} */", e.summary);
+        }
+        let params = if e.params.is_empty() {
+            "void".to_string()
+        } else {
+            e.params
+                .iter()
+                .map(|(name, ty)| c_decl(c_type(ty), name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let _ = writeln!(h, "{}({});", c_decl(c_type(&e.ret), &e.name), params);
+    }
+    h.push_str("\n#ifdef __cplusplus\n}\n#endif\n\n#endif /* GONHANH_H */\n");
+    h
+}
+
+/// Write a struct typedef plus layout assertions against the Rust struct
+fn write_struct(h: &mut String, comment: &str, name: &str, fields: &[Field], size: usize) {
+    // Writing to a String cannot fail
+    let _ = writeln!(h, "\n/* {} */\ntypedef struct {} {{", comment, name);
+    for f in fields {
+        if f.len > 0 {
+            let _ = writeln!(h, "    {} {}[{}];", f.c_type, f.name, f.len);
+        } else {
+            let _ = writeln!(h, "    {} {};", f.c_type, f.name);
+        }
+    }
+    let _ = writeln!(h, "}} {};\n", name);
+    let _ = writeln!(
+        h,
+        "GONHANH_STATIC_ASSERT(sizeof({0}) == {1}, \"{0} size mismatch with Rust core\");",
+        name, size
+    );
+    for f in fields {
+        let _ = writeln!(
+            h,
+            "GONHANH_STATIC_ASSERT(offsetof({0}, {1}) == {2}, \"{0}.{1} offset mismatch with Rust core\");",
+            name, f.name, f.offset
+        );
+        let _ = writeln!(
+            h,
+            "GONHANH_STATIC_ASSERT(sizeof((({0} *)0)->{1}) == {2}, \"{0}.{1} size mismatch with Rust core\");",
+            name, f.name, f.size
+        );
+    }
+}
+
+/// Join a C type and a name (`int x`, `char *x`)
+fn c_decl(c_type: &str, name: &str) -> String {
+    if c_type.ends_with('*') {
+        format!("{}{}", c_type, name)
+    } else {
+        format!("{} {}", c_type, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_result_fields_cover_struct() {
+        // Every byte of Result must be described (no new fields hidden from C)
+        let fields = result_fields();
+        let mut end = 0;
+        for f in &fields {
+            assert_eq!(f.offset, end, "gap or reorder before field `{}`", f.name);
+            end = f.offset + f.size;
+        }
+        assert_eq!(end, size_of::<Result>(), "Result has undeclared fields");
+    }
+
+    #[test]
+    fn test_preedit_fields_cover_struct() {
+        let mut end = 0;
+        for f in &preedit_fields() {
+            assert_eq!(f.offset, end, "gap or reorder before field `{}`", f.name);
+            end = f.offset + f.size;
+        }
+        assert_eq!(end, size_of::<Preedit>(), "Preedit has undeclared fields");
+    }
+
+    #[test]
+    fn test_parse_exports() {
+        let exports = parse_exports(LIB_SOURCE);
+        let key_ext = exports.iter().find(|e| e.name == "ime_key_ext").unwrap();
+        assert_eq!(key_ext.params.len(), 4);
+        assert_eq!(key_ext.ret, "*mut Result");
+        assert!(exports.iter().any(|e| e.name == "ime_abi_version"));
+        assert!(exports.iter().any(|e| e.name == "ime_engine_new"));
+    }
+
+    #[test]
+    fn test_header_is_up_to_date() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(HEADER_PATH);
+        let expected = c_header();
+        if std::env::var_os("GONHANH_UPDATE_HEADER").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &expected).unwrap();
+            return;
+        }
+        let actual = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            actual == expected,
+            "{} is out of date with the Rust FFI definitions.\n\
+             Regenerate: cd core && GONHANH_UPDATE_HEADER=1 cargo test --lib abi",
+            HEADER_PATH
+        );
+    }
+}
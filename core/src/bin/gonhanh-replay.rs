@@ -0,0 +1,101 @@
+//! gonhanh-replay - Rerun keystroke traces and diff the results
+//!
+//! ```text
+//! $ gonhanh-replay bug.trace
+//! bug.trace: key 4 (14): expected send bs=1 "ê", got none
+//! ```
+//!
+//! Traces are recorded by the engine (`ime_trace_start` / `ime_trace_stop`).
+//! Exits with 1 if any result differs from the recorded one, 2 if a trace
+//! can't be read or its starting state can't be restored.
+
+use gonhanh_core::engine::trace::Trace;
+use std::fs;
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: gonhanh-replay [OPTIONS] [TRACE]...
+
+Replay keystroke traces on a fresh engine and report every key whose
+result differs from the recorded one. Reads stdin without TRACE files.
+
+Options:
+  --update           Rewrite the traces with the current results
+  -h, --help         Show this help
+";
+
+fn main() -> ExitCode {
+    let mut update = false;
+    let mut paths = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--update" => update = true,
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            _ if arg.starts_with('-') && arg != "-" => {
+                eprintln!("gonhanh-replay: unknown option '{}'\n\n{}", arg, USAGE);
+                return ExitCode::from(2);
+            }
+            _ => paths.push(arg),
+        }
+    }
+    if paths.is_empty() {
+        paths.push("-".to_string());
+    }
+
+    let mut differs = false;
+    for path in &paths {
+        match replay(path, update) {
+            Ok(same) => differs |= !same,
+            Err(e) => {
+                eprintln!("gonhanh-replay: {}: {}", path, e);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    if differs {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Replay one trace; true if every result matches (or it was updated)
+fn replay(path: &str, update: bool) -> Result<bool, String> {
+    let text = if path == "-" {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| e.to_string())?;
+        text
+    } else {
+        fs::read_to_string(path).map_err(|e| e.to_string())?
+    };
+    let trace = Trace::parse(&text).map_err(|e| e.to_string())?;
+
+    if update {
+        let replayed = trace.replay().to_string();
+        if path == "-" {
+            print!("{}", replayed);
+        } else if replayed != text {
+            fs::write(path, replayed).map_err(|e| e.to_string())?;
+            println!("{}: updated", path);
+        }
+        return Ok(true);
+    }
+
+    let mismatches = trace.mismatches();
+    for m in &mismatches {
+        println!(
+            "{}: key {} ({}): expected {}, got {}",
+            path, m.index, m.input, m.expected, m.actual
+        );
+    }
+    if mismatches.is_empty() {
+        println!("{}: ok ({} keys)", path, trace.keys().count());
+    }
+    Ok(mismatches.is_empty())
+}
@@ -0,0 +1,78 @@
+//! gonhanh - Convert Telex/VNI-typed text on stdin to Vietnamese on stdout
+//!
+//! ```text
+//! $ echo "Tieengs Vieejt" | gonhanh
+//! Tiếng Việt
+//! ```
+//!
+//! Bytes that are not valid UTF-8 are passed through unchanged.
+
+use gonhanh_core::convert::{self, Options};
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: gonhanh [OPTIONS] < input > output
+
+Convert text typed without an IME (Telex by default) to Vietnamese.
+
+Options:
+  --vni              Input is VNI (digits 1-9 for marks and tones)
+  --viqr             Input is VIQR (' ` ? ~ . marks, ^ + ( tones, \\ escape)
+  --telex            Input is Telex (default)
+  --traditional      Traditional tone placement (hòa, thúy)
+  --free-tone        Place marks anywhere, skip spelling validation
+  --auto-restore     Restore English words typed with Telex modifiers
+  --foreign          Accept z, w, j, f as initial consonants
+  -h, --help         Show this help
+";
+
+fn main() -> ExitCode {
+    let mut method = 0;
+    let mut options = Options::default();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--telex" => method = 0,
+            "--vni" => method = 1,
+            "--viqr" => method = 2,
+            "--traditional" => options.modern_tone = false,
+            "--free-tone" => options.free_tone = true,
+            "--auto-restore" => options.english_auto_restore = true,
+            "--foreign" => options.allow_foreign_consonants = true,
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            _ => {
+                eprintln!("gonhanh: unknown option '{}'\n\n{}", arg, USAGE);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    match run(method, options) {
+        Ok(()) => ExitCode::SUCCESS,
+        // Reader went away (`gonhanh < big.txt | head`): not an error
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("gonhanh: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Convert stdin line by line so output streams
+fn run(method: u8, options: Options) -> io::Result<()> {
+    let mut input = io::stdin().lock();
+    let mut output = io::BufWriter::new(io::stdout().lock());
+    let mut line = Vec::new();
+    while input.read_until(b'\n', &mut line)? > 0 {
+        for chunk in line.utf8_chunks() {
+            let text = convert::convert(chunk.valid(), method, options);
+            output.write_all(text.as_bytes())?;
+            output.write_all(chunk.invalid())?;
+        }
+        line.clear();
+    }
+    output.flush()
+}
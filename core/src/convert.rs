@@ -0,0 +1,161 @@
+//! Text Conversion - Whole-text Telex/VNI/VIQR to Vietnamese
+//!
+//! Converts text typed without an IME ("Tieengs Vieejt") in bulk. Each word
+//! is typed through a fresh `Engine` with the same options as the
+//! interactive engine; everything between words is copied unchanged.
+//!
+//! ```
+//! use gonhanh_core::convert::{telex_to_unicode, Options};
+//!
+//! assert_eq!(telex_to_unicode("Tieengs Vieejt!", Options::default()), "Tiếng Việt!");
+//! ```
+
+use crate::data::keys;
+use crate::engine::{Action, Engine, Result};
+use crate::utils;
+
+/// Engine options used for conversion (same defaults as `Engine::new`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// Modern tone placement (hoà, thuý) instead of traditional (hòa, thúy)
+    pub modern_tone: bool,
+    /// Place marks anywhere, skipping spelling validation
+    pub free_tone: bool,
+    /// Restore English words typed with Telex modifiers ("text", "fix")
+    pub english_auto_restore: bool,
+    /// Accept z, w, j, f as initial consonants (loanwords)
+    pub allow_foreign_consonants: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            modern_tone: true,
+            free_tone: false,
+            english_auto_restore: false,
+            allow_foreign_consonants: false,
+        }
+    }
+}
+
+/// Convert Telex-typed text to Vietnamese
+pub fn telex_to_unicode(text: &str, options: Options) -> String {
+    convert(text, 0, options)
+}
+
+/// Convert VNI-typed text to Vietnamese
+pub fn vni_to_unicode(text: &str, options: Options) -> String {
+    convert(text, 1, options)
+}
+
+/// VIQR mark and tone symbols, plus the `\` escape
+const VIQR_SYMBOLS: &[char] = &['\'', '`', '?', '~', '.', '^', '+', '*', '(', '\\'];
+
+/// Convert text typed with input method `method` (0=Telex, 1=VNI, 2=VIQR)
+///
+/// Words start with an ASCII letter (or a digit for VNI) and run over ASCII
+/// letters, plus digits for VNI and the mark/tone symbols for VIQR. As when
+/// typing, VIQR `.` and `?` right after an unmarked word are marks: write
+/// `\.` to end such a sentence. All other text, including already-Vietnamese
+/// letters, is copied byte-for-byte.
+pub fn convert(text: &str, method: u8, options: Options) -> String {
+    let mut e = Engine::new();
+    e.set_method(method);
+    e.set_modern_tone(options.modern_tone);
+    e.set_free_tone(options.free_tone);
+    e.set_english_auto_restore(options.english_auto_restore);
+    e.set_allow_foreign_consonants(options.allow_foreign_consonants);
+
+    let is_word_start = |c: char| c.is_ascii_alphabetic() || (method == 1 && c.is_ascii_digit());
+    let is_word_char = |c: char| is_word_start(c) || (method == 2 && VIQR_SYMBOLS.contains(&c));
+    let mut out = String::with_capacity(text.len() + text.len() / 4);
+    let mut rest = text;
+    while !rest.is_empty() {
+        let is_word = rest.starts_with(is_word_start);
+        let split = if is_word {
+            rest.find(|c: char| !is_word_char(c))
+        } else {
+            rest.find(is_word_start)
+        }
+        .unwrap_or(rest.len());
+        let (run, tail) = rest.split_at(split);
+        if is_word {
+            out.extend(convert_word(&mut e, run));
+        } else {
+            out.push_str(run);
+        }
+        rest = tail;
+    }
+    out
+}
+
+/// Type one word, then a space to finish it (auto-restore runs on space)
+pub(crate) fn convert_word(e: &mut Engine, word: &str) -> Vec<char> {
+    e.clear_all();
+    let mut screen = Vec::with_capacity(word.len());
+    for c in word.chars() {
+        let Some((key, caps, shift)) = utils::char_to_key_ext(c) else {
+            screen.push(c);
+            continue;
+        };
+        let r = e.on_key_ext(key, caps, false, shift);
+        if !apply(&mut screen, &r) {
+            screen.push(c);
+        }
+    }
+    let r = e.on_key_ext(keys::SPACE, false, false, false);
+    if apply(&mut screen, &r) && screen.last() == Some(&' ') {
+        screen.pop();
+    }
+    screen
+}
+
+/// Apply a Send result to the screen; false if the key passes through
+fn apply(screen: &mut Vec<char>, r: &Result) -> bool {
+    if r.action != Action::Send as u8 {
+        return false;
+    }
+    screen.truncate(screen.len().saturating_sub(r.backspace as usize));
+    screen.extend(
+        r.chars[..r.count as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c)),
+    );
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_non_word_runs() {
+        let opts = Options::default();
+        assert_eq!(
+            telex_to_unicode("  Tieengs,\tVieejt!\r\n", opts),
+            "  Tiếng,\tViệt!\r\n"
+        );
+        assert_eq!(telex_to_unicode("việt → 123", opts), "việt → 123");
+        assert_eq!(telex_to_unicode("", opts), "");
+    }
+
+    #[test]
+    fn viqr_symbols_are_word_chars() {
+        let opts = Options::default();
+        assert_eq!(
+            convert("Tie^'ng Vie^.t, (ddu*o*`ng) ba\\.", 2, opts),
+            "Tiếng Việt, (đường) ba."
+        );
+        // Symbols only continue a word
+        assert_eq!(convert("'quoted' ?", 2, opts), "'quoted' ?");
+    }
+
+    #[test]
+    fn vni_digits_are_word_chars() {
+        let opts = Options::default();
+        assert_eq!(
+            vni_to_unicode("Tie61ng Vie65t 2024", opts),
+            "Tiếng Việt 2024"
+        );
+    }
+}
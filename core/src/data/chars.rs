@@ -404,6 +404,156 @@ pub fn parse_char(c: char) -> Option<ParsedChar> {
     }
 }
 
+// ============================================================
+// DECOMPOSED (NFD) FORMS
+// ============================================================
+
+/// Combining marks used by decomposed Vietnamese
+pub mod combining {
+    pub const GRAVE: char = '\u{0300}'; // huyền
+    pub const ACUTE: char = '\u{0301}'; // sắc
+    pub const CIRCUMFLEX: char = '\u{0302}'; // â ê ô
+    pub const TILDE: char = '\u{0303}'; // ngã
+    pub const BREVE: char = '\u{0306}'; // ă
+    pub const HOOK: char = '\u{0309}'; // hỏi
+    pub const HORN: char = '\u{031B}'; // ơ ư
+    pub const DOT_BELOW: char = '\u{0323}'; // nặng
+
+    /// Check if char is one of the Vietnamese combining marks
+    pub fn is_combining(c: char) -> bool {
+        matches!(
+            c,
+            GRAVE | ACUTE | CIRCUMFLEX | TILDE | BREVE | HOOK | HORN | DOT_BELOW
+        )
+    }
+}
+
+/// Combining char for a mark (sắc..nặng)
+fn combining_mark(m: u8) -> Option<char> {
+    match m {
+        mark::SAC => Some(combining::ACUTE),
+        mark::HUYEN => Some(combining::GRAVE),
+        mark::HOI => Some(combining::HOOK),
+        mark::NGA => Some(combining::TILDE),
+        mark::NANG => Some(combining::DOT_BELOW),
+        _ => None,
+    }
+}
+
+/// Canonical decomposition (NFD) of a Vietnamese vowel
+///
+/// Returns the base letter followed by combining marks in canonical order
+/// (horn, dot below, circumflex/breve, other marks), or None for chars
+/// that don't decompose (ASCII, đ, non-Vietnamese).
+///
+/// Example: 'ệ' → ['e', U+0323, U+0302]
+pub fn decompose(c: char) -> Option<([char; 3], usize)> {
+    let p = parse_char(c)?;
+    if p.stroke || (p.tone == 0 && p.mark == 0) {
+        return None;
+    }
+    let base = to_char(p.key, p.caps, 0, 0)?;
+    let mut out = [base; 3];
+    let mut len = 1;
+    let mut push = |ch: char| {
+        out[len] = ch;
+        len += 1;
+    };
+    let tone_mark = match (p.tone, p.key) {
+        (tone::CIRCUMFLEX, _) => Some(combining::CIRCUMFLEX),
+        (tone::HORN, keys::A) => Some(combining::BREVE),
+        (tone::HORN, _) => {
+            push(combining::HORN);
+            None
+        }
+        _ => None,
+    };
+    if p.mark == mark::NANG {
+        push(combining::DOT_BELOW);
+    }
+    if let Some(t) = tone_mark {
+        push(t);
+    }
+    if p.mark != mark::NANG {
+        if let Some(m) = combining_mark(p.mark) {
+            push(m);
+        }
+    }
+    Some((out, len))
+}
+
+/// Apply one combining mark to a (possibly already marked) vowel
+///
+/// Returns None if the mark doesn't fit (e.g. horn on 'a', two tone marks).
+fn combine(base: char, c: char) -> Option<char> {
+    let p = parse_char(base)?;
+    if p.stroke {
+        return None;
+    }
+    let (t, m) = match c {
+        combining::CIRCUMFLEX if matches!(p.key, keys::A | keys::E | keys::O) => {
+            (tone::CIRCUMFLEX, p.mark)
+        }
+        combining::HORN if matches!(p.key, keys::O | keys::U) => (tone::HORN, p.mark),
+        combining::BREVE if p.key == keys::A => (tone::HORN, p.mark),
+        combining::ACUTE => (p.tone, mark::SAC),
+        combining::GRAVE => (p.tone, mark::HUYEN),
+        combining::HOOK => (p.tone, mark::HOI),
+        combining::TILDE => (p.tone, mark::NGA),
+        combining::DOT_BELOW => (p.tone, mark::NANG),
+        _ => return None,
+    };
+    let is_tone = matches!(
+        c,
+        combining::CIRCUMFLEX | combining::HORN | combining::BREVE
+    );
+    if (is_tone && p.tone != 0) || (!is_tone && p.mark != 0) || p.key == keys::D {
+        return None;
+    }
+    to_char(p.key, p.caps, t, m)
+}
+
+/// Parse one letter from the start of `s`, precomposed (NFC) or decomposed (NFD)
+///
+/// Combining marks following the base letter are folded into the result, in
+/// any order. Returns the parsed letter and the number of bytes consumed.
+///
+/// Example: "e\u{0323}\u{0302}t" → (ệ, 5)
+pub fn parse_grapheme(s: &str) -> Option<(ParsedChar, usize)> {
+    let mut chars = s.chars();
+    let mut c = chars.next()?;
+    let mut len = c.len_utf8();
+    for next in chars {
+        if !combining::is_combining(next) {
+            break;
+        }
+        c = combine(c, next)?;
+        len += next.len_utf8();
+    }
+    parse_char(c).map(|p| (p, len))
+}
+
+/// Compose decomposed Vietnamese letters into precomposed (NFC) form
+///
+/// Combining marks that don't fit the preceding letter are kept as-is.
+pub fn compose(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut last: Option<char> = None;
+    for c in s.chars() {
+        if combining::is_combining(c) {
+            if let Some(composed) = last.and_then(|l| combine(l, c)) {
+                out.pop();
+                out.push(composed);
+                last = Some(composed);
+                continue;
+            }
+        }
+        out.push(c);
+        last = Some(c);
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -552,4 +702,60 @@ mod tests {
             assert_eq!((p.key, p.tone, p.mark), (key, t, m), "Failed for '{}'", ch);
         }
     }
+
+    #[test]
+    fn test_decompose() {
+        let nfd = |c| {
+            let (chars, len) = decompose(c).unwrap();
+            chars[..len].iter().collect::<String>()
+        };
+        assert_eq!(nfd('ệ'), "e\u{0323}\u{0302}");
+        assert_eq!(nfd('ợ'), "o\u{031B}\u{0323}");
+        assert_eq!(nfd('Ấ'), "A\u{0302}\u{0301}");
+        assert_eq!(nfd('ằ'), "a\u{0306}\u{0300}");
+        assert_eq!(nfd('ư'), "u\u{031B}");
+        assert_eq!(decompose('đ'), None);
+        assert_eq!(decompose('a'), None);
+    }
+
+    #[test]
+    fn test_compose_round_trip() {
+        for key in [keys::A, keys::E, keys::I, keys::O, keys::U, keys::Y] {
+            for t in [0, tone::CIRCUMFLEX, tone::HORN] {
+                for m in 0..=mark::NANG {
+                    for caps in [false, true] {
+                        let c = to_char(key, caps, t, m).unwrap();
+                        let nfd: String = match decompose(c) {
+                            Some((chars, len)) => chars[..len].iter().collect(),
+                            None => c.to_string(),
+                        };
+                        assert_eq!(compose(&nfd), c.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_grapheme() {
+        // Non-canonical order is accepted too
+        for s in [
+            "ệt",
+            "e\u{0323}\u{0302}t",
+            "e\u{0302}\u{0323}t",
+            "ê\u{0323}t",
+        ] {
+            let (p, len) = parse_grapheme(s).unwrap();
+            assert_eq!(
+                (p.key, p.tone, p.mark),
+                (keys::E, tone::CIRCUMFLEX, mark::NANG)
+            );
+            assert_eq!(&s[len..], "t");
+        }
+        // Conflicting marks are not a letter
+        assert!(parse_grapheme("a\u{0301}\u{0300}").is_none());
+        assert!(parse_grapheme("â\u{0302}").is_none());
+        // Stray combining mark stays after compose
+        assert_eq!(compose("x\u{0301}"), "x\u{0301}");
+    }
 }
@@ -0,0 +1,625 @@
+//! Legacy Vietnamese Charsets
+//!
+//! Pre-Unicode encodings still used by older fonts and applications:
+//! - TCVN3 (ABC): one byte per letter, lowercase toned letters only
+//!   (uppercase toned letters need a separate "ABC caps" font)
+//! - VNI-Windows: base letter + accent byte (up to 2 bytes per letter)
+//! - VISCII (RFC 1456): one byte per letter, all 134 letters
+//!
+//! The engine emits legacy bytes as chars U+0000-U+00FF, which is what
+//! legacy fonts render when the platform sends them as Latin-1 text.
+//!
+//! Decomposed Unicode (NFD: base letter + combining marks) is handled as one
+//! more output charset, since it has the same "several chars per letter" issue.
+
+use super::chars::{self, get_d, mark, parse_char, to_char, tone};
+use super::keys;
+
+/// Output charset
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Charset {
+    #[default]
+    Unicode = 0,
+    Tcvn3 = 1,
+    VniWindows = 2,
+    Viscii = 3,
+    /// Unicode with decomposed letters (NFD), e.g. "ệ" = e + U+0323 + U+0302
+    UnicodeNfd = 4,
+}
+
+impl Charset {
+    /// Parse from FFI value (0=Unicode, 1=TCVN3, 2=VNI-Windows, 3=VISCII, 4=Unicode NFD)
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Unicode),
+            1 => Some(Self::Tcvn3),
+            2 => Some(Self::VniWindows),
+            3 => Some(Self::Viscii),
+            4 => Some(Self::UnicodeNfd),
+            _ => None,
+        }
+    }
+}
+
+/// Legacy byte sequence of one character (1-2 bytes)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Encoded {
+    bytes: [u8; 2],
+    len: u8,
+}
+
+impl Encoded {
+    fn one(b: u8) -> Self {
+        Self {
+            bytes: [b, 0],
+            len: 1,
+        }
+    }
+
+    fn two(b0: u8, b1: u8) -> Self {
+        Self {
+            bytes: [b0, b1],
+            len: 2,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// TCVN3 lowercase letters (uppercase toned letters share these bytes)
+const TCVN3: &[(char, u8)] = &[
+    ('ă', 0xA8),
+    ('â', 0xA9),
+    ('ê', 0xAA),
+    ('ô', 0xAB),
+    ('ơ', 0xAC),
+    ('ư', 0xAD),
+    ('đ', 0xAE),
+    ('à', 0xB5),
+    ('ả', 0xB6),
+    ('ã', 0xB7),
+    ('á', 0xB8),
+    ('ạ', 0xB9),
+    ('ằ', 0xBB),
+    ('ẳ', 0xBC),
+    ('ẵ', 0xBD),
+    ('ắ', 0xBE),
+    ('ặ', 0xC6),
+    ('ầ', 0xC7),
+    ('ẩ', 0xC8),
+    ('ẫ', 0xC9),
+    ('ấ', 0xCA),
+    ('ậ', 0xCB),
+    ('è', 0xCC),
+    ('ẻ', 0xCE),
+    ('ẽ', 0xCF),
+    ('é', 0xD0),
+    ('ẹ', 0xD1),
+    ('ề', 0xD2),
+    ('ể', 0xD3),
+    ('ễ', 0xD4),
+    ('ế', 0xD5),
+    ('ệ', 0xD6),
+    ('ì', 0xD7),
+    ('ỉ', 0xD8),
+    ('ĩ', 0xDC),
+    ('í', 0xDD),
+    ('ị', 0xDE),
+    ('ò', 0xDF),
+    ('ỏ', 0xE1),
+    ('õ', 0xE2),
+    ('ó', 0xE3),
+    ('ọ', 0xE4),
+    ('ồ', 0xE5),
+    ('ổ', 0xE6),
+    ('ỗ', 0xE7),
+    ('ố', 0xE8),
+    ('ộ', 0xE9),
+    ('ờ', 0xEA),
+    ('ở', 0xEB),
+    ('ỡ', 0xEC),
+    ('ớ', 0xED),
+    ('ợ', 0xEE),
+    ('ù', 0xEF),
+    ('ủ', 0xF1),
+    ('ũ', 0xF2),
+    ('ú', 0xF3),
+    ('ụ', 0xF4),
+    ('ừ', 0xF5),
+    ('ử', 0xF6),
+    ('ữ', 0xF7),
+    ('ứ', 0xF8),
+    ('ự', 0xF9),
+    ('ỳ', 0xFA),
+    ('ỷ', 0xFB),
+    ('ỹ', 0xFC),
+    ('ý', 0xFD),
+    ('ỵ', 0xFE),
+];
+
+/// TCVN3 uppercase letters that have their own byte
+const TCVN3_UPPER: &[(char, u8)] = &[
+    ('Ă', 0xA1),
+    ('Â', 0xA2),
+    ('Ê', 0xA3),
+    ('Ô', 0xA4),
+    ('Ơ', 0xA5),
+    ('Ư', 0xA6),
+    ('Đ', 0xA7),
+];
+
+/// VISCII (RFC 1456), all non-ASCII Vietnamese letters
+const VISCII: &[(char, u8)] = &[
+    ('Ẳ', 0x02),
+    ('Ẵ', 0x05),
+    ('Ẫ', 0x06),
+    ('Ỷ', 0x14),
+    ('Ỹ', 0x19),
+    ('Ỵ', 0x1E),
+    ('Ạ', 0x80),
+    ('Ắ', 0x81),
+    ('Ằ', 0x82),
+    ('Ặ', 0x83),
+    ('Ấ', 0x84),
+    ('Ầ', 0x85),
+    ('Ẩ', 0x86),
+    ('Ậ', 0x87),
+    ('Ẽ', 0x88),
+    ('Ẹ', 0x89),
+    ('Ế', 0x8A),
+    ('Ề', 0x8B),
+    ('Ể', 0x8C),
+    ('Ễ', 0x8D),
+    ('Ệ', 0x8E),
+    ('Ố', 0x8F),
+    ('Ồ', 0x90),
+    ('Ổ', 0x91),
+    ('Ỗ', 0x92),
+    ('Ộ', 0x93),
+    ('Ợ', 0x94),
+    ('Ớ', 0x95),
+    ('Ờ', 0x96),
+    ('Ở', 0x97),
+    ('Ị', 0x98),
+    ('Ỏ', 0x99),
+    ('Ọ', 0x9A),
+    ('Ỉ', 0x9B),
+    ('Ủ', 0x9C),
+    ('Ũ', 0x9D),
+    ('Ụ', 0x9E),
+    ('Ỳ', 0x9F),
+    ('Õ', 0xA0),
+    ('ắ', 0xA1),
+    ('ằ', 0xA2),
+    ('ặ', 0xA3),
+    ('ấ', 0xA4),
+    ('ầ', 0xA5),
+    ('ẩ', 0xA6),
+    ('ậ', 0xA7),
+    ('ẽ', 0xA8),
+    ('ẹ', 0xA9),
+    ('ế', 0xAA),
+    ('ề', 0xAB),
+    ('ể', 0xAC),
+    ('ễ', 0xAD),
+    ('ệ', 0xAE),
+    ('ố', 0xAF),
+    ('ồ', 0xB0),
+    ('ổ', 0xB1),
+    ('ỗ', 0xB2),
+    ('Ỡ', 0xB3),
+    ('Ơ', 0xB4),
+    ('ộ', 0xB5),
+    ('ờ', 0xB6),
+    ('ở', 0xB7),
+    ('ị', 0xB8),
+    ('Ự', 0xB9),
+    ('Ứ', 0xBA),
+    ('Ừ', 0xBB),
+    ('Ử', 0xBC),
+    ('ơ', 0xBD),
+    ('ớ', 0xBE),
+    ('Ư', 0xBF),
+    ('À', 0xC0),
+    ('Á', 0xC1),
+    ('Â', 0xC2),
+    ('Ã', 0xC3),
+    ('Ả', 0xC4),
+    ('Ă', 0xC5),
+    ('ẳ', 0xC6),
+    ('ẵ', 0xC7),
+    ('È', 0xC8),
+    ('É', 0xC9),
+    ('Ê', 0xCA),
+    ('Ẻ', 0xCB),
+    ('Ì', 0xCC),
+    ('Í', 0xCD),
+    ('Ĩ', 0xCE),
+    ('ỳ', 0xCF),
+    ('Đ', 0xD0),
+    ('ứ', 0xD1),
+    ('Ò', 0xD2),
+    ('Ó', 0xD3),
+    ('Ô', 0xD4),
+    ('ạ', 0xD5),
+    ('ỷ', 0xD6),
+    ('ừ', 0xD7),
+    ('ử', 0xD8),
+    ('Ù', 0xD9),
+    ('Ú', 0xDA),
+    ('ỹ', 0xDB),
+    ('ỵ', 0xDC),
+    ('Ý', 0xDD),
+    ('ỡ', 0xDE),
+    ('ư', 0xDF),
+    ('à', 0xE0),
+    ('á', 0xE1),
+    ('â', 0xE2),
+    ('ã', 0xE3),
+    ('ả', 0xE4),
+    ('ă', 0xE5),
+    ('ữ', 0xE6),
+    ('ẫ', 0xE7),
+    ('è', 0xE8),
+    ('é', 0xE9),
+    ('ê', 0xEA),
+    ('ẻ', 0xEB),
+    ('ì', 0xEC),
+    ('í', 0xED),
+    ('ĩ', 0xEE),
+    ('ỉ', 0xEF),
+    ('đ', 0xF0),
+    ('ự', 0xF1),
+    ('ò', 0xF2),
+    ('ó', 0xF3),
+    ('ô', 0xF4),
+    ('õ', 0xF5),
+    ('ỏ', 0xF6),
+    ('ọ', 0xF7),
+    ('ụ', 0xF8),
+    ('ù', 0xF9),
+    ('ú', 0xFA),
+    ('ũ', 0xFB),
+    ('ủ', 0xFC),
+    ('ý', 0xFD),
+    ('ợ', 0xFE),
+    ('Ữ', 0xFF),
+];
+
+/// VNI-Windows accent bytes on a plain vowel or ơ/ư, indexed by mark (sắc..nặng)
+const VNI_MARK: [u8; 5] = [0xF9, 0xF8, 0xFB, 0xF5, 0xEF];
+/// VNI-Windows circumflex bytes, indexed by mark (none, sắc..nặng)
+const VNI_CIRCUMFLEX: [u8; 6] = [0xE2, 0xE1, 0xE0, 0xE5, 0xE3, 0xE4];
+/// VNI-Windows breve bytes, indexed by mark (none, sắc..nặng)
+const VNI_BREVE: [u8; 6] = [0xEA, 0xE9, 0xE8, 0xFA, 0xFC, 0xEB];
+/// VNI-Windows single-byte i with mark (sắc..nặng)
+const VNI_I: [u8; 5] = [0xED, 0xEC, 0xE6, 0xF3, 0xF2];
+/// VNI-Windows single-byte ỵ
+const VNI_Y_DOT: u8 = 0xEE;
+
+/// Uppercase form of a VNI-Windows accent/special byte
+fn vni_upper(b: u8, caps: bool) -> u8 {
+    if caps {
+        b - 0x20
+    } else {
+        b
+    }
+}
+
+fn lookup(table: &[(char, u8)], c: char) -> Option<u8> {
+    table.iter().find(|&&(ch, _)| ch == c).map(|&(_, b)| b)
+}
+
+fn encode_vni(c: char) -> Option<Encoded> {
+    let p = parse_char(c)?;
+    let letter = keys_to_letter(p.key)?;
+    let base = if p.caps {
+        letter.to_ascii_uppercase()
+    } else {
+        letter
+    };
+
+    if p.stroke {
+        return Some(Encoded::one(vni_upper(0xF1, p.caps)));
+    }
+    match (p.key, p.tone, p.mark) {
+        (keys::I, _, m) if m > 0 => Some(Encoded::one(vni_upper(VNI_I[m as usize - 1], p.caps))),
+        (keys::Y, _, mark::NANG) => Some(Encoded::one(vni_upper(VNI_Y_DOT, p.caps))),
+        (_, tone::CIRCUMFLEX, m) => Some(Encoded::two(
+            base,
+            vni_upper(VNI_CIRCUMFLEX[m as usize], p.caps),
+        )),
+        (keys::A, tone::HORN, m) => {
+            Some(Encoded::two(base, vni_upper(VNI_BREVE[m as usize], p.caps)))
+        }
+        (key, tone::HORN, m) => {
+            let horn = vni_upper(if key == keys::O { 0xF4 } else { 0xF6 }, p.caps);
+            if m == 0 {
+                Some(Encoded::one(horn))
+            } else {
+                Some(Encoded::two(
+                    horn,
+                    vni_upper(VNI_MARK[m as usize - 1], p.caps),
+                ))
+            }
+        }
+        (_, _, 0) => Some(Encoded::one(base)),
+        (_, _, m) => Some(Encoded::two(
+            base,
+            vni_upper(VNI_MARK[m as usize - 1], p.caps),
+        )),
+    }
+}
+
+/// Lowercase ASCII letter for a vowel/d keycode
+fn keys_to_letter(key: u16) -> Option<u8> {
+    Some(match key {
+        keys::A => b'a',
+        keys::E => b'e',
+        keys::I => b'i',
+        keys::O => b'o',
+        keys::U => b'u',
+        keys::Y => b'y',
+        keys::D => b'd',
+        _ => return None,
+    })
+}
+
+/// Encode one character in a legacy charset.
+///
+/// ASCII maps to itself. Returns None for the Unicode charsets and for
+/// characters the charset cannot represent. TCVN3 encodes uppercase toned
+/// letters with their lowercase byte (the ABC caps font renders them upper).
+pub fn encode_char(c: char, charset: Charset) -> Option<Encoded> {
+    if matches!(charset, Charset::Unicode | Charset::UnicodeNfd) {
+        return None;
+    }
+    if c.is_ascii() {
+        return Some(Encoded::one(c as u8));
+    }
+    match charset {
+        Charset::Unicode | Charset::UnicodeNfd => None,
+        Charset::Tcvn3 => lookup(TCVN3_UPPER, c)
+            .or_else(|| lookup(TCVN3, c))
+            .or_else(|| c.to_lowercase().next().and_then(|l| lookup(TCVN3, l)))
+            .map(Encoded::one),
+        Charset::Viscii => lookup(VISCII, c).map(Encoded::one),
+        Charset::VniWindows => encode_vni(c),
+    }
+}
+
+/// Chars `c` occupies on screen in `charset` output
+///
+/// Legacy bytes become chars U+00XX; NFD letters become base + combining
+/// marks. Returns None when `c` is output unchanged (Unicode, letters
+/// without decomposition, chars a legacy charset cannot represent).
+pub fn screen_form(c: char, charset: Charset) -> Option<([char; 3], usize)> {
+    if charset == Charset::UnicodeNfd {
+        return chars::decompose(c);
+    }
+    let e = encode_char(c, charset)?;
+    let mut out = ['\0'; 3];
+    for (slot, &b) in out.iter_mut().zip(e.as_bytes()) {
+        *slot = b as char;
+    }
+    Some((out, e.len()))
+}
+
+/// Encode a string in a charset.
+///
+/// Unicode returns UTF-8 (decomposed for NFD). Characters a legacy charset
+/// cannot represent become '?'.
+pub fn encode(text: &str, charset: Charset) -> Vec<u8> {
+    match charset {
+        Charset::Unicode => return text.as_bytes().to_vec(),
+        Charset::UnicodeNfd => {
+            let mut out = String::with_capacity(text.len() * 2);
+            for c in text.chars() {
+                match chars::decompose(c) {
+                    Some((parts, len)) => out.extend(&parts[..len]),
+                    None => out.push(c),
+                }
+            }
+            return out.into_bytes();
+        }
+        _ => {}
+    }
+    let mut out = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match encode_char(c, charset) {
+            Some(e) => out.extend_from_slice(e.as_bytes()),
+            None => out.push(b'?'),
+        }
+    }
+    out
+}
+
+/// All non-ASCII Vietnamese letters (134: 67 lowercase + 67 uppercase)
+pub fn vietnamese_letters() -> impl Iterator<Item = char> {
+    const VOWELS: [u16; 6] = [keys::A, keys::E, keys::I, keys::O, keys::U, keys::Y];
+    let mut letters = Vec::with_capacity(134);
+    for caps in [false, true] {
+        for &key in &VOWELS {
+            for t in [0, tone::CIRCUMFLEX, tone::HORN] {
+                for m in 0..=mark::NANG {
+                    if let Some(c) = to_char(key, caps, t, m) {
+                        if !c.is_ascii() && !letters.contains(&c) {
+                            letters.push(c);
+                        }
+                    }
+                }
+            }
+        }
+        letters.push(get_d(caps));
+    }
+    letters.into_iter()
+}
+
+/// Decode legacy bytes (or UTF-8 for the Unicode charsets) into a
+/// precomposed (NFC) string.
+///
+/// Bytes without a Vietnamese meaning decode as Latin-1. VNI-Windows
+/// sequences are matched longest-first, so "aù" decodes to "á".
+pub fn decode(bytes: &[u8], charset: Charset) -> String {
+    match charset {
+        Charset::Unicode => return String::from_utf8_lossy(bytes).into_owned(),
+        Charset::UnicodeNfd => return chars::compose(&String::from_utf8_lossy(bytes)),
+        _ => {}
+    }
+    let mut out = String::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let decoded = match charset {
+            Charset::Tcvn3 => TCVN3_UPPER
+                .iter()
+                .chain(TCVN3)
+                .find(|&&(_, byte)| byte == b)
+                .map(|&(c, _)| (c, 1)),
+            Charset::Viscii => VISCII
+                .iter()
+                .find(|&&(_, byte)| byte == b)
+                .map(|&(c, _)| (c, 1)),
+            Charset::VniWindows => decode_vni(&bytes[i..]),
+            Charset::Unicode | Charset::UnicodeNfd => None,
+        };
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                i += len;
+            }
+            None => {
+                out.push(b as char);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn decode_vni(bytes: &[u8]) -> Option<(char, usize)> {
+    let find = |seq: &[u8]| {
+        vietnamese_letters().find(|&c| encode_vni(c).is_some_and(|e| e.as_bytes() == seq))
+    };
+    if bytes.len() >= 2 {
+        if let Some(c) = find(&bytes[..2]) {
+            return Some((c, 2));
+        }
+    }
+    if bytes[0].is_ascii() {
+        return None;
+    }
+    find(&bytes[..1]).map(|c| (c, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(text: &str, charset: Charset) -> Vec<u8> {
+        encode(text, charset)
+    }
+
+    #[test]
+    fn letter_count() {
+        let letters: Vec<char> = vietnamese_letters().collect();
+        assert_eq!(letters.len(), 134);
+    }
+
+    #[test]
+    fn viscii_covers_all_letters() {
+        assert_eq!(VISCII.len(), 134);
+        for c in vietnamese_letters() {
+            let e = encode_char(c, Charset::Viscii).expect("VISCII letter");
+            assert_eq!(decode(e.as_bytes(), Charset::Viscii), c.to_string());
+        }
+    }
+
+    #[test]
+    fn vni_round_trip() {
+        for c in vietnamese_letters() {
+            let e = encode_char(c, Charset::VniWindows).expect("VNI letter");
+            assert_eq!(decode(e.as_bytes(), Charset::VniWindows), c.to_string());
+        }
+    }
+
+    #[test]
+    fn tcvn3_round_trip_lowercase() {
+        for c in vietnamese_letters().filter(|c| c.is_lowercase()) {
+            let e = encode_char(c, Charset::Tcvn3).expect("TCVN3 letter");
+            assert_eq!(decode(e.as_bytes(), Charset::Tcvn3), c.to_string());
+        }
+        // Uppercase toned letters share the lowercase byte
+        assert_eq!(bytes("Á", Charset::Tcvn3), vec![0xB8]);
+        assert_eq!(bytes("Đ", Charset::Tcvn3), vec![0xA7]);
+    }
+
+    #[test]
+    fn known_sequences() {
+        assert_eq!(bytes("Việt", Charset::Tcvn3), vec![b'V', b'i', 0xD6, b't']);
+        assert_eq!(bytes("Việt", Charset::Viscii), vec![b'V', b'i', 0xAE, b't']);
+        assert_eq!(
+            bytes("Việt", Charset::VniWindows),
+            vec![b'V', b'i', b'e', 0xE4, b't']
+        );
+        assert_eq!(
+            bytes("ĐƯỜNG", Charset::VniWindows),
+            vec![0xD1, 0xD6, 0xD4, 0xD8, b'N', b'G']
+        );
+        assert_eq!(bytes("ăn", Charset::VniWindows), vec![b'a', 0xEA, b'n']);
+        assert_eq!(bytes("ị", Charset::VniWindows), vec![0xF2]);
+        assert_eq!(bytes("Ơ", Charset::VniWindows), vec![0xD4]);
+    }
+
+    #[test]
+    fn unencodable_becomes_question_mark() {
+        assert_eq!(bytes("é€", Charset::Viscii), vec![0xE9, b'?']);
+        assert_eq!(encode_char('€', Charset::VniWindows), None);
+        assert_eq!(encode_char('a', Charset::Unicode), None);
+    }
+
+    #[test]
+    fn unknown_bytes_decode_as_latin1() {
+        assert_eq!(decode(&[b'a', 0x80], Charset::Tcvn3), "a\u{80}");
+    }
+
+    #[test]
+    fn decode_strings() {
+        let text = "Tiếng Việt có dấu, đường phố!";
+        for charset in [Charset::VniWindows, Charset::Viscii, Charset::Tcvn3] {
+            assert_eq!(
+                decode(&encode(text, charset), charset),
+                text,
+                "{:?}",
+                charset
+            );
+        }
+    }
+
+    #[test]
+    fn nfd_round_trip() {
+        let text = "Tiếng Việt, ĐƯỜNG";
+        let nfd = encode(text, Charset::UnicodeNfd);
+        assert_eq!(
+            String::from_utf8(nfd.clone()).unwrap().chars().count(),
+            text.chars().count() + 7
+        );
+        assert_eq!(decode(&nfd, Charset::UnicodeNfd), text);
+        assert_eq!(screen_form('đ', Charset::UnicodeNfd), None);
+        assert_eq!(
+            screen_form('ệ', Charset::UnicodeNfd).map(|(_, n)| n),
+            Some(3)
+        );
+    }
+}
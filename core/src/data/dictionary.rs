@@ -1,26 +1,17 @@
 //! Vietnamese Spell Checking Module
 //!
-//! Uses HashSet-based word lookup for efficient Vietnamese word validation.
-//! Memory-efficient: ~0.5MB vs ~5.5MB with full Hunspell implementation.
+//! Looks words up in `vi.dic`, compiled into a trie at build time (`trie`):
+//! no heap, no startup parsing, and no allocation per lookup.
+//! Words added or removed at runtime (`user_dict`) override the built-in lists.
 
-use std::collections::HashSet;
-use std::sync::LazyLock;
+use super::trie::{include_trie, Trie};
+use super::user_dict::{self, WordList};
 
-// Embed dictionary files into binary
-const DIC_VI: &str = include_str!("dictionaries/vi.dic");
-const DIC_KEEP: &str = include_str!("dictionaries/keep.dic");
+/// Vietnamese dictionary (`dictionaries/vi.dic`)
+pub static VI: Trie = include_trie!("vi.trie");
 
-/// Parse .dic file into HashSet (skip first line which is word count)
-fn parse_dic_to_hashset(dic_content: &'static str) -> HashSet<&'static str> {
-    dic_content.lines().skip(1).collect()
-}
-
-/// Lazy-loaded Vietnamese dictionary - ~0.5MB memory
-static DICT_VI: LazyLock<HashSet<&'static str>> = LazyLock::new(|| parse_dic_to_hashset(DIC_VI));
-
-/// Lazy-loaded keep list - words that should not be auto-restored
-static DICT_KEEP: LazyLock<HashSet<&'static str>> =
-    LazyLock::new(|| parse_dic_to_hashset(DIC_KEEP));
+/// Keep list (`dictionaries/keep.dic`) - words that should not be auto-restored
+pub static KEEP: Trie = include_trie!("keep.trie");
 
 /// Check if word starts with foreign consonant (z, w, j, f)
 fn starts_with_foreign_consonant(word: &str) -> bool {
@@ -45,8 +36,32 @@ pub fn is_vietnamese(word: &str, allow_foreign: bool) -> bool {
     }
 
     // Case-insensitive lookup (dictionary stores lowercase)
-    let word_lower = word.to_lowercase();
-    DICT_VI.contains(word_lower.as_str())
+    user_dict::lookup(WordList::Vietnamese, word.chars())
+        .unwrap_or_else(|| VI.contains_lowercase(word.chars()))
+}
+
+/// Check if a word is in the Vietnamese dictionary, matching its case
+///
+/// Unlike `is_vietnamese`, proper nouns and acronyms stored capitalized
+/// ("Huế", "HTML") are found too. Tries the word as-is, lowercased, then
+/// capitalized. User words match in any case.
+pub fn contains(word: &str) -> bool {
+    if word.is_empty() {
+        return false;
+    }
+    if let Some(found) = user_dict::lookup(WordList::Vietnamese, word.chars()) {
+        return found;
+    }
+    if VI.contains(word) || VI.contains_lowercase(word.chars()) {
+        return true;
+    }
+    let mut chars = word.chars();
+    let capitalized = chars
+        .next()
+        .into_iter()
+        .flat_map(char::to_uppercase)
+        .chain(chars.flat_map(char::to_lowercase));
+    VI.contains_chars(capitalized)
 }
 
 /// Check if a word is in the keep list (should not be auto-restored)
@@ -54,8 +69,8 @@ pub fn should_keep(word: &str) -> bool {
     if word.is_empty() {
         return false;
     }
-    let word_lower = word.to_lowercase();
-    DICT_KEEP.contains(word_lower.as_str())
+    user_dict::lookup(WordList::Keep, word.chars())
+        .unwrap_or_else(|| KEEP.contains_lowercase(word.chars()))
 }
 
 #[cfg(test)]
@@ -82,6 +97,15 @@ mod tests {
         assert!(!is_vietnamese("hêllô", false));
     }
 
+    #[test]
+    fn test_contains_matches_case() {
+        assert!(contains("Huế"));
+        assert!(contains("HUẾ"));
+        assert!(contains("huế"));
+        assert!(contains("việt"));
+        assert!(!contains("hello"));
+    }
+
     #[test]
     fn test_empty_word() {
         assert!(!is_vietnamese("", false));
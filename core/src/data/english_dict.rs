@@ -1,26 +1,36 @@
 //! English dictionary for auto-restore detection
 //!
-//! Uses merged dictionary: 10k common words + words with double telex chars.
+//! Uses merged dictionary: 10k common words + words with double telex chars,
+//! compiled into a trie at build time (`trie`) for allocation-free lookup.
 //! Only restores to English when raw_input is a known English word.
+//! User words and the never-restore list (`user_dict`) are checked first.
 
-use std::collections::HashSet;
-use std::sync::LazyLock;
+use super::trie::{include_trie, Trie};
+use super::user_dict::{self, WordList};
 
-/// Embedded English word list (10k + double telex patterns)
-const ENGLISH_WORDS: &str = include_str!("english_dict_merged.txt");
-
-/// HashSet for O(1) lookup
-static DICT: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
-    ENGLISH_WORDS
-        .lines()
-        .filter(|line| !line.is_empty())
-        .collect()
-});
+/// English word list (`english_dict_merged.txt`: 10k + double telex patterns)
+pub static WORDS: Trie = include_trie!("english.trie");
 
 /// Check if a word is in the English dictionary (case-insensitive)
 pub fn is_english_word(word: &str) -> bool {
-    let lower = word.to_lowercase();
-    DICT.contains(lower.as_str())
+    is_english_chars(word.chars())
+}
+
+/// Check if chars spell an English word (case-insensitive), without
+/// building a String
+pub fn is_english_chars(chars: impl IntoIterator<Item = char> + Clone) -> bool {
+    user_dict::lookup(WordList::English, chars.clone())
+        .unwrap_or_else(|| WORDS.contains_lowercase(chars))
+}
+
+/// Check if a word was added to the user's English list (case-insensitive)
+pub fn is_user_word(word: &str) -> bool {
+    user_dict::lookup(WordList::English, word.chars()) == Some(true)
+}
+
+/// Check if raw input is in the user's never-restore list (case-insensitive)
+pub fn is_never_restore(word: &str) -> bool {
+    user_dict::lookup(WordList::NeverRestore, word.chars()) == Some(true)
 }
 
 #[cfg(test)]
@@ -52,6 +62,6 @@ mod tests {
 
     #[test]
     fn test_dict_size() {
-        assert!(DICT.len() >= 17000); // Should have ~18k words (10k + double telex)
+        assert!(WORDS.len() >= 17000); // Should have ~18k words (10k + double telex)
     }
 }
@@ -3,15 +3,21 @@
 //! This module contains all linguistic data for Vietnamese input:
 //! - `keys`: Virtual keycode definitions (platform-specific)
 //! - `chars`: Unicode character conversion (includes tone/mark constants)
+//! - `charset`: Output charsets (TCVN3, VNI-Windows, VISCII, Unicode NFD)
 //! - `vowel`: Vietnamese vowel phonology system
 //! - `telex_doubles`: English words with Telex double patterns for auto-restore
+//! - `trie`: Compact word lists compiled by `build.rs`
+//! - `user_dict`: Words added to or removed from the dictionaries at runtime
 
 pub mod chars;
+pub mod charset;
 pub mod constants;
 pub mod dictionary;
 pub mod english_dict;
 pub mod keys;
 pub mod telex_doubles;
+pub mod trie;
+pub mod user_dict;
 pub mod vowel;
 
 pub use chars::{get_d, mark, to_char, tone};
@@ -0,0 +1,152 @@
+//! Compact word lists: minimized tries built by `build.rs`
+//!
+//! Word lists are compiled into a DAWG (trie with shared suffixes) over
+//! UTF-8 bytes and embedded with `include_bytes!`, so lookups need no heap,
+//! no startup parsing and no allocation. Lowercase lookups lowercase one char
+//! at a time while walking the trie instead of building a lowercase String.
+//!
+//! The trade-off is lookup speed: walking the trie is slower than hashing
+//! into the old `HashSet<&str>` (about 125 vs 80 ns per vi.dic word and 130
+//! vs 70 ns per English word in `benches/dictionary.rs`), in exchange for
+//! ~1.4MB less heap and no startup build. Sorted-array lists got faster.
+//!
+//! Layout: word count (u32 LE), root offset (u32 LE), then nodes
+//! `[final: u8][edge count: u8][labels, sorted][targets: u24 LE]`.
+
+/// Header fields: word count, then root node offset
+const WORD_COUNT_AT: usize = 0;
+const ROOT_AT: usize = 4;
+
+/// Nodes with at most this many edges are scanned instead of bisected
+const LINEAR_SCAN_MAX: usize = 8;
+
+/// Word list compiled by `build.rs`
+pub struct Trie {
+    bytes: &'static [u8],
+}
+
+impl Trie {
+    pub const fn new(bytes: &'static [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Check if `word` is in the list, matching case exactly
+    pub fn contains(&self, word: &str) -> bool {
+        self.contains_chars(word.chars())
+    }
+
+    /// Check if the lowercased chars spell a word in the list
+    pub fn contains_lowercase(&self, chars: impl IntoIterator<Item = char>) -> bool {
+        self.walk(chars, true).is_some()
+    }
+
+    /// Check if the chars spell a word in the list, matching case exactly
+    pub fn contains_chars(&self, chars: impl IntoIterator<Item = char>) -> bool {
+        self.walk(chars, false).is_some()
+    }
+
+    fn walk(&self, chars: impl IntoIterator<Item = char>, lowercase: bool) -> Option<()> {
+        let mut node = self.read_u32(ROOT_AT) as usize;
+        let mut utf8 = [0u8; 4];
+        for c in chars {
+            // ASCII (nearly every lookup): one byte, no case tables
+            if c.is_ascii() {
+                let b = if lowercase { c.to_ascii_lowercase() } else { c } as u8;
+                node = self.child(node, b)?;
+                continue;
+            }
+            let mut step = |c: char| -> Option<()> {
+                for &b in c.encode_utf8(&mut utf8).as_bytes() {
+                    node = self.child(node, b)?;
+                }
+                Some(())
+            };
+            if lowercase {
+                c.to_lowercase().try_for_each(&mut step)?;
+            } else {
+                step(c)?;
+            }
+        }
+        (self.bytes[node] != 0).then_some(())
+    }
+
+    /// Number of words
+    pub fn len(&self) -> usize {
+        self.read_u32(WORD_COUNT_AT) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Size of the compiled list in bytes (all of its memory)
+    pub fn size_bytes(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Offset of the node reached from `node` by `label`
+    fn child(&self, node: usize, label: u8) -> Option<usize> {
+        let count = self.bytes[node + 1] as usize;
+        let labels = &self.bytes[node + 2..node + 2 + count];
+        // Labels are sorted: scan the few edges of most nodes, bisect wide
+        // ones (the root and other early nodes)
+        let i = if count <= LINEAR_SCAN_MAX {
+            labels.iter().position(|&l| l == label)?
+        } else {
+            labels.binary_search(&label).ok()?
+        };
+        let t = node + 2 + count + i * 3;
+        Some(
+            self.bytes[t] as usize
+                | (self.bytes[t + 1] as usize) << 8
+                | (self.bytes[t + 2] as usize) << 16,
+        )
+    }
+
+    fn read_u32(&self, at: usize) -> u32 {
+        let b = &self.bytes[at..at + 4];
+        u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+    }
+}
+
+/// Embed a trie compiled by `build.rs` from `OUT_DIR`
+macro_rules! include_trie {
+    ($name:literal) => {
+        $crate::data::trie::Trie::new(include_bytes!(concat!(env!("OUT_DIR"), "/", $name)))
+    };
+}
+pub(crate) use include_trie;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static VI: Trie = include_trie!("vi.trie");
+
+    #[test]
+    fn every_word_found() {
+        let text = include_str!("dictionaries/vi.dic");
+        let words: Vec<&str> = text.lines().skip(1).filter(|w| !w.is_empty()).collect();
+        assert_eq!(VI.len(), words.len());
+        for word in words {
+            assert!(VI.contains(word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn prefixes_and_extensions_not_found() {
+        assert!(VI.contains("nghiêng"));
+        assert!(!VI.contains("nghiê"));
+        assert!(!VI.contains("nghiêngg"));
+        assert!(!VI.contains(""));
+    }
+
+    #[test]
+    fn lowercase_lookup() {
+        assert!(!VI.contains("VIỆT"));
+        assert!(VI.contains_lowercase("VIỆT".chars()));
+        // Stored capitalized: only found as-is
+        assert!(VI.contains("Huế"));
+        assert!(!VI.contains_lowercase("Huế".chars()));
+    }
+}
@@ -0,0 +1,265 @@
+//! User Dictionaries - Runtime additions to the built-in word lists
+//!
+//! The built-in lists are tries compiled at build time. User entries are kept
+//! beside them as owned, lowercased strings: `added` words extend a list and
+//! `removed` words hide built-in ones. `dictionary` and `english_dict` consult them before
+//! their own sets, so every engine instance sees the same merged lists.
+//!
+//! Lists also hold prefix and suffix rules ("re-", "-ing"): a word counts as
+//! added if stripping a rule's affix leaves an added word, so listing
+//! "serialize" also covers "deserialize" and "serializing". The English lists
+//! start with common English affixes; the Vietnamese lists with none.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, RwLock};
+
+/// Word list a user entry belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordList {
+    /// Vietnamese words (`vi.dic`), used by `dictionary::is_vietnamese`
+    Vietnamese = 0,
+    /// Vietnamese results never auto-restored to English (`keep.dic`)
+    Keep = 1,
+    /// English words (`english_dict_merged.txt`), used by auto-restore
+    English = 2,
+    /// Raw keystrokes never auto-restored to English, even if in `English`
+    NeverRestore = 3,
+}
+
+impl WordList {
+    const ALL: [WordList; 4] = [
+        WordList::Vietnamese,
+        WordList::Keep,
+        WordList::English,
+        WordList::NeverRestore,
+    ];
+
+    /// List from its FFI value (0 = Vietnamese, 1 = Keep, 2 = English,
+    /// 3 = NeverRestore)
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::ALL.get(value as usize).copied()
+    }
+}
+
+/// Default prefix rules of the English lists ("rewrite", "unset", "deserialize")
+const ENGLISH_PREFIXES: &[&str] = &["re", "un", "de", "pre"];
+
+/// Default suffix rules of the English lists ("crates", "linted", "linting")
+const ENGLISH_SUFFIXES: &[&str] = &["s", "es", "ed", "ing", "er", "ers"];
+
+/// Shortest stem a rule may leave ("re" + "do" is fine, "s" + "" is not)
+const MIN_STEM: usize = 2;
+
+/// Longest word (UTF-8 bytes) a list holds; lookups lowercase into a stack
+/// buffer of this size instead of allocating on every keystroke
+const MAX_WORD_BYTES: usize = 64;
+
+/// User changes to one list
+struct Entries {
+    added: HashSet<String>,
+    removed: HashSet<String>,
+    prefixes: Vec<String>,
+    suffixes: Vec<String>,
+}
+
+impl Entries {
+    fn new(list: WordList) -> Self {
+        let (prefixes, suffixes) = match list {
+            WordList::English | WordList::NeverRestore => (ENGLISH_PREFIXES, ENGLISH_SUFFIXES),
+            WordList::Vietnamese | WordList::Keep => (&[][..], &[][..]),
+        };
+        Self {
+            added: HashSet::new(),
+            removed: HashSet::new(),
+            prefixes: prefixes.iter().map(|p| p.to_string()).collect(),
+            suffixes: suffixes.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Check if `word` is an added word plus a prefix and/or suffix
+    fn is_inflected(&self, word: &str) -> bool {
+        self.has_suffix_of_added(word)
+            || self.prefixes.iter().any(|p| {
+                word.strip_prefix(p.as_str()).is_some_and(|rest| {
+                    rest.len() >= MIN_STEM
+                        && (self.added.contains(rest) || self.has_suffix_of_added(rest))
+                })
+            })
+    }
+
+    /// Check if `word` is an added word plus a suffix, allowing the usual
+    /// spelling changes: dropped e ("serializing") and doubled final
+    /// consonant ("committed")
+    fn has_suffix_of_added(&self, word: &str) -> bool {
+        self.suffixes.iter().any(|s| {
+            let Some(stem) = word.strip_suffix(s.as_str()) else {
+                return false;
+            };
+            if stem.len() < MIN_STEM {
+                return false;
+            }
+            let undoubled = match stem.as_bytes() {
+                [.., a, b] if a == b => Some(&stem[..stem.len() - 1]),
+                _ => None,
+            };
+            let mut buf = [0u8; MAX_WORD_BYTES + 1];
+            let with_e = write_chars(stem.chars().chain(['e']), &mut buf);
+            self.added.contains(stem)
+                || with_e.is_some_and(|w| self.added.contains(w))
+                || undoubled.is_some_and(|u| self.added.contains(u))
+        })
+    }
+}
+
+/// Affix rule written as "re-" (prefix) or "-ing" (suffix)
+enum Rule<'a> {
+    Prefix(&'a str),
+    Suffix(&'a str),
+}
+
+impl<'a> Rule<'a> {
+    fn parse(entry: &'a str) -> Option<Self> {
+        if let Some(suffix) = entry.strip_prefix('-').filter(|s| !s.is_empty()) {
+            Some(Self::Suffix(suffix))
+        } else {
+            entry
+                .strip_suffix('-')
+                .filter(|p| !p.is_empty())
+                .map(Self::Prefix)
+        }
+    }
+}
+
+static LISTS: LazyLock<[RwLock<Entries>; 4]> =
+    LazyLock::new(|| WordList::ALL.map(|list| RwLock::new(Entries::new(list))));
+
+/// Lists with added or removed words; lookups skip the others without
+/// locking or allocating
+static ACTIVE: [AtomicBool; 4] = [const { AtomicBool::new(false) }; 4];
+
+fn entries(list: WordList) -> &'static RwLock<Entries> {
+    &LISTS[list as usize]
+}
+
+/// Record whether a list has words, after changing it
+fn update_active(list: WordList, e: &Entries) {
+    let active = !e.added.is_empty() || !e.removed.is_empty();
+    ACTIVE[list as usize].store(active, Ordering::Release);
+}
+
+/// Write `chars` into `buf` as UTF-8, None if they don't fit
+fn write_chars(chars: impl IntoIterator<Item = char>, buf: &mut [u8]) -> Option<&str> {
+    let mut len = 0;
+    for c in chars {
+        let end = len + c.len_utf8();
+        c.encode_utf8(buf.get_mut(len..end)?);
+        len = end;
+    }
+    std::str::from_utf8(&buf[..len]).ok()
+}
+
+/// User override for a word (case-insensitive): Some(true) if added (or an
+/// added word with an affix), Some(false) if removed, None to fall back to
+/// the built-in list
+pub(crate) fn lookup(list: WordList, chars: impl IntoIterator<Item = char>) -> Option<bool> {
+    if !ACTIVE[list as usize].load(Ordering::Acquire) {
+        return None;
+    }
+    let mut buf = [0u8; MAX_WORD_BYTES];
+    // Longer words can't have been added or removed
+    let word_lower = write_chars(chars.into_iter().flat_map(char::to_lowercase), &mut buf)?;
+    let e = entries(list).read().unwrap_or_else(|e| e.into_inner());
+    if e.removed.contains(word_lower) {
+        Some(false)
+    } else if e.added.contains(word_lower) || e.is_inflected(word_lower) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Add a word to a list (case-insensitive)
+///
+/// "re-" and "-ing" add a prefix or suffix rule instead. Returns false for an
+/// empty or too long word, or one already added.
+pub fn add(list: WordList, word: &str) -> bool {
+    let word = word.trim().to_lowercase();
+    if word.is_empty() || word.len() > MAX_WORD_BYTES {
+        return false;
+    }
+    let mut e = entries(list).write().unwrap_or_else(|e| e.into_inner());
+    let rules = match Rule::parse(&word) {
+        Some(Rule::Prefix(p)) => Some((&mut e.prefixes, p)),
+        Some(Rule::Suffix(s)) => Some((&mut e.suffixes, s)),
+        None => None,
+    };
+    if let Some((rules, affix)) = rules {
+        if rules.iter().any(|r| r == affix) {
+            return false;
+        }
+        rules.push(affix.to_string());
+        return true;
+    }
+    e.removed.remove(&word);
+    let added = e.added.insert(word);
+    update_active(list, &e);
+    added
+}
+
+/// Remove a word from a list, hiding it if it is built in
+///
+/// "re-" and "-ing" remove a prefix or suffix rule instead. Returns false for
+/// an empty or too long word, or one already removed.
+pub fn remove(list: WordList, word: &str) -> bool {
+    let word = word.trim().to_lowercase();
+    if word.is_empty() || word.len() > MAX_WORD_BYTES {
+        return false;
+    }
+    let mut e = entries(list).write().unwrap_or_else(|e| e.into_inner());
+    let rules = match Rule::parse(&word) {
+        Some(Rule::Prefix(p)) => Some((&mut e.prefixes, p)),
+        Some(Rule::Suffix(s)) => Some((&mut e.suffixes, s)),
+        None => None,
+    };
+    if let Some((rules, affix)) = rules {
+        let before = rules.len();
+        rules.retain(|r| r != affix);
+        return rules.len() != before;
+    }
+    e.added.remove(&word);
+    let removed = e.removed.insert(word);
+    update_active(list, &e);
+    removed
+}
+
+/// Add every word of a dictionary file's contents, returning how many were new
+///
+/// One word or affix rule per line. Blank lines, `#` comments and a leading
+/// word count (the `.dic` header) are skipped, so `vi.dic`-style files load
+/// as-is.
+pub fn load(list: WordList, content: &str) -> usize {
+    content
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|&(i, line)| {
+            let is_count = i == 0 && line.bytes().all(|b| b.is_ascii_digit());
+            !line.is_empty() && !line.starts_with('#') && !is_count
+        })
+        .filter(|&(_, line)| add(list, line))
+        .count()
+}
+
+/// Read and load a dictionary file (see `load`)
+pub fn load_file(list: WordList, path: impl AsRef<std::path::Path>) -> std::io::Result<usize> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(load(list, &content))
+}
+
+/// Drop all user changes to a list, restoring the built-in words and rules
+pub fn clear(list: WordList) {
+    let mut e = entries(list).write().unwrap_or_else(|e| e.into_inner());
+    *e = Entries::new(list);
+    update_active(list, &e);
+}
@@ -0,0 +1,334 @@
+//! Engine Configuration - Every option in one serializable struct
+//!
+//! `EngineConfig` is written as one `key = value` line per option, in a fixed
+//! order, so frontends can store it as-is and pass it back:
+//!
+//! ```text
+//! method = telex
+//! enabled = true
+//! modern_tone = true
+//! english_auto_restore = false
+//! charset = unicode
+//! ```
+//!
+//! Reading accepts any subset of the keys in any order; blank lines and `#`
+//! comments are skipped. Unknown keys and bad values are reported as
+//! line-numbered `ConfigError`s, the other lines still apply.
+
+use std::fmt;
+
+use super::learning::DEFAULT_THRESHOLD;
+use super::Engine;
+use crate::data::charset::Charset;
+use crate::input::custom;
+
+/// Method names; custom methods are written as their id
+const METHODS: &[(&str, u8)] = &[("telex", 0), ("vni", 1), ("viqr", 2)];
+
+/// Charset names
+const CHARSETS: &[(&str, Charset)] = &[
+    ("unicode", Charset::Unicode),
+    ("tcvn3", Charset::Tcvn3),
+    ("vni_windows", Charset::VniWindows),
+    ("viscii", Charset::Viscii),
+    ("unicode_nfd", Charset::UnicodeNfd),
+];
+
+/// Keys in serialization order
+const KEYS: &[&str] = &[
+    "method",
+    "enabled",
+    "modern_tone",
+    "free_tone",
+    "english_auto_restore",
+    "learn_auto_restore",
+    "learn_threshold",
+    "auto_capitalize",
+    "skip_w_shortcut",
+    "bracket_shortcut",
+    "esc_restore",
+    "allow_foreign_consonants",
+    "suggest_on_invalid",
+    "charset",
+];
+
+/// Every engine option (shortcuts and word lists excluded)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EngineConfig {
+    /// 0 = Telex, 1 = VNI, 2 = VIQR, custom method ids from `CUSTOM_BASE`
+    pub method: u8,
+    pub enabled: bool,
+    pub modern_tone: bool,
+    pub free_tone: bool,
+    pub english_auto_restore: bool,
+    pub learn_auto_restore: bool,
+    pub learn_threshold: u8,
+    pub auto_capitalize: bool,
+    pub skip_w_shortcut: bool,
+    pub bracket_shortcut: bool,
+    pub esc_restore: bool,
+    pub allow_foreign_consonants: bool,
+    pub suggest_on_invalid: bool,
+    pub charset: Charset,
+}
+
+impl Default for EngineConfig {
+    /// Options of a new engine
+    fn default() -> Self {
+        Self {
+            method: 0,
+            enabled: true,
+            modern_tone: true,
+            free_tone: false,
+            english_auto_restore: false,
+            learn_auto_restore: false,
+            learn_threshold: DEFAULT_THRESHOLD,
+            auto_capitalize: false,
+            skip_w_shortcut: false,
+            bracket_shortcut: false,
+            esc_restore: false,
+            allow_foreign_consonants: false,
+            suggest_on_invalid: false,
+            charset: Charset::Unicode,
+        }
+    }
+}
+
+/// Error from reading a configuration line
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    /// 1-based line
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl EngineConfig {
+    /// Read a configuration, starting from the defaults
+    pub fn parse(text: &str) -> Result<Self, Vec<ConfigError>> {
+        let mut config = Self::default();
+        let errors = config.update(text);
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Apply the lines of a configuration, returning the ones that failed
+    ///
+    /// Keys not mentioned keep their value; failed lines change nothing.
+    pub fn update(&mut self, text: &str) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let result = match line.split_once('=') {
+                Some((key, value)) => self.set(key.trim(), value.trim()),
+                None => Err(format!("expected `key = value`, got `{}`", line)),
+            };
+            if let Err(message) = result {
+                errors.push(ConfigError {
+                    line: i + 1,
+                    message,
+                });
+            }
+        }
+        errors
+    }
+
+    /// Set one option from its text value
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let flag = match key {
+            "method" => {
+                self.method = parse_method(value)?;
+                return Ok(());
+            }
+            "learn_threshold" => {
+                self.learn_threshold = match value.parse::<u8>() {
+                    Ok(n @ 1..=127) => n,
+                    _ => return Err(format!("`{}` must be 1-127, got `{}`", key, value)),
+                };
+                return Ok(());
+            }
+            "charset" => {
+                self.charset = lookup(CHARSETS, value).ok_or_else(|| {
+                    format!("unknown charset `{}` (expected {})", value, names(CHARSETS))
+                })?;
+                return Ok(());
+            }
+            "enabled" => &mut self.enabled,
+            "modern_tone" => &mut self.modern_tone,
+            "free_tone" => &mut self.free_tone,
+            "english_auto_restore" => &mut self.english_auto_restore,
+            "learn_auto_restore" => &mut self.learn_auto_restore,
+            "auto_capitalize" => &mut self.auto_capitalize,
+            "skip_w_shortcut" => &mut self.skip_w_shortcut,
+            "bracket_shortcut" => &mut self.bracket_shortcut,
+            "esc_restore" => &mut self.esc_restore,
+            "allow_foreign_consonants" => &mut self.allow_foreign_consonants,
+            "suggest_on_invalid" => &mut self.suggest_on_invalid,
+            _ => return Err(format!("unknown key `{}`", key)),
+        };
+        *flag = match value {
+            "true" => true,
+            "false" => false,
+            _ => return Err(format!("`{}` must be true or false, got `{}`", key, value)),
+        };
+        Ok(())
+    }
+
+    /// Text value of one option (None for an unknown key)
+    pub fn get(&self, key: &str) -> Option<String> {
+        let value = match key {
+            "method" => match METHODS.iter().find(|&&(_, id)| id == self.method) {
+                Some((name, _)) => name.to_string(),
+                None => self.method.to_string(),
+            },
+            "enabled" => self.enabled.to_string(),
+            "modern_tone" => self.modern_tone.to_string(),
+            "free_tone" => self.free_tone.to_string(),
+            "english_auto_restore" => self.english_auto_restore.to_string(),
+            "learn_auto_restore" => self.learn_auto_restore.to_string(),
+            "learn_threshold" => self.learn_threshold.to_string(),
+            "auto_capitalize" => self.auto_capitalize.to_string(),
+            "skip_w_shortcut" => self.skip_w_shortcut.to_string(),
+            "bracket_shortcut" => self.bracket_shortcut.to_string(),
+            "esc_restore" => self.esc_restore.to_string(),
+            "allow_foreign_consonants" => self.allow_foreign_consonants.to_string(),
+            "suggest_on_invalid" => self.suggest_on_invalid.to_string(),
+            "charset" => CHARSETS
+                .iter()
+                .find(|&&(_, c)| c == self.charset)
+                .map(|(name, _)| name.to_string())?,
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// All keys, in the order they are written
+    pub fn keys() -> &'static [&'static str] {
+        KEYS
+    }
+}
+
+impl fmt::Display for EngineConfig {
+    /// One `key = value` line per option
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for key in KEYS {
+            let value = self.get(key).unwrap_or_default();
+            writeln!(f, "{} = {}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Method by name or id; ids must be built in or a registered custom method
+fn parse_method(value: &str) -> Result<u8, String> {
+    if let Some(id) = lookup(METHODS, value) {
+        return Ok(id);
+    }
+    match value.parse::<u8>() {
+        Ok(id) if METHODS.iter().any(|&(_, m)| m == id) || custom::get(id).is_some() => Ok(id),
+        _ => {
+            let names: Vec<&str> = METHODS.iter().map(|&(name, _)| name).collect();
+            Err(format!(
+                "unknown method `{}` (expected {} or a custom method id)",
+                value,
+                names.join(", ")
+            ))
+        }
+    }
+}
+
+fn lookup<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    table
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|&(_, value)| value)
+}
+
+/// "a, b or c"
+fn names<T>(table: &[(&str, T)]) -> String {
+    let names: Vec<&str> = table.iter().map(|&(name, _)| name).collect();
+    match names.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+        _ => names.concat(),
+    }
+}
+
+impl Engine {
+    /// Current options
+    pub fn config(&self) -> EngineConfig {
+        EngineConfig {
+            method: self.method,
+            enabled: self.enabled,
+            modern_tone: self.modern_tone,
+            free_tone: self.free_tone_enabled,
+            english_auto_restore: self.english_auto_restore,
+            learn_auto_restore: self.learn_auto_restore,
+            learn_threshold: self.learning.threshold(),
+            auto_capitalize: self.auto_capitalize,
+            skip_w_shortcut: self.skip_w_shortcut,
+            bracket_shortcut: self.bracket_shortcut,
+            esc_restore: self.esc_restore_enabled,
+            allow_foreign_consonants: self.allow_foreign_consonants,
+            suggest_on_invalid: self.suggest_on_invalid,
+            charset: self.charset,
+        }
+    }
+
+    /// Apply every option
+    pub fn set_config(&mut self, config: &EngineConfig) {
+        self.set_method(config.method);
+        self.set_enabled(config.enabled);
+        self.set_modern_tone(config.modern_tone);
+        self.set_free_tone(config.free_tone);
+        self.set_english_auto_restore(config.english_auto_restore);
+        self.set_learn_auto_restore(config.learn_auto_restore);
+        self.learning.set_threshold(config.learn_threshold);
+        self.set_auto_capitalize(config.auto_capitalize);
+        self.set_skip_w_shortcut(config.skip_w_shortcut);
+        self.set_bracket_shortcut(config.bracket_shortcut);
+        self.set_esc_restore(config.esc_restore);
+        self.set_allow_foreign_consonants(config.allow_foreign_consonants);
+        self.set_suggest_on_invalid(config.suggest_on_invalid);
+        if config.charset != self.charset {
+            self.set_charset(config.charset);
+        }
+    }
+
+    /// Apply a `key = value` configuration on top of the current options
+    ///
+    /// Valid lines apply even if others fail; the failures are returned.
+    pub fn configure(&mut self, text: &str) -> Vec<ConfigError> {
+        let mut config = self.config();
+        let errors = config.update(text);
+        self.set_config(&config);
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_matches_new_engine() {
+        assert_eq!(Engine::new().config(), EngineConfig::default());
+    }
+
+    #[test]
+    fn every_key_round_trips() {
+        let text = EngineConfig::default().to_string();
+        assert_eq!(text.lines().count(), KEYS.len());
+        assert_eq!(EngineConfig::parse(&text), Ok(EngineConfig::default()));
+    }
+}
@@ -0,0 +1,202 @@
+//! Learning Auto-Restore - Adapt English auto-restore to the user's corrections
+//!
+//! Corrections are counted per word, keyed by its English spelling (the raw
+//! keystrokes, lowercased):
+//! - ESC restoring a word, or a word committed after a manual mark revert
+//!   ("mixx" → "mix"): auto-restore should have restored it
+//! - Backspace right after an auto-restored word: it should have been kept
+//!
+//! Each correction moves the word's score one step; once it reaches the
+//! threshold either way, auto-restore always (or never) restores the word.
+//! Opposite corrections move it back, so a mistaken habit can be unlearned.
+
+use std::collections::HashMap;
+
+/// Corrections needed before a word is always (or never) restored
+pub const DEFAULT_THRESHOLD: u8 = 3;
+
+/// Learned words kept; the least certain are dropped first
+const MAX_WORDS: usize = 4096;
+
+/// Export format tag and version
+const MAGIC: &[u8; 4] = b"GNL1";
+
+/// What auto-restore should have done with a word
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Learned {
+    /// Restore to English
+    Restore,
+    /// Keep the Vietnamese transform
+    Keep,
+}
+
+/// Correction scores per word: positive = restore, negative = keep
+#[derive(Clone, Debug)]
+pub struct Learning {
+    scores: HashMap<String, i8>,
+    threshold: u8,
+}
+
+impl Default for Learning {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Learning {
+    pub fn new() -> Self {
+        Self {
+            scores: HashMap::new(),
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+
+    /// Set the corrections needed to decide a word (at least 1)
+    pub fn set_threshold(&mut self, threshold: u8) {
+        self.threshold = threshold.clamp(1, i8::MAX as u8);
+    }
+
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Record one correction of `word` (case-insensitive)
+    pub fn record(&mut self, word: &str, correction: Learned) {
+        let word = word.to_lowercase();
+        if word.is_empty() || word.len() > u8::MAX as usize {
+            return;
+        }
+        if !self.scores.contains_key(&word) && self.scores.len() >= MAX_WORDS {
+            self.evict();
+        }
+        let limit = self.threshold as i8;
+        let score = self.scores.entry(word).or_insert(0);
+        *score = match correction {
+            Learned::Restore => score.saturating_add(1),
+            Learned::Keep => score.saturating_sub(1),
+        }
+        .clamp(-limit, limit);
+    }
+
+    /// Learned decision for raw input (lowercase), once past the threshold
+    pub fn decision(&self, word: &str) -> Option<Learned> {
+        let score = *self.scores.get(word)?;
+        let threshold = self.threshold as i8;
+        if score >= threshold {
+            Some(Learned::Restore)
+        } else if score <= -threshold {
+            Some(Learned::Keep)
+        } else {
+            None
+        }
+    }
+
+    /// Number of words with corrections
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.scores.clear();
+    }
+
+    /// Serialize the table: "GNL1", then per word `[score: i8][len: u8][UTF-8]`
+    pub fn export(&self) -> Vec<u8> {
+        let mut words: Vec<(&String, &i8)> = self.scores.iter().collect();
+        words.sort();
+        let mut out = MAGIC.to_vec();
+        for (word, &score) in words {
+            out.push(score as u8);
+            out.push(word.len() as u8);
+            out.extend_from_slice(word.as_bytes());
+        }
+        out
+    }
+
+    /// Replace the table with exported bytes, returning the number of words
+    ///
+    /// Returns None (and keeps the current table) if the data is malformed.
+    pub fn import(&mut self, bytes: &[u8]) -> Option<usize> {
+        let mut rest = bytes.strip_prefix(MAGIC)?;
+        let limit = self.threshold as i8;
+        let mut scores = HashMap::new();
+        while let [score, len, tail @ ..] = rest {
+            let len = *len as usize;
+            let word = std::str::from_utf8(tail.get(..len)?).ok()?;
+            if word.is_empty() || scores.len() >= MAX_WORDS {
+                return None;
+            }
+            let score = (*score as i8).clamp(-limit, limit);
+            scores.insert(word.to_lowercase(), score);
+            rest = &tail[len..];
+        }
+        if !rest.is_empty() {
+            return None;
+        }
+        self.scores = scores;
+        Some(self.scores.len())
+    }
+
+    /// Drop the least certain word to make room
+    fn evict(&mut self) {
+        let weakest = self
+            .scores
+            .iter()
+            .min_by_key(|(_, score)| score.unsigned_abs())
+            .map(|(word, _)| word.clone());
+        if let Some(word) = weakest {
+            self.scores.remove(&word);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decides_after_threshold() {
+        let mut l = Learning::new();
+        l.set_threshold(2);
+        l.record("Mix", Learned::Restore);
+        assert_eq!(l.decision("mix"), None);
+        l.record("mix", Learned::Restore);
+        assert_eq!(l.decision("mix"), Some(Learned::Restore));
+        // Capped at the threshold: two opposite corrections flip it
+        l.record("mix", Learned::Restore);
+        l.record("mix", Learned::Keep);
+        l.record("mix", Learned::Keep);
+        assert_eq!(l.decision("mix"), None);
+        l.record("mix", Learned::Keep);
+        l.record("mix", Learned::Keep);
+        assert_eq!(l.decision("mix"), Some(Learned::Keep));
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let mut l = Learning::new();
+        l.record("box", Learned::Restore);
+        l.record("việt", Learned::Keep);
+        let bytes = l.export();
+        let mut other = Learning::new();
+        assert_eq!(other.import(&bytes), Some(2));
+        assert_eq!(other.export(), bytes);
+    }
+
+    #[test]
+    fn import_rejects_malformed() {
+        let mut l = Learning::new();
+        l.record("box", Learned::Restore);
+        let bytes = l.export();
+        assert_eq!(l.import(b"XXXX"), None);
+        assert_eq!(l.import(&bytes[..bytes.len() - 1]), None);
+        assert_eq!(l.import(&[b'G', b'N', b'L', b'1', 1, 2, 0xFF, 0xFE]), None);
+        // Unchanged after failed imports
+        assert_eq!(l.export(), bytes);
+        assert_eq!(l.import(MAGIC), Some(0));
+    }
+}
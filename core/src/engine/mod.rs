@@ -11,20 +11,32 @@
 //! 4. **Longest-Match-First**: For diacritic placement
 
 pub mod buffer;
+pub mod config;
+pub mod learning;
+pub mod preedit;
+pub mod profile;
 pub mod shortcut;
+pub mod snapshot;
+pub mod surrounding;
 pub mod syllable;
+pub mod trace;
 pub mod transform;
 pub mod validation;
 
 use crate::data::{
     chars::{self, mark, tone},
+    charset::{self, Charset},
     constants, dictionary, english_dict, keys, telex_doubles,
     vowel::{Phonology, Vowel},
 };
 use crate::input::{self, ToneType};
+use crate::text;
 use crate::utils;
 use buffer::{Buffer, Char, MAX};
+use learning::{Learned, Learning};
+use profile::Profiles;
 use shortcut::{InputMethod, ShortcutTable};
+use trace::{KeyInput, Trace};
 use validation::{
     is_foreign_word_pattern, is_valid, is_valid_for_transform_with_foreign, is_valid_with_foreign,
     is_valid_with_tones, is_valid_with_tones_and_foreign,
@@ -37,6 +49,9 @@ pub enum Action {
     None = 0,
     Send = 1,
     Restore = 2,
+    /// Committed word is misspelled: `chars` holds a suggestion to offer for
+    /// the `backspace` chars before the key (the key itself passes through)
+    Suggest = 3,
 }
 
 /// Result for FFI
@@ -49,12 +64,17 @@ pub struct Result {
     /// Flags byte:
     /// - bit 0 (0x01): key_consumed - if set, the trigger key should NOT be passed through
     ///   Used for shortcuts where the trigger key is part of the replacement
+    /// - bit 1 (0x02): delete_tail - before applying, delete the rest of the word
+    ///   after the caret (count returned by `restore_surrounding`)
     pub flags: u8,
 }
 
 /// Flag: key was consumed by shortcut, don't pass through
 pub const FLAG_KEY_CONSUMED: u8 = 0x01;
 
+/// Flag: delete the word tail after the caret first (see `restore_surrounding`)
+pub const FLAG_DELETE_TAIL: u8 = 0x02;
+
 impl Result {
     pub fn none() -> Self {
         Self {
@@ -87,6 +107,13 @@ impl Result {
         result
     }
 
+    /// Suggestion for the committed word (see `Action::Suggest`)
+    pub fn suggest(backspace: u8, chars: &[char]) -> Self {
+        let mut result = Self::send(backspace, chars);
+        result.action = Action::Suggest as u8;
+        result
+    }
+
     /// Check if key was consumed (should not be passed through)
     pub fn key_consumed(&self) -> bool {
         self.flags & FLAG_KEY_CONSUMED != 0
@@ -114,16 +141,30 @@ enum Transform {
     BracketAsVowel,
 }
 
+/// On-screen mirror capacity for legacy charset backspace counting
+const SCREEN_CAPACITY: usize = 256;
+
 /// Word history ring buffer capacity (stores last N committed words)
 const HISTORY_CAPACITY: usize = 10;
 
+/// Language of a committed word, for context-aware auto-restore
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Lang {
+    English,
+    Vietnamese,
+    /// Could be either ("the", "an", "to")
+    Neutral,
+}
+
 /// Ring buffer for word history (stack-allocated, O(1) push/pop)
 ///
 /// Used for backspace-after-space feature: when user presses backspace
 /// immediately after committing a word with space, restore the previous
-/// buffer state to allow editing.
+/// buffer state to allow editing. Each word also keeps its language, the
+/// context for auto-restoring the next ambiguous word.
 struct WordHistory {
     data: [Buffer; HISTORY_CAPACITY],
+    langs: [Lang; HISTORY_CAPACITY],
     head: usize,
     len: usize,
 }
@@ -132,14 +173,16 @@ impl WordHistory {
     fn new() -> Self {
         Self {
             data: std::array::from_fn(|_| Buffer::new()),
+            langs: [Lang::Neutral; HISTORY_CAPACITY],
             head: 0,
             len: 0,
         }
     }
 
     /// Push buffer to history (overwrites oldest if full)
-    fn push(&mut self, buf: Buffer) {
+    fn push(&mut self, buf: Buffer, lang: Lang) {
         self.data[self.head] = buf;
+        self.langs[self.head] = lang;
         self.head = (self.head + 1) % HISTORY_CAPACITY;
         if self.len < HISTORY_CAPACITY {
             self.len += 1;
@@ -160,6 +203,18 @@ impl WordHistory {
         self.len = 0;
         self.head = 0;
     }
+
+    /// Language of the last two words that have one, if they agree
+    /// (neutral words are skipped)
+    fn context(&self) -> Option<Lang> {
+        let mut recent = (1..=self.len)
+            .map(|i| self.langs[(self.head + HISTORY_CAPACITY - i) % HISTORY_CAPACITY])
+            .filter(|&lang| lang != Lang::Neutral);
+        match (recent.next(), recent.next()) {
+            (Some(a), Some(b)) if a == b => Some(a),
+            _ => None,
+        }
+    }
 }
 
 /// Check if key is sentence-ending punctuation (. ! ?) but NOT Enter
@@ -278,6 +333,14 @@ pub struct Engine {
     /// When true, automatically restores English words that were transformed
     /// e.g., "tẽt" → "text", "ễpct" → "expect"
     english_auto_restore: bool,
+    /// Learn auto-restore decisions from the user's corrections (ESC, mark
+    /// revert, backspace after a restore)
+    learn_auto_restore: bool,
+    /// Corrections learned per word (see `learning`)
+    learning: Learning,
+    /// English word auto-restored by the last SPACE commit, for learning
+    /// when backspace takes it back
+    last_restored_word: Option<String>,
     /// Word history for backspace-after-space feature
     word_history: WordHistory,
     /// Number of spaces typed after committing a word (for backspace tracking)
@@ -341,6 +404,9 @@ pub struct Engine {
     /// Restored word was pure ASCII (no Vietnamese chars) - clear on ANY letter
     /// For Vietnamese restored words, only clear on consonant (allow mark/tone edits)
     restored_is_ascii: bool,
+    /// Restored word came from `restore_surrounding` (see
+    /// `complete_restored_vowels`)
+    restored_from_surrounding: bool,
     /// Auto-capitalize first letter after sentence-ending punctuation
     /// Triggers: . ! ? Enter → next letter becomes uppercase
     auto_capitalize: bool,
@@ -356,6 +422,26 @@ pub struct Engine {
     /// Allow foreign consonants (z, w, j, f) as valid initial consonants
     /// When true, these letters are accepted as Vietnamese consonants for loanwords
     allow_foreign_consonants: bool,
+    /// Offer a correction (`Action::Suggest`) when a misspelled word is committed
+    suggest_on_invalid: bool,
+    /// Output charset: other than Unicode, `Result::chars` is re-encoded as
+    /// legacy bytes or decomposed (NFD) letters
+    charset: Charset,
+    /// Precomposed mirror of the text before the cursor (non-Unicode charsets only)
+    /// Backspace counts depend on how many bytes each deleted char occupies
+    /// (VNI-Windows "ệ" is 2 chars on screen), so the engine remembers what it typed
+    screen: Vec<char>,
+    /// Composing string in preedit mode (see `on_key_preedit`)
+    preedit: Vec<char>,
+    /// Letters of the buffer word that sit after the caret
+    /// Set by `restore_surrounding` when the caret is inside a word
+    caret_tail: usize,
+    /// Per-app settings (see `profile`)
+    profiles: Profiles,
+    /// VIQR/custom: `\` ended the last word, the next modifier symbol is literal
+    symbol_escape: bool,
+    /// Keys being recorded (see `trace`)
+    trace: Option<Trace>,
 }
 
 impl Default for Engine {
@@ -380,6 +466,9 @@ impl Engine {
             free_tone_enabled: false,
             modern_tone: true,           // Default: modern style (hoà, thuý)
             english_auto_restore: false, // Default: OFF (experimental feature)
+            learn_auto_restore: false,   // Default: OFF
+            learning: Learning::new(),
+            last_restored_word: None,
             word_history: WordHistory::new(),
             spaces_after_commit: 0,
             pending_breve_pos: None,
@@ -397,11 +486,20 @@ impl Engine {
             shortcut_prefix: String::new(),
             restored_pending_clear: false,
             restored_is_ascii: false,
+            restored_from_surrounding: false,
             auto_capitalize: false, // Default: OFF
             pending_capitalize: false,
             auto_capitalize_used: false,
             saw_sentence_ending: false,
             allow_foreign_consonants: false, // Default: OFF
+            suggest_on_invalid: false,       // Default: OFF
+            charset: Charset::Unicode,
+            screen: Vec::new(),
+            preedit: Vec::new(),
+            caret_tail: 0,
+            profiles: Profiles::default(),
+            symbol_escape: false,
+            trace: None,
         }
     }
 
@@ -448,6 +546,20 @@ impl Engine {
         self.english_auto_restore = enabled;
     }
 
+    /// Set whether auto-restore learns from corrections (opt-in)
+    pub fn set_learn_auto_restore(&mut self, enabled: bool) {
+        self.learn_auto_restore = enabled;
+    }
+
+    pub fn learning(&self) -> &Learning {
+        &self.learning
+    }
+
+    /// Learned table: threshold, export/import for persistence
+    pub fn learning_mut(&mut self) -> &mut Learning {
+        &mut self.learning
+    }
+
     /// Set whether to enable auto-capitalize after sentence-ending punctuation
     pub fn set_auto_capitalize(&mut self, enabled: bool) {
         self.auto_capitalize = enabled;
@@ -462,6 +574,22 @@ impl Engine {
         self.allow_foreign_consonants = enabled;
     }
 
+    /// Set whether committing a misspelled word returns a suggestion
+    pub fn set_suggest_on_invalid(&mut self, enabled: bool) {
+        self.suggest_on_invalid = enabled;
+    }
+
+    /// Set output charset (Unicode, TCVN3, VNI-Windows, VISCII, Unicode NFD)
+    pub fn set_charset(&mut self, charset: Charset) {
+        self.charset = charset;
+        self.screen.clear();
+    }
+
+    /// Get output charset
+    pub fn charset(&self) -> Charset {
+        self.charset
+    }
+
     /// Get whether foreign consonants are allowed
     pub fn allow_foreign_consonants(&self) -> bool {
         self.allow_foreign_consonants
@@ -567,7 +695,66 @@ impl Engine {
         let Some(ch) = ch else {
             return self.on_key_ext(key, caps, ctrl, shift);
         };
+        let result = self.on_symbol_char(ch, ctrl);
+        self.record(
+            KeyInput {
+                key,
+                caps,
+                ctrl,
+                shift,
+                ch: Some(ch),
+            },
+            &result,
+        );
+        result
+    }
+
+    /// Handle a character produced by the OS keyboard layout.
+    ///
+    /// Layout-independent alternative to `on_key_ext`: the platform passes the
+    /// character the user's layout (AZERTY, Dvorak, Colemak, ...) already
+    /// produced, and the engine maps it to its own key model. Caps and shift
+    /// are derived from the character (e.g. 'A' → A+caps, '@' → Shift+2).
+    ///
+    /// Characters outside the key model (Option/AltGr symbols, accented
+    /// letters) only take part in shortcut matching, like `on_key_with_char`.
+    ///
+    /// # Arguments
+    /// * `ch` - Character to type. Control chars: '\u{8}' backspace,
+    ///   '\r'/'\n' Enter, '\t' Tab, '\u{1b}' ESC
+    /// * `ctrl` - true if Cmd/Ctrl/Alt is pressed (bypasses IME)
+    pub fn on_char(&mut self, ch: char, ctrl: bool) -> Result {
+        match utils::char_to_key_ext(ch) {
+            Some((key, caps, shift)) => self.on_key_ext(key, caps, ctrl, shift),
+            // No key for the char: recorded like `on_key_with_char` (the key
+            // is ignored on replay)
+            None => self.on_key_with_char(0, false, ctrl, false, Some(ch)),
+        }
+    }
+
+    /// Accumulate a non-keymap character for special-character shortcuts (Issue #275)
+    fn on_symbol_char(&mut self, ch: char, ctrl: bool) -> Result {
+        if self.caret_tail > 0 {
+            // Symbol typed inside the word: stop editing it
+            self.caret_tail = 0;
+            self.clear();
+        }
+        let result = self.handle_symbol_char(ch, ctrl);
+        if self.charset == Charset::Unicode {
+            return result;
+        }
+        if ctrl {
+            self.screen.clear();
+            return result;
+        }
+        let result = self.encode_output(result);
+        if !result.key_consumed() {
+            self.push_screen(ch);
+        }
+        result
+    }
 
+    fn handle_symbol_char(&mut self, ch: char, ctrl: bool) -> Result {
         // Ctrl/Cmd bypasses everything
         if ctrl {
             self.clear();
@@ -628,6 +815,158 @@ impl Engine {
     /// * `ctrl` - true if Cmd/Ctrl/Alt is pressed (bypasses IME)
     /// * `shift` - true if Shift key is pressed (for symbols like @, #, $)
     pub fn on_key_ext(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
+        let result = self.process_key(key, caps, ctrl, shift);
+        self.record(
+            KeyInput {
+                key,
+                caps,
+                ctrl,
+                shift,
+                ch: None,
+            },
+            &result,
+        );
+        result
+    }
+
+    /// `on_key_ext` without recording
+    fn process_key(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
+        // Word this key commits, checked after the key is handled
+        let committed = (self.suggest_on_invalid
+            && !ctrl
+            && !self.buf.is_empty()
+            && keys::is_break_ext(key, shift))
+        .then(|| self.buf.to_full_string());
+        let mut result = if self.caret_tail > 0 {
+            self.handle_key_mid_word(key, caps, ctrl, shift)
+        } else {
+            self.handle_key(key, caps, ctrl, shift)
+        };
+        if let Some(word) = committed.filter(|_| result.action == Action::None as u8) {
+            if let Some(suggestion) = Self::suggestion_for(&word) {
+                let chars: Vec<char> = suggestion.chars().collect();
+                let backspace = word.chars().count().min(u8::MAX as usize) as u8;
+                result = Result::suggest(backspace, &chars);
+            }
+        }
+        if self.charset == Charset::Unicode {
+            return result;
+        }
+        if ctrl || matches!(key, keys::LEFT | keys::RIGHT | keys::UP | keys::DOWN) {
+            // Cursor may have moved: the mirror no longer matches the screen
+            self.screen.clear();
+            return result;
+        }
+        let sent = result.action == Action::Send as u8;
+        let result = self.encode_output(result);
+
+        // Keys the platform still types after (or instead of) the result.
+        // SPACE Send output already contains the space.
+        if key == keys::DELETE {
+            if !sent {
+                // A native backspace only deletes one char: delete the whole
+                // letter ourselves when it takes more (VNI-Windows or NFD "ệ")
+                let width = self
+                    .screen
+                    .pop()
+                    .and_then(|c| charset::screen_form(c, self.charset))
+                    .map_or(1, |(_, len)| len);
+                if width > 1 {
+                    return Result::send_consumed(width as u8, &[]);
+                }
+            }
+        } else if !result.key_consumed()
+            && (!sent || (key != keys::SPACE && keys::is_break_ext(key, shift)))
+        {
+            let typed = match key {
+                keys::SPACE => Some(' '),
+                keys::TAB => Some('\t'),
+                keys::RETURN | keys::ENTER => Some('\n'),
+                _ => utils::key_to_char_ext(key, caps, shift),
+            };
+            if let Some(c) = typed {
+                self.push_screen(c);
+            }
+        }
+        result
+    }
+
+    /// Re-encode a Send or Suggest result for the output charset.
+    ///
+    /// Backspace becomes the number of chars the deleted text occupies on
+    /// screen (unknown text counts 1 per char); output chars become legacy
+    /// bytes or decomposed letters. Characters the charset cannot represent
+    /// are sent unchanged. A suggestion is only offered, so the mirror keeps
+    /// the word until the platform replaces it.
+    fn encode_output(&mut self, result: Result) -> Result {
+        let suggest = result.action == Action::Suggest as u8;
+        if result.action != Action::Send as u8 && !suggest {
+            return result;
+        }
+        let charset = self.charset;
+        let width = |c: &char| charset::screen_form(*c, charset).map_or(1, |(_, len)| len);
+        let count = result.backspace as usize;
+        let backspace: usize = if suggest {
+            let known = self.screen.len().min(count);
+            self.screen[self.screen.len() - known..]
+                .iter()
+                .map(width)
+                .sum::<usize>()
+                + (count - known)
+        } else {
+            (0..count)
+                .map(|_| self.screen.pop().as_ref().map_or(1, width))
+                .sum()
+        };
+        let mut output = Vec::with_capacity(result.count as usize * 2);
+        for &code in &result.chars[..result.count as usize] {
+            let Some(c) = char::from_u32(code) else {
+                continue;
+            };
+            if !suggest {
+                self.push_screen(c);
+            }
+            match charset::screen_form(c, self.charset) {
+                Some((form, len)) => output.extend_from_slice(&form[..len]),
+                None => output.push(c),
+            }
+        }
+        let mut encoded = Result::send(backspace.min(u8::MAX as usize) as u8, &output);
+        encoded.action = result.action;
+        encoded.flags = result.flags;
+        encoded
+    }
+
+    /// Top correction for a committed word with diacritics that fails spellcheck
+    ///
+    /// Plain ASCII words are left alone: they are usually English.
+    fn suggestion_for(word: &str) -> Option<String> {
+        if word.is_ascii() || text::spellcheck::check_syllable(word).is_none() {
+            return None;
+        }
+        text::suggest(word, 1).into_iter().next()
+    }
+
+    /// Append a char to the on-screen mirror (bounded)
+    fn push_screen(&mut self, c: char) {
+        if self.screen.len() >= SCREEN_CAPACITY {
+            self.screen.drain(..SCREEN_CAPACITY / 2);
+        }
+        self.screen.push(c);
+    }
+
+    fn handle_key(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
+        // VIQR/custom: `\` right after a word escapes the next modifier symbol,
+        // which replaces the backslash ("nhie^u\?" → "nhiêu?", "\\" → "\")
+        if std::mem::take(&mut self.symbol_escape) && !ctrl && self.enabled {
+            if let Some(c) = self.escaped_symbol(key, shift) {
+                self.clear();
+                self.word_history.clear();
+                self.spaces_after_commit = 0;
+                return Result::send_consumed(1, &[c]);
+            }
+        }
+
         // Issue #129: Process shortcuts even when IME is disabled
         // Only bypass completely for Ctrl/Cmd modifier keys
         if ctrl {
@@ -735,6 +1074,12 @@ impl Engine {
             // Auto-restore: if buffer has transforms but is invalid Vietnamese,
             // restore to raw English (like ESC but triggered by space)
             let restore_result = self.try_auto_restore_on_space();
+            if !self.buf.is_empty() {
+                self.learn_from_commit(restore_result.action != 0);
+                // Backspace right after this commit would take the restore back
+                self.last_restored_word = (self.learn_auto_restore && restore_result.action != 0)
+                    .then(|| self.get_raw_input_string());
+            }
 
             // If auto-restore happened, repopulate buffer with plain chars from raw_input
             // This ensures word_history stores the correct restored word (not transformed)
@@ -749,7 +1094,8 @@ impl Engine {
 
             // Push buffer to history before clearing (for backspace-after-space feature)
             if !self.buf.is_empty() {
-                self.word_history.push(self.buf.clone());
+                let lang = self.committed_lang(restore_result.action != 0);
+                self.word_history.push(self.buf.clone(), lang);
                 self.spaces_after_commit = 1; // First space after word
             } else if self.spaces_after_commit > 0 {
                 // Additional space after commit - increment counter
@@ -776,12 +1122,30 @@ impl Engine {
             } else {
                 Result::none()
             };
+            // Learning: ESC restores what auto-restore kept
+            if self.learn_auto_restore && result.action != 0 {
+                let raw = self.get_raw_input_string();
+                self.learning.record(&raw, Learned::Restore);
+            }
             self.clear();
             self.word_history.clear();
             self.spaces_after_commit = 0;
             return result;
         }
 
+        // VIQR/custom: modifier symbols (' ` ? ~ . ^ + * () are break keys in other methods.
+        // Apply them when the word has something to modify, else fall through as punctuation.
+        if self.method >= 2 {
+            if let Some(mut result) = self.try_symbol_modifier(key, caps, shift) {
+                // The symbol was used as a modifier - platform must not type it
+                result.flags |= FLAG_KEY_CONSUMED;
+                return result;
+            }
+            if key == keys::BACKSLASH && !shift && !self.buf.is_empty() {
+                self.symbol_escape = true;
+            }
+        }
+
         // Issue #159: In Telex mode, `]` → ư and `[` → ơ
         // caps affects revert: ]] → ], uppercase (Shift/CapsLock) → }
         if self.method == 0 && (key == keys::RBRACKET || key == keys::LBRACKET) {
@@ -881,6 +1245,9 @@ impl Engine {
             }
 
             let restore_result = self.try_auto_restore_on_break();
+            if !self.buf.is_empty() {
+                self.learn_from_commit(restore_result.action != 0);
+            }
             self.clear();
             self.word_history.clear();
             self.spaces_after_commit = 0;
@@ -903,6 +1270,10 @@ impl Engine {
                 if self.spaces_after_commit == 0 {
                     // All spaces deleted - restore the word buffer
                     if let Some(restored_buf) = self.word_history.pop() {
+                        // Learning: the user took an auto-restore back
+                        if let Some(word) = self.last_restored_word.take() {
+                            self.learning.record(&word, Learned::Keep);
+                        }
                         // Restore raw_input from buffer (for ESC restore to work)
                         self.restore_raw_input_from_buffer(&restored_buf);
                         self.buf = restored_buf;
@@ -1032,10 +1403,17 @@ impl Engine {
             };
             if should_clear {
                 self.clear();
+            } else if self.restored_is_ascii
+                && self.restored_from_surrounding
+                && m.mark(key).is_some()
+            {
+                // Surrounding text may have been typed without diacritics
+                self.complete_restored_vowels();
             }
             // Reset flags regardless - user is now actively typing
             self.restored_pending_clear = false;
             self.restored_is_ascii = false;
+            self.restored_from_surrounding = false;
         }
 
         // Issue #212: Reset has_non_letter_prefix when user starts typing letter into empty buffer
@@ -1326,6 +1704,106 @@ impl Engine {
         self.handle_normal_letter(key, caps)
     }
 
+    /// VIQR/custom: apply a symbol modifier to the current word
+    ///
+    /// Returns None when the symbol should be typed as punctuation. `?` and `.`
+    /// also end sentences, so they only act as marks right after a vowel:
+    /// "a." → "ạ" but "ban." → "ban.".
+    fn try_symbol_modifier(&mut self, key: u16, caps: bool, shift: bool) -> Option<Result> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        let m = input::get(self.method);
+        let mark_val = m.mark_with_shift(key, shift);
+        let tone_type = m.tone_with_shift(key, shift);
+        if mark_val.is_none() && tone_type.is_none() {
+            return None;
+        }
+        // `?` and `.` are also sentence punctuation: they only mark a vowel
+        // at the end of a word that has no mark yet ("qua'." → "quá.")
+        let is_punctuation = matches!(key, keys::SLASH | keys::DOT);
+        let after_vowel = self.buf.last().is_some_and(|c| keys::is_vowel(c.key));
+        if is_punctuation && !after_vowel {
+            return None;
+        }
+
+        // Same symbol twice: undo the diacritic and type the symbol ("a''" → "a'")
+        // ` and ~ share a key, so marks compare the value too
+        match self.last_transform {
+            Some(Transform::Mark(k, v)) if k == key => {
+                if mark_val == Some(v) {
+                    return Some(self.revert_symbol_modifier(key, caps, shift));
+                }
+                self.last_transform = None; // ` then ~ replaces the mark
+            }
+            Some(Transform::Tone(k, _)) if k == key => {
+                return Some(self.revert_symbol_modifier(key, caps, shift));
+            }
+            _ => {}
+        }
+        if is_punctuation && self.buf.iter().any(|c| c.mark > mark::NONE) {
+            return None;
+        }
+
+        let result = match (mark_val, tone_type) {
+            (Some(mark_val), _) => self.try_mark(key, caps, mark_val),
+            (None, Some(tone_type)) => self.try_tone(key, caps, tone_type, m.tone_targets(key)),
+            (None, None) => None,
+        }?;
+        // Record for ESC restore ("Vie^.t" → ESC → "Vie^.t")
+        self.raw_input.push((key, caps, shift));
+        Some(result)
+    }
+
+    /// VIQR/custom: character of a modifier symbol (or `\`) typed after `\`
+    fn escaped_symbol(&self, key: u16, shift: bool) -> Option<char> {
+        if self.method < 2 {
+            return None;
+        }
+        let m = input::get(self.method);
+        let is_symbol = (key == keys::BACKSLASH && !shift)
+            || m.mark_with_shift(key, shift).is_some()
+            || m.tone_with_shift(key, shift).is_some();
+        is_symbol.then(|| utils::key_to_char_ext(key, false, shift))?
+    }
+
+    /// VIQR/custom: revert the last symbol modifier and type the symbol literally
+    ///
+    /// The literal symbol is not tracked in the buffer, so it ends the word.
+    fn revert_symbol_modifier(&mut self, key: u16, caps: bool, shift: bool) -> Result {
+        let is_mark = matches!(self.last_transform, Some(Transform::Mark(..)));
+        let pos = self.buf.find_vowels().into_iter().rev().find(|&pos| {
+            self.buf
+                .get(pos)
+                .is_some_and(|c| if is_mark { c.mark > 0 } else { c.tone > 0 })
+        });
+
+        let mut output = Vec::new();
+        let mut backspace = 0;
+        if let Some(pos) = pos {
+            if let Some(c) = self.buf.get_mut(pos) {
+                if is_mark {
+                    c.mark = mark::NONE;
+                } else {
+                    c.tone = tone::NONE;
+                }
+            }
+            let rebuilt = self.rebuild_from(pos);
+            backspace = rebuilt.backspace;
+            output.extend(
+                rebuilt.chars[..rebuilt.count as usize]
+                    .iter()
+                    .filter_map(|&c| char::from_u32(c)),
+            );
+        }
+        output.extend(utils::key_to_char_ext(key, caps, shift));
+
+        self.clear();
+        self.word_history.clear();
+        self.spaces_after_commit = 0;
+        Result::send(backspace, &output)
+    }
+
     /// Try word boundary shortcuts (triggered by space, punctuation, etc.)
     /// The `trigger_char` is appended to the output (space for space, punctuation for punctuation)
     fn try_word_boundary_shortcut_with_char(&mut self, trigger_char: char) -> Result {
@@ -1678,6 +2156,14 @@ impl Engine {
                 // Only revertible if no mark applied - mark confirms Vietnamese intent
                 (0, is_d_vowels_only_pattern && !has_mark_applied)
             }
+        } else if keys::is_letter(key) {
+            // VIQR/custom letter stroke: only adjacent "dd" → "đ"
+            let last_pos = self.buf.len().checked_sub(1)?;
+            let last_char = self.buf.get(last_pos)?;
+            if last_char.key != keys::D || last_char.stroke {
+                return None;
+            }
+            (last_pos, false)
         } else {
             // VNI: Allow delayed stroke - find first un-stroked 'd' anywhere in buffer
             // '9' is always intentional stroke command, not a letter
@@ -2848,13 +3334,7 @@ impl Engine {
                         // This prevents "pasta" → "pất", "costa" → "côt", etc.
                         // The raw_input check works because English words like "pasta"
                         // are in our dictionary, while Vietnamese typing patterns are not.
-                        let raw_str: String = self
-                            .raw_input
-                            .iter()
-                            .filter_map(|&(k, caps, _)| utils::key_to_char(k, caps))
-                            .collect::<String>()
-                            .to_lowercase();
-                        if english_dict::is_english_word(&raw_str) {
+                        if self.raw_input_is_english() {
                             // Raw input is English - don't apply delayed circumflex
                             // Let the letter be added normally, auto-restore will handle it
                         } else {
@@ -3249,13 +3729,7 @@ impl Engine {
     /// Returns Some((old_pos, new_pos)) if tone was moved, None otherwise.
     fn reposition_tone_if_needed(&mut self) -> Option<(usize, usize)> {
         // Check if raw_input is an English word (used later with diphthong check)
-        let raw_str: String = self
-            .raw_input
-            .iter()
-            .filter_map(|&(k, caps, _)| utils::key_to_char(k, caps))
-            .collect::<String>()
-            .to_lowercase();
-        let is_english_word = english_dict::is_english_word(&raw_str);
+        let is_english_word = self.raw_input_is_english();
 
         // Find vowel with tone mark (sắc/huyền/hỏi/ngã/nặng)
         let tone_info: Option<(usize, u8)> = self
@@ -3424,13 +3898,7 @@ impl Engine {
         // This prevents corrupting English words like "vista" → "víat"
         // The auto-restore will handle restoring "vísta" to "vista" since it's invalid VN
         // But if we reorder, "víat" looks like valid VN structure and won't restore
-        let raw_str: String = self
-            .raw_input
-            .iter()
-            .filter_map(|&(key, caps, _)| utils::key_to_char(key, caps))
-            .collect::<String>()
-            .to_lowercase();
-        if english_dict::is_english_word(&raw_str) {
+        if self.raw_input_is_english() {
             return None;
         }
 
@@ -3827,13 +4295,7 @@ impl Engine {
                 // Skip circumflex if raw_input is an English word
                 // This prevents "pasta" → "pất", "costa" → "côt", etc.
                 // raw_input includes the current key (pushed before process() is called)
-                let raw_str: String = self
-                    .raw_input
-                    .iter()
-                    .filter_map(|&(k, caps, _)| utils::key_to_char(k, caps))
-                    .collect::<String>()
-                    .to_lowercase();
-                if english_dict::is_english_word(&raw_str) {
+                if self.raw_input_is_english() {
                     // Raw input is English - skip circumflex, add vowel normally
                     // The auto-restore will handle restoring the English word
                 } else {
@@ -4352,6 +4814,7 @@ impl Engine {
         self.telex_double_raw_len = 0;
         self.restored_pending_clear = false;
         self.restored_is_ascii = false;
+        self.restored_from_surrounding = false;
         self.shortcut_prefix.clear();
     }
 
@@ -4364,6 +4827,10 @@ impl Engine {
         self.clear();
         self.word_history.clear();
         self.spaces_after_commit = 0;
+        self.last_restored_word = None;
+        self.screen.clear();
+        self.preedit.clear();
+        self.caret_tail = 0;
         // Issue #274: Reset auto-capitalize state on cursor change
         // This prevents incorrect capitalization after copy-paste
         self.pending_capitalize = false;
@@ -4395,12 +4862,21 @@ impl Engine {
     /// Restore buffer from a Vietnamese word string
     ///
     /// Used when native app detects cursor at word boundary and wants to edit.
-    /// Parses Vietnamese characters back to buffer components.
+    /// Parses Vietnamese characters back to buffer components; precomposed
+    /// (NFC) and decomposed (NFD) letters are both accepted.
     pub fn restore_word(&mut self, word: &str) {
         self.clear();
+        self.caret_tail = 0;
         let mut is_ascii = true;
-        for c in word.chars() {
-            if let Some(parsed) = chars::parse_char(c) {
+        // Accept decomposed (NFD) words: each letter may span several chars
+        let mut rest = word;
+        while let Some(c) = rest.chars().next() {
+            let (parsed, len) = match chars::parse_grapheme(rest) {
+                Some((parsed, len)) => (Some(parsed), len),
+                None => (None, c.len_utf8()),
+            };
+            rest = &rest[len..];
+            if let Some(parsed) = parsed {
                 let mut ch = Char::new(parsed.key, parsed.caps);
                 ch.tone = parsed.tone;
                 ch.mark = parsed.mark;
@@ -4421,6 +4897,9 @@ impl Engine {
             self.restored_pending_clear = true;
             self.restored_is_ascii = is_ascii;
         }
+        if self.charset != Charset::Unicode {
+            self.screen = chars::compose(word).chars().collect();
+        }
     }
 
     /// Check if buffer has transforms and is invalid Vietnamese
@@ -4447,6 +4926,57 @@ impl Engine {
             return None;
         }
 
+        // User's lists: never-restore keystrokes are always meant as typed;
+        // user English words ("kubectl", "terraform") restore unless the result
+        // is a Vietnamese word
+        let raw_str = self.get_raw_input_string();
+        if english_dict::is_never_restore(&raw_str) {
+            return None;
+        }
+        if english_dict::is_user_word(&raw_str)
+            && !dictionary::is_vietnamese(&self.buf.to_full_string(), self.allow_foreign_consonants)
+        {
+            return self.build_raw_chars_exact();
+        }
+        // Learned from the user's corrections
+        if self.learn_auto_restore {
+            match self.learning.decision(&raw_str) {
+                Some(Learned::Restore) => return self.build_raw_chars_exact(),
+                Some(Learned::Keep) => return None,
+                None => {}
+            }
+        }
+        let restore = self.restore_by_spelling(is_word_complete);
+        self.restore_by_context(restore, &raw_str)
+    }
+
+    /// Neighbouring words decide words that are both Vietnamese and English:
+    /// after two English words the English spelling is restored ("play the
+    /// music as" → "as", not "á"); after two Vietnamese words the Vietnamese
+    /// result is kept ("anh ấy là refer" → "rể"). Words with a manual revert
+    /// ("tooo", "noww") keep the decision of the revert handling.
+    fn restore_by_context(&self, restore: Option<Vec<char>>, raw_str: &str) -> Option<Vec<char>> {
+        if self.had_mark_revert
+            || self.had_circumflex_revert
+            || self.stroke_reverted
+            || self.reverted_circumflex_key.is_some()
+        {
+            return restore;
+        }
+        let ambiguous = english_dict::is_english_word(raw_str)
+            && dictionary::is_vietnamese(&self.buf.to_full_string(), self.allow_foreign_consonants);
+        if !ambiguous {
+            return restore;
+        }
+        match self.word_history.context() {
+            Some(Lang::English) => self.build_raw_chars_exact(),
+            Some(Lang::Vietnamese) => None,
+            _ => restore,
+        }
+    }
+
+    /// Auto-restore decision from the word's spelling alone
+    fn restore_by_spelling(&self, is_word_complete: bool) -> Option<Vec<char>> {
         // Issue #211: Skip auto-restore for extended character patterns
         // When user types "ơiiiiii", "điiii", "ôiiii", "vàooooo", etc.
         // This is intentional Vietnamese (casual messaging) not English.
@@ -5300,6 +5830,16 @@ impl Engine {
             .to_lowercase()
     }
 
+    /// Check if raw_input spells an English word, without building a String
+    /// (checked on every mark/vowel key)
+    fn raw_input_is_english(&self) -> bool {
+        english_dict::is_english_chars(
+            self.raw_input
+                .iter()
+                .filter_map(|&(key, caps, _)| utils::key_to_char(key, caps)),
+        )
+    }
+
     /// Get raw_input as ASCII string preserving original case
     fn get_raw_input_string_preserve_case(&self) -> String {
         self.raw_input
@@ -7465,6 +8005,40 @@ impl Engine {
         Some(Result::send_consumed(0, &[vowel_char]))
     }
 
+    /// Language of the word being committed, as context for the next ones
+    fn committed_lang(&self, restored: bool) -> Lang {
+        if restored {
+            return Lang::English;
+        }
+        let word = self.buf.to_full_string();
+        if !word.is_ascii() {
+            return Lang::Vietnamese;
+        }
+        // Typed without any Vietnamese transform: not Vietnamese means English,
+        // dictionary word or not ("deploy", "kubectl")
+        match (
+            dictionary::is_vietnamese(&word, self.allow_foreign_consonants),
+            english_dict::is_english_word(&word),
+        ) {
+            (true, false) => Lang::Vietnamese,
+            (false, _) => Lang::English,
+            // Both ("an", "me", "the"): no evidence
+            (true, true) => Lang::Neutral,
+        }
+    }
+
+    /// Learning on word commit: a word committed after a manual mark revert
+    /// ("mixx" → "mix") is one auto-restore missed
+    fn learn_from_commit(&mut self, restored: bool) {
+        if !self.learn_auto_restore || restored || !self.had_mark_revert {
+            return;
+        }
+        let word = self.buf.to_full_string();
+        if word.is_ascii() {
+            self.learning.record(&word, Learned::Restore);
+        }
+    }
+
     /// Auto-restore invalid Vietnamese to raw English on space
     ///
     /// Called when SPACE is pressed. If buffer has transforms but result is not
@@ -0,0 +1,212 @@
+//! Preedit Mode - Composition string output
+//!
+//! Instead of "backspace N then insert chars", the current word is reported
+//! as a composing (preedit) string that the frontend draws inline, and text
+//! is committed explicitly when the word ends. This is how IBus/Fcitx input
+//! contexts work, and it avoids synthetic backspaces entirely (terminals,
+//! browser autocomplete, apps that ignore them).
+//!
+//! Keys go through the same path as `on_key_ext` (surrounding-text edits,
+//! suggestions, output charset), and the result is turned into composing and
+//! commit strings. Use either `on_key_preedit` or `on_key_ext` on an engine,
+//! not both: the composing string is only tracked by `on_key_preedit`.
+
+use super::buffer::MAX;
+use super::trace::KeyInput;
+use super::{Action, Engine, FLAG_DELETE_TAIL, FLAG_KEY_CONSUMED};
+use crate::data::keys;
+use crate::utils;
+
+/// Preedit result for FFI
+#[repr(C)]
+pub struct Preedit {
+    /// Composing string (UTF-32), replaces the previous one
+    pub preedit: [u32; MAX],
+    /// Text to commit before the composing string (UTF-32)
+    pub commit: [u32; MAX],
+    /// Correction to offer for the word just committed (UTF-32), see
+    /// `Action::Suggest`
+    pub suggestion: [u32; MAX],
+    pub preedit_len: u16,
+    /// Caret offset inside the composing string, in chars
+    pub cursor: u16,
+    pub commit_len: u16,
+    pub suggestion_len: u16,
+    /// Chars the suggestion replaces, ending just before the key's own char
+    /// in `commit`
+    pub suggestion_backspace: u16,
+    /// Chars of committed text to delete before the cursor first
+    /// (backspace into the previous word re-opens it for composing)
+    pub delete_before: u8,
+    /// Flags byte:
+    /// - bit 0 (0x01): key_consumed - if not set, also pass the key to the app
+    ///   (Enter, Tab, arrows, Ctrl shortcuts, Backspace with nothing composing)
+    /// - bit 1 (0x02): delete_tail - delete the word tail after the caret
+    ///   first (see `restore_surrounding`)
+    pub flags: u8,
+}
+
+impl Preedit {
+    fn new() -> Self {
+        Self {
+            preedit: [0; MAX],
+            commit: [0; MAX],
+            suggestion: [0; MAX],
+            preedit_len: 0,
+            cursor: 0,
+            commit_len: 0,
+            suggestion_len: 0,
+            suggestion_backspace: 0,
+            delete_before: 0,
+            flags: 0,
+        }
+    }
+
+    fn push_commit(&mut self, c: char) {
+        if (self.commit_len as usize) < MAX {
+            self.commit[self.commit_len as usize] = c as u32;
+            self.commit_len += 1;
+        }
+    }
+
+    fn set_preedit(&mut self, chars: &[char]) {
+        let len = chars.len().min(MAX);
+        for (slot, &c) in self.preedit.iter_mut().zip(&chars[..len]) {
+            *slot = c as u32;
+        }
+        self.preedit_len = len as u16;
+        self.cursor = len as u16;
+    }
+
+    fn set_suggestion(&mut self, backspace: u8, codes: &[u32]) {
+        let len = codes.len().min(MAX);
+        self.suggestion[..len].copy_from_slice(&codes[..len]);
+        self.suggestion_len = len as u16;
+        self.suggestion_backspace = backspace as u16;
+    }
+
+    /// Composing string
+    pub fn preedit_string(&self) -> String {
+        to_string(&self.preedit[..self.preedit_len as usize])
+    }
+
+    /// Text to commit
+    pub fn commit_string(&self) -> String {
+        to_string(&self.commit[..self.commit_len as usize])
+    }
+
+    /// Correction to offer for the committed word, empty if none
+    pub fn suggestion_string(&self) -> String {
+        to_string(&self.suggestion[..self.suggestion_len as usize])
+    }
+
+    /// Check if key was consumed (should not be passed through)
+    pub fn key_consumed(&self) -> bool {
+        self.flags & FLAG_KEY_CONSUMED != 0
+    }
+}
+
+fn to_string(codes: &[u32]) -> String {
+    codes.iter().filter_map(|&c| char::from_u32(c)).collect()
+}
+
+impl Engine {
+    /// Handle key event in preedit mode
+    ///
+    /// Same arguments as `on_key_ext`. Returns the composing string after the
+    /// key, plus a commit string when the word ends (break key, Ctrl, cursor
+    /// movement).
+    pub fn on_key_preedit(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Preedit {
+        let mut out = Preedit::new();
+        let r = self.process_key(key, caps, ctrl, shift);
+        self.record(
+            KeyInput {
+                key,
+                caps,
+                ctrl,
+                shift,
+                ch: None,
+            },
+            &r,
+        );
+        if ctrl {
+            self.commit_preedit(&mut out);
+            return out;
+        }
+        out.flags |= r.flags & FLAG_DELETE_TAIL;
+        if r.action == Action::Suggest as u8 {
+            // The key commits the word below, the suggestion is for it
+            out.set_suggestion(r.backspace, &r.chars[..r.count as usize]);
+        }
+
+        let sent = r.action == Action::Send as u8;
+        if sent {
+            for _ in 0..r.backspace {
+                if self.preedit.pop().is_none() {
+                    out.delete_before = out.delete_before.saturating_add(1);
+                }
+            }
+            self.preedit.extend(
+                r.chars[..r.count as usize]
+                    .iter()
+                    .filter_map(|&c| char::from_u32(c)),
+            );
+            if out.delete_before > 0 {
+                self.reopen_word(&mut out);
+            }
+        }
+
+        let is_break = keys::is_break_ext(key, shift);
+        // Same pass-through rules as the platform applies to `Result`
+        let passes = !r.key_consumed() && (!sent || (is_break && key != keys::SPACE));
+
+        if key == keys::DELETE {
+            if !sent && self.preedit.pop().is_none() {
+                // Nothing composing: the app deletes committed text itself
+                out.set_preedit(&self.preedit);
+                return out;
+            }
+        } else if passes {
+            let typed = match key {
+                keys::SPACE => Some(' '),
+                _ => utils::key_to_char_ext(key, caps, shift),
+            };
+            match typed {
+                Some(c) => self.preedit.push(c),
+                // Enter, Tab, ESC, arrows: commit, then let the app handle the key
+                None => {
+                    self.commit_preedit(&mut out);
+                    return out;
+                }
+            }
+        }
+
+        if is_break && self.buf.is_empty() {
+            self.commit_preedit(&mut out);
+        }
+        out.set_preedit(&self.preedit);
+        out.flags |= FLAG_KEY_CONSUMED;
+        out
+    }
+
+    /// Pull the rest of the current word out of the committed text
+    ///
+    /// A Send that reaches past the composing string (backspace after space
+    /// restores the previous word) only replaces the changed tail; compose the
+    /// whole word again so it can be edited inline.
+    fn reopen_word(&mut self, out: &mut Preedit) {
+        let word: Vec<char> = self.buf.to_full_string().chars().collect();
+        if word.len() > self.preedit.len() && word.ends_with(&self.preedit) {
+            let extra = word.len() - self.preedit.len();
+            out.delete_before = out.delete_before.saturating_add(extra as u8);
+            self.preedit = word;
+        }
+    }
+
+    /// Move the composing string to the commit string
+    fn commit_preedit(&mut self, out: &mut Preedit) {
+        for c in std::mem::take(&mut self.preedit) {
+            out.push_commit(c);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Profiles - Per-application engine settings
+//!
+//! A profile is a named bundle of every `Engine` setting: the options
+//! (`EngineConfig`) and the shortcut set. Apps are assigned to profiles by
+//! an app identifier (bundle id, process name, window class); `switch_app`
+//! applies the app's profile, and apps without one get the `DEFAULT_PROFILE`.
+//!
+//! Settings changed while an app is active (the user toggling Vietnamese off
+//! in a terminal) are saved back into its profile when switching away, so
+//! they are still there when the app comes back. The word being typed and
+//! the word history are cleared on every switch.
+
+use std::collections::HashMap;
+
+use super::config::EngineConfig;
+use super::shortcut::ShortcutTable;
+use super::Engine;
+
+/// Profile used by apps without an assigned one
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Every engine setting: options and shortcut set
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    pub config: EngineConfig,
+    pub shortcuts: ShortcutTable,
+}
+
+/// Defined profiles and app assignments of one engine
+#[derive(Debug, Default)]
+pub struct Profiles {
+    profiles: HashMap<String, Profile>,
+    /// App identifier → profile name
+    apps: HashMap<String, String>,
+    /// Profile applied by the last switch (None before the first)
+    active: Option<String>,
+}
+
+impl Profiles {
+    /// Profile by name
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.get(name)
+    }
+
+    /// Define or replace a profile
+    pub fn insert(&mut self, name: &str, profile: Profile) {
+        self.profiles.insert(name.to_string(), profile);
+    }
+
+    /// Remove a profile and its app assignments, returning it
+    pub fn remove(&mut self, name: &str) -> Option<Profile> {
+        self.apps.retain(|_, profile| profile != name);
+        if self.active.as_deref() == Some(name) {
+            self.active = None;
+        }
+        self.profiles.remove(name)
+    }
+
+    /// Assign an app to a profile (it may be defined later)
+    pub fn assign(&mut self, app: &str, name: &str) {
+        self.apps.insert(app.to_string(), name.to_string());
+    }
+
+    /// Drop an app's assignment: it goes back to the default profile
+    pub fn unassign(&mut self, app: &str) -> bool {
+        self.apps.remove(app).is_some()
+    }
+
+    /// Profile name used for an app
+    pub fn profile_for(&self, app: &str) -> &str {
+        self.apps.get(app).map_or(DEFAULT_PROFILE, String::as_str)
+    }
+
+    /// Profile applied by the last switch
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    /// Number of defined profiles
+    pub fn len(&self) -> usize {
+        self.profiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_empty()
+    }
+
+    /// Remove all profiles and assignments
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Engine {
+    /// Current settings as a profile
+    pub fn profile(&self) -> Profile {
+        Profile {
+            config: self.config(),
+            shortcuts: self.shortcuts.clone(),
+        }
+    }
+
+    /// Apply every setting of a profile and start a fresh word
+    pub fn apply_profile(&mut self, profile: &Profile) {
+        self.clear_all();
+        self.set_config(&profile.config);
+        self.shortcuts = profile.shortcuts.clone();
+    }
+
+    /// Save the current settings as profile `name`
+    pub fn save_profile(&mut self, name: &str) {
+        let profile = self.profile();
+        self.profiles.insert(name, profile);
+    }
+
+    pub fn profiles(&self) -> &Profiles {
+        &self.profiles
+    }
+
+    /// Define profiles and assign apps
+    pub fn profiles_mut(&mut self) -> &mut Profiles {
+        &mut self.profiles
+    }
+
+    /// Switch to the profile of app `app` (focus change)
+    ///
+    /// The settings of the profile being left are saved back into it first.
+    /// Without a `DEFAULT_PROFILE`, the settings at the first switch become
+    /// the default. Returns false if the app's profile isn't defined yet
+    /// (settings unchanged).
+    pub fn switch_app(&mut self, app: &str) -> bool {
+        let target = self.profiles.profile_for(app).to_string();
+        match self.profiles.active.take() {
+            Some(active) => {
+                let current = self.profile();
+                self.profiles.insert(&active, current);
+            }
+            None if self.profiles.get(DEFAULT_PROFILE).is_none() => {
+                self.save_profile(DEFAULT_PROFILE);
+            }
+            None => {}
+        }
+        let changed = match self.profiles.get(&target).cloned() {
+            Some(profile) => {
+                self.apply_profile(&profile);
+                true
+            }
+            // Assigned to a profile not defined yet: it starts from the
+            // current settings
+            None => {
+                self.clear_all();
+                false
+            }
+        };
+        self.profiles.active = Some(target);
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::charset::Charset;
+
+    #[test]
+    fn profile_round_trip() {
+        let mut e = Engine::new();
+        e.set_method(1);
+        e.set_enabled(false);
+        e.set_charset(Charset::Tcvn3);
+        let saved = e.profile();
+
+        let mut other = Engine::new();
+        other.apply_profile(&saved);
+        let applied = other.profile().config;
+        assert_eq!(applied.method, 1);
+        assert!(!applied.enabled);
+        assert_eq!(applied.charset, Charset::Tcvn3);
+    }
+
+    #[test]
+    fn remove_drops_assignments() {
+        let mut p = Profiles::default();
+        p.insert("games", Profile::default());
+        p.assign("steam", "games");
+        assert_eq!(p.profile_for("steam"), "games");
+        assert!(p.remove("games").is_some());
+        assert_eq!(p.profile_for("steam"), DEFAULT_PROFILE);
+    }
+}
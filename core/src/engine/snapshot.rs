@@ -0,0 +1,386 @@
+//! Snapshots - Park and resume the typing state of an engine
+//!
+//! `snapshot` captures everything about the word in progress and the words
+//! before it: buffer, raw keystrokes, last transform, the pending and
+//! per-word flags, auto-capitalize state, word history, preedit and the
+//! legacy-charset screen mirror. `restore_snapshot` puts it back, so a
+//! frontend can resume an input context after a focus change or a restart
+//! exactly where it left off.
+//!
+//! Options, shortcuts and profiles are not included (see `config`).
+//!
+//! Format: "GNS1", then the fields in a fixed order. Integers are little
+//! endian, lengths and positions u16, strings `[len: u16][UTF-8]`, options
+//! a 0/1 tag before the value.
+
+use super::buffer::{Buffer, Char, MAX};
+use super::{Engine, Lang, Transform, WordHistory};
+use crate::data::chars::{mark, tone};
+
+/// Format tag and version
+const MAGIC: &[u8; 4] = b"GNS1";
+
+impl Engine {
+    /// Serialize the typing state
+    pub fn snapshot(&self) -> Vec<u8> {
+        let mut w = Writer(MAGIC.to_vec());
+        w.buffer(&self.buf);
+        w.len(self.raw_input.len());
+        for &(key, caps, shift) in &self.raw_input {
+            w.u16(key);
+            w.bool(caps);
+            w.bool(shift);
+        }
+        w.transform(self.last_transform);
+        w.bool(self.has_non_letter_prefix);
+
+        // Word history, oldest first
+        let h = &self.word_history;
+        w.len(h.len);
+        for i in (1..=h.len).rev() {
+            let at = (h.head + super::HISTORY_CAPACITY - i) % super::HISTORY_CAPACITY;
+            w.buffer(&h.data[at]);
+            w.u8(h.langs[at] as u8);
+        }
+        w.u8(self.spaces_after_commit);
+        w.opt_str(self.last_restored_word.as_deref());
+
+        w.opt_len(self.pending_breve_pos);
+        w.opt_len(self.pending_u_horn_pos);
+        w.bool(self.stroke_reverted);
+        w.bool(self.had_mark_revert);
+        w.bool(self.pending_mark_revert_pop);
+        w.bool(self.had_any_transform);
+        w.bool(self.had_vowel_triggered_circumflex);
+        w.bool(self.had_circumflex_revert);
+        w.opt_u16(self.reverted_circumflex_key);
+        w.bool(self.had_telex_transform);
+        w.opt_str(self.telex_double_raw.as_deref());
+        w.len(self.telex_double_raw_len);
+        w.str(&self.shortcut_prefix);
+        w.bool(self.restored_pending_clear);
+        w.bool(self.restored_is_ascii);
+        w.bool(self.restored_from_surrounding);
+
+        w.bool(self.pending_capitalize);
+        w.bool(self.auto_capitalize_used);
+        w.bool(self.saw_sentence_ending);
+
+        w.chars(&self.screen);
+        w.chars(&self.preedit);
+        w.len(self.caret_tail);
+        w.bool(self.symbol_escape);
+        w.0
+    }
+
+    /// Restore the typing state from `snapshot` bytes
+    ///
+    /// Returns false (and leaves the state unchanged) if the data is
+    /// malformed or from another format version.
+    pub fn restore_snapshot(&mut self, bytes: &[u8]) -> bool {
+        let current = self.snapshot();
+        let restored = bytes
+            .strip_prefix(MAGIC)
+            .and_then(|rest| self.read_state(&mut Reader(rest)));
+        if restored.is_none() {
+            let _ = self.read_state(&mut Reader(&current[MAGIC.len()..]));
+        }
+        restored.is_some()
+    }
+
+    /// Read the fields written by `snapshot` (after the magic)
+    fn read_state(&mut self, r: &mut Reader) -> Option<()> {
+        self.buf = r.buffer()?;
+        let n = r.len()?;
+        self.raw_input.clear();
+        for _ in 0..n {
+            self.raw_input.push((r.u16()?, r.bool()?, r.bool()?));
+        }
+        self.last_transform = r.transform()?;
+        self.has_non_letter_prefix = r.bool()?;
+
+        self.word_history = WordHistory::new();
+        let n = r.len()?;
+        for _ in 0..n {
+            let buf = r.buffer()?;
+            let lang = match r.u8()? {
+                0 => Lang::English,
+                1 => Lang::Vietnamese,
+                2 => Lang::Neutral,
+                _ => return None,
+            };
+            self.word_history.push(buf, lang);
+        }
+        self.spaces_after_commit = r.u8()?;
+        self.last_restored_word = r.opt_str()?;
+
+        self.pending_breve_pos = r.opt_len()?;
+        self.pending_u_horn_pos = r.opt_len()?;
+        self.stroke_reverted = r.bool()?;
+        self.had_mark_revert = r.bool()?;
+        self.pending_mark_revert_pop = r.bool()?;
+        self.had_any_transform = r.bool()?;
+        self.had_vowel_triggered_circumflex = r.bool()?;
+        self.had_circumflex_revert = r.bool()?;
+        self.reverted_circumflex_key = r.opt_u16()?;
+        self.had_telex_transform = r.bool()?;
+        self.telex_double_raw = r.opt_str()?;
+        self.telex_double_raw_len = r.len()?;
+        self.shortcut_prefix = r.str()?;
+        self.restored_pending_clear = r.bool()?;
+        self.restored_is_ascii = r.bool()?;
+        self.restored_from_surrounding = r.bool()?;
+
+        self.pending_capitalize = r.bool()?;
+        self.auto_capitalize_used = r.bool()?;
+        self.saw_sentence_ending = r.bool()?;
+
+        self.screen = r.chars()?;
+        self.preedit = r.chars()?;
+        self.caret_tail = r.len()?;
+        self.symbol_escape = r.bool()?;
+        r.0.is_empty().then_some(())
+    }
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, v: u8) {
+        self.0.push(v);
+    }
+
+    fn bool(&mut self, v: bool) {
+        self.u8(v as u8);
+    }
+
+    fn u16(&mut self, v: u16) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    /// Lengths and positions (all below `MAX` or a few hundred)
+    fn len(&mut self, v: usize) {
+        self.u16(v.min(u16::MAX as usize) as u16);
+    }
+
+    fn opt_len(&mut self, v: Option<usize>) {
+        self.bool(v.is_some());
+        if let Some(v) = v {
+            self.len(v);
+        }
+    }
+
+    fn opt_u16(&mut self, v: Option<u16>) {
+        self.bool(v.is_some());
+        if let Some(v) = v {
+            self.u16(v);
+        }
+    }
+
+    fn str(&mut self, s: &str) {
+        self.len(s.len());
+        self.0.extend_from_slice(s.as_bytes());
+    }
+
+    fn opt_str(&mut self, s: Option<&str>) {
+        self.bool(s.is_some());
+        if let Some(s) = s {
+            self.str(s);
+        }
+    }
+
+    fn chars(&mut self, chars: &[char]) {
+        self.len(chars.len());
+        for &c in chars {
+            self.0.extend_from_slice(&(c as u32).to_le_bytes());
+        }
+    }
+
+    fn buffer(&mut self, buf: &Buffer) {
+        self.len(buf.len());
+        for c in buf.iter() {
+            self.u16(c.key);
+            self.bool(c.caps);
+            self.u8(c.tone);
+            self.u8(c.mark);
+            self.bool(c.stroke);
+        }
+    }
+
+    fn transform(&mut self, t: Option<Transform>) {
+        match t {
+            None => self.u8(0),
+            Some(Transform::Mark(key, value)) => {
+                self.u8(1);
+                self.u16(key);
+                self.u8(value);
+            }
+            Some(Transform::Tone(key, value)) => {
+                self.u8(2);
+                self.u16(key);
+                self.u8(value);
+            }
+            Some(Transform::Stroke(key)) => {
+                self.u8(3);
+                self.u16(key);
+            }
+            Some(Transform::ShortPatternStroke) => self.u8(4),
+            Some(Transform::DelayedCircumflex(key)) => {
+                self.u8(5);
+                self.u16(key);
+            }
+            Some(Transform::WAsVowel) => self.u8(6),
+            Some(Transform::WShortcutSkipped) => self.u8(7),
+            Some(Transform::BracketAsVowel) => self.u8(8),
+        }
+    }
+}
+
+/// Reads what `Writer` wrote; every read is None past the end or on a bad
+/// value
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Option<&[u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        match self.u8()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let b = self.take(2)?;
+        Some(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn len(&mut self) -> Option<usize> {
+        Some(self.u16()? as usize)
+    }
+
+    /// Position in the buffer
+    fn opt_len(&mut self) -> Option<Option<usize>> {
+        if !self.bool()? {
+            return Some(None);
+        }
+        let pos = self.len()?;
+        (pos < MAX).then_some(Some(pos))
+    }
+
+    fn opt_u16(&mut self) -> Option<Option<u16>> {
+        if !self.bool()? {
+            return Some(None);
+        }
+        Some(Some(self.u16()?))
+    }
+
+    fn str(&mut self) -> Option<String> {
+        let n = self.len()?;
+        let bytes = self.take(n)?;
+        std::str::from_utf8(bytes).ok().map(str::to_string)
+    }
+
+    fn opt_str(&mut self) -> Option<Option<String>> {
+        if !self.bool()? {
+            return Some(None);
+        }
+        Some(Some(self.str()?))
+    }
+
+    fn chars(&mut self) -> Option<Vec<char>> {
+        let n = self.len()?;
+        let mut chars = Vec::with_capacity(n);
+        for _ in 0..n {
+            let b = self.take(4)?;
+            chars.push(char::from_u32(u32::from_le_bytes([
+                b[0], b[1], b[2], b[3],
+            ]))?);
+        }
+        Some(chars)
+    }
+
+    fn buffer(&mut self) -> Option<Buffer> {
+        let n = self.len()?;
+        if n > MAX {
+            return None;
+        }
+        let mut buf = Buffer::new();
+        for _ in 0..n {
+            let mut c = Char::new(self.u16()?, self.bool()?);
+            c.tone = self.u8()?;
+            c.mark = self.u8()?;
+            c.stroke = self.bool()?;
+            if c.tone > tone::HORN || c.mark > mark::NANG {
+                return None;
+            }
+            buf.push(c);
+        }
+        Some(buf)
+    }
+
+    fn transform(&mut self) -> Option<Option<Transform>> {
+        let t = match self.u8()? {
+            0 => return Some(None),
+            1 => Transform::Mark(self.u16()?, self.u8()?),
+            2 => Transform::Tone(self.u16()?, self.u8()?),
+            3 => Transform::Stroke(self.u16()?),
+            4 => Transform::ShortPatternStroke,
+            5 => Transform::DelayedCircumflex(self.u16()?),
+            6 => Transform::WAsVowel,
+            7 => Transform::WShortcutSkipped,
+            8 => Transform::BracketAsVowel,
+            _ => return None,
+        };
+        Some(Some(t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_is_exact() {
+        let mut e = Engine::new();
+        e.set_auto_capitalize(true);
+        for key in [
+            crate::data::keys::T,
+            crate::data::keys::R,
+            crate::data::keys::A,
+        ] {
+            e.on_key(key, false, false);
+        }
+        let bytes = e.snapshot();
+        let mut other = Engine::new();
+        assert!(other.restore_snapshot(&bytes));
+        assert_eq!(other.snapshot(), bytes);
+    }
+
+    #[test]
+    fn malformed_leaves_state_unchanged() {
+        let mut e = Engine::new();
+        e.on_key(crate::data::keys::A, false, false);
+        let bytes = e.snapshot();
+        let mut other = Engine::new();
+        let empty = other.snapshot();
+        assert!(!other.restore_snapshot(&bytes[..bytes.len() - 1]));
+        assert!(!other.restore_snapshot(b"GNS9"));
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(!other.restore_snapshot(&trailing));
+        assert_eq!(other.snapshot(), empty);
+    }
+}
@@ -0,0 +1,132 @@
+//! Surrounding Text - Edit the word around the caret
+//!
+//! IBus and Wayland text-input report the text before and after the caret.
+//! `restore_surrounding` finds the word the caret touches and rebuilds the
+//! buffer from it, so a mark or tone key edits that word in place:
+//! "Viet|" + j → "Việt", "(Vi|et)" + j → "(Việt)".
+//!
+//! With the caret inside the word, results are re-based on the caret: edits
+//! before the caret leave the tail alone, edits that reach past it set
+//! `FLAG_DELETE_TAIL` so the frontend deletes the old tail first.
+
+use super::{Action, Engine, Result, FLAG_DELETE_TAIL};
+use crate::data::chars::{self, tone};
+use crate::data::keys;
+use crate::text::is_word_char;
+
+impl Engine {
+    /// Restore the word around the caret from surrounding text
+    ///
+    /// `before` / `after` are the text before and after the caret (any amount
+    /// of context). The word is the run of letters touching the caret; marks
+    /// and punctuation around it are left alone. Returns how many chars of
+    /// `after` belong to the word: the count to delete when a result carries
+    /// `FLAG_DELETE_TAIL`.
+    pub fn restore_surrounding(&mut self, before: &str, after: &str) -> usize {
+        let start = before
+            .char_indices()
+            .rev()
+            .take_while(|&(_, c)| is_word_char(c))
+            .last()
+            .map_or(before.len(), |(i, _)| i);
+        let end = after
+            .char_indices()
+            .find(|&(_, c)| !is_word_char(c))
+            .map_or(after.len(), |(i, _)| i);
+        let head = &before[start..];
+        let tail = &after[..end];
+
+        self.restore_word(&format!("{}{}", head, tail));
+        self.restored_from_surrounding = !self.buf.is_empty();
+        self.caret_tail = chars::compose(tail).chars().count();
+        if self.buf.is_empty() {
+            self.caret_tail = 0;
+        }
+        if self.charset != super::Charset::Unicode {
+            // Mirror everything before the caret, not just the word
+            let composed = chars::compose(before);
+            let skip = composed
+                .chars()
+                .count()
+                .saturating_sub(super::SCREEN_CAPACITY);
+            self.screen = composed.chars().skip(skip).collect();
+        }
+        tail.chars().count()
+    }
+
+    /// Handle a key while the caret is inside a restored word
+    ///
+    /// The engine computes results as if the caret were at the end of the
+    /// word; re-base them on the real caret position.
+    pub(super) fn handle_key_mid_word(
+        &mut self,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+    ) -> Result {
+        let old: Vec<char> = self.buf.to_full_string().chars().collect();
+        let tail = self.caret_tail.min(old.len());
+        let result = self.handle_key(key, caps, ctrl, shift);
+        if result.action != Action::Send as u8 {
+            // Typing or deleting at the caret splits the word: stop editing it
+            self.caret_tail = 0;
+            self.clear();
+            return result;
+        }
+
+        let caret = old.len() - tail;
+        let backspace = result.backspace as usize;
+        // Backspace beyond the word deletes text before it
+        let extra = backspace.saturating_sub(old.len());
+        let kept = old.len() - (backspace - extra);
+        let mut new: Vec<char> = old[..kept].to_vec();
+        new.extend(
+            result.chars[..result.count as usize]
+                .iter()
+                .filter_map(|&c| char::from_u32(c)),
+        );
+
+        let mut out = if kept <= caret && new.len() >= kept + tail && new.ends_with(&old[caret..]) {
+            // Edit lies before the caret: leave the tail alone
+            Result::send((caret - kept + extra) as u8, &new[kept..new.len() - tail])
+        } else {
+            // Edit reaches past the caret: replace the tail too, caret ends up
+            // after the word
+            let keep = kept.min(caret);
+            self.caret_tail = 0;
+            let mut r = Result::send((caret - keep + extra) as u8, &new[keep..]);
+            if tail > 0 {
+                r.flags |= FLAG_DELETE_TAIL;
+            }
+            r
+        };
+        out.flags |= result.flags;
+        if self.buf.is_empty() {
+            self.caret_tail = 0;
+        }
+        out
+    }
+
+    /// Add diacritics a restored word can't be without before a mark lands
+    ///
+    /// Text on screen may have been typed without diacritics. "ie"/"ye" only
+    /// exist as iê/yê in Vietnamese, so "Viet" + j gives "Việt", not "Viẹt".
+    /// Only used for surrounding words without any diacritics.
+    pub(super) fn complete_restored_vowels(&mut self) {
+        for i in 1..self.buf.len() {
+            let prev_is_i = self
+                .buf
+                .get(i - 1)
+                .is_some_and(|c| matches!(c.key, keys::I | keys::Y) && c.tone == 0);
+            if !prev_is_i {
+                continue;
+            }
+            if let Some(c) = self.buf.get_mut(i) {
+                if c.key == keys::E && c.tone == 0 {
+                    c.tone = tone::CIRCUMFLEX;
+                }
+            }
+        }
+    }
+}
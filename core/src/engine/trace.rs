@@ -0,0 +1,489 @@
+//! Traces - Record keystrokes and results, replay them to reproduce a bug
+//!
+//! While recording, every key passed to `on_key`, `on_key_ext`,
+//! `on_key_with_char`, `on_char` and `on_key_preedit` is logged with the
+//! `Result` it produced (for preedit keys, the one the composing string was
+//! built from).
+//! The trace starts with the options in effect (and the typing state, if a
+//! word or history was already there); options changed while recording are
+//! logged between the keys. `Trace::replay` runs the keys on a fresh engine,
+//! so a bug report carrying a trace reproduces exactly.
+//!
+//! Shortcuts are not recorded.
+//!
+//! Text format, one item per line (`#` comments and blank lines skipped):
+//!
+//! ```text
+//! method = telex
+//! english_auto_restore = true
+//! 17 -> send "t"
+//! 1 shift -> send bs=1 "Ấ"
+//! 9 U+221A -> none
+//! 49 -> restore bs=4 "test "
+//! ```
+//!
+//! `key = value` lines are options (see `config`); `state = <hex>` is a
+//! `snapshot` of the starting state. Key lines are the keycode, the `caps`,
+//! `ctrl` and `shift` modifiers and the `U+XXXX` char if any, then the
+//! result: action, `bs=` backspaces, `flags=` and the output chars.
+
+use std::fmt;
+
+use super::config::{ConfigError, EngineConfig};
+use super::{Action, Engine, Result};
+
+/// One key passed to the engine
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyInput {
+    pub key: u16,
+    pub caps: bool,
+    pub ctrl: bool,
+    pub shift: bool,
+    /// Character given with the key (`on_key_with_char`, `on_char` symbols)
+    pub ch: Option<char>,
+}
+
+/// What the engine returned for a key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Output {
+    pub action: u8,
+    pub backspace: u8,
+    pub flags: u8,
+    pub chars: String,
+}
+
+impl Output {
+    pub fn from_result(r: &Result) -> Self {
+        Self {
+            action: r.action,
+            backspace: r.backspace,
+            flags: r.flags,
+            chars: r.chars[..r.count as usize]
+                .iter()
+                .map(|&c| char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect(),
+        }
+    }
+}
+
+/// One recorded item
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    Key(KeyInput, Output),
+    /// Options changed to this
+    Config(EngineConfig),
+}
+
+/// Key differing between a trace and its replay
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    /// 1-based index among the keys
+    pub index: usize,
+    pub input: KeyInput,
+    pub expected: Output,
+    pub actual: Output,
+}
+
+/// Recorded keystrokes with their results
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trace {
+    /// Options at the start
+    pub config: EngineConfig,
+    /// Typing state at the start (`snapshot` bytes), None for a fresh engine
+    pub state: Option<Vec<u8>>,
+    pub steps: Vec<Step>,
+}
+
+impl Trace {
+    /// Read a trace written by `Display`
+    pub fn parse(text: &str) -> std::result::Result<Self, ConfigError> {
+        let mut trace = Trace::default();
+        // Options in effect at the current line
+        let mut config = EngineConfig::default();
+        let mut started = false;
+        for (i, line) in text.lines().enumerate() {
+            let error = |message: String| ConfigError {
+                line: i + 1,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((input, output)) = line.split_once("->") {
+                let step = Step::Key(
+                    parse_input(input).map_err(error)?,
+                    parse_output(output).map_err(error)?,
+                );
+                if !started {
+                    trace.config = config.clone();
+                    started = true;
+                }
+                trace.steps.push(step);
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(error(format!(
+                    "expected `key = value` or a key, got `{}`",
+                    line
+                )));
+            };
+            let (key, value) = (key.trim(), value.trim());
+            if key == "state" {
+                if started {
+                    return Err(error("`state` must come before the keys".to_string()));
+                }
+                let state = parse_hex(value).map_err(error)?;
+                if !Engine::new().restore_snapshot(&state) {
+                    return Err(error(
+                        "`state` is not a snapshot this engine can restore".to_string(),
+                    ));
+                }
+                trace.state = Some(state);
+                continue;
+            }
+            config.set(key, value).map_err(error)?;
+            if started {
+                trace.steps.push(Step::Config(config.clone()));
+            }
+        }
+        if !started {
+            trace.config = config;
+        }
+        Ok(trace)
+    }
+
+    /// Run the keys on a fresh engine, recording what it returns now
+    ///
+    /// `parse` rejects a `state` this engine can't restore; a trace built
+    /// with one by hand replays from the fresh state.
+    pub fn replay(&self) -> Trace {
+        let mut e = Engine::new();
+        e.set_config(&self.config);
+        if let Some(state) = &self.state {
+            let _ = e.restore_snapshot(state);
+        }
+        let steps = self
+            .steps
+            .iter()
+            .map(|step| match step {
+                Step::Key(input, _) => {
+                    let r = match input.ch {
+                        Some(ch) => e.on_key_with_char(
+                            input.key,
+                            input.caps,
+                            input.ctrl,
+                            input.shift,
+                            Some(ch),
+                        ),
+                        None => e.on_key_ext(input.key, input.caps, input.ctrl, input.shift),
+                    };
+                    Step::Key(*input, Output::from_result(&r))
+                }
+                Step::Config(config) => {
+                    e.set_config(config);
+                    step.clone()
+                }
+            })
+            .collect();
+        Trace {
+            config: self.config.clone(),
+            state: self.state.clone(),
+            steps,
+        }
+    }
+
+    /// Keys whose result differs on replay
+    pub fn mismatches(&self) -> Vec<Mismatch> {
+        let replayed = self.replay();
+        self.keys()
+            .zip(replayed.keys())
+            .enumerate()
+            .filter(|(_, ((_, expected), (_, actual)))| expected != actual)
+            .map(|(i, ((input, expected), (_, actual)))| Mismatch {
+                index: i + 1,
+                input: *input,
+                expected: expected.clone(),
+                actual: actual.clone(),
+            })
+            .collect()
+    }
+
+    /// Keys with their recorded results
+    pub fn keys(&self) -> impl Iterator<Item = (&KeyInput, &Output)> {
+        self.steps.iter().filter_map(|step| match step {
+            Step::Key(input, output) => Some((input, output)),
+            Step::Config(_) => None,
+        })
+    }
+}
+
+impl fmt::Display for Trace {
+    /// Options, starting state, then one line per key or option change
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.config)?;
+        if let Some(state) = &self.state {
+            write!(f, "state = ")?;
+            for b in state {
+                write!(f, "{:02x}", b)?;
+            }
+            writeln!(f)?;
+        }
+        let mut config = &self.config;
+        for step in &self.steps {
+            match step {
+                Step::Key(input, output) => writeln!(f, "{} -> {}", input, output)?,
+                Step::Config(next) => {
+                    // Only the options that changed
+                    for key in EngineConfig::keys() {
+                        let value = next.get(key);
+                        if value != config.get(key) {
+                            writeln!(f, "{} = {}", key, value.unwrap_or_default())?;
+                        }
+                    }
+                    config = next;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for KeyInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key)?;
+        for (on, name) in [
+            (self.caps, "caps"),
+            (self.ctrl, "ctrl"),
+            (self.shift, "shift"),
+        ] {
+            if on {
+                write!(f, " {}", name)?;
+            }
+        }
+        if let Some(ch) = self.ch {
+            write!(f, " U+{:04X}", ch as u32)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match ACTIONS.iter().find(|&&(_, a)| a == self.action) {
+            Some((name, _)) => write!(f, "{}", name)?,
+            None => write!(f, "{}", self.action)?,
+        }
+        if self.backspace > 0 {
+            write!(f, " bs={}", self.backspace)?;
+        }
+        if self.flags != 0 {
+            write!(f, " flags={}", self.flags)?;
+        }
+        if !self.chars.is_empty() {
+            write!(f, " {:?}", self.chars)?;
+        }
+        Ok(())
+    }
+}
+
+/// Action names
+const ACTIONS: &[(&str, u8)] = &[
+    ("none", Action::None as u8),
+    ("send", Action::Send as u8),
+    ("restore", Action::Restore as u8),
+    ("suggest", Action::Suggest as u8),
+];
+
+fn parse_input(text: &str) -> std::result::Result<KeyInput, String> {
+    let mut words = text.split_whitespace();
+    let key = words.next().unwrap_or_default();
+    let mut input = KeyInput {
+        key: key
+            .parse()
+            .map_err(|_| format!("expected a keycode, got `{}`", key))?,
+        caps: false,
+        ctrl: false,
+        shift: false,
+        ch: None,
+    };
+    for word in words {
+        match word {
+            "caps" => input.caps = true,
+            "ctrl" => input.ctrl = true,
+            "shift" => input.shift = true,
+            _ => {
+                input.ch = Some(
+                    word.strip_prefix("U+")
+                        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| format!("unknown modifier `{}`", word))?,
+                );
+            }
+        }
+    }
+    Ok(input)
+}
+
+fn parse_output(text: &str) -> std::result::Result<Output, String> {
+    let text = text.trim();
+    // Output chars are the quoted rest of the line
+    let (head, chars) = match text.find('"') {
+        Some(at) => (&text[..at], unquote(&text[at..])?),
+        None => (text, String::new()),
+    };
+    let mut words = head.split_whitespace();
+    let action = words.next().unwrap_or_default();
+    let mut output = Output {
+        action: ACTIONS
+            .iter()
+            .find(|&&(name, _)| name == action)
+            .map(|&(_, a)| a)
+            .or_else(|| action.parse().ok())
+            .ok_or_else(|| format!("unknown action `{}`", action))?,
+        backspace: 0,
+        flags: 0,
+        chars,
+    };
+    for word in words {
+        let (field, value) = match word.split_once('=') {
+            Some(("bs", value)) => (&mut output.backspace, value),
+            Some(("flags", value)) => (&mut output.flags, value),
+            _ => return Err(format!("unknown result field `{}`", word)),
+        };
+        *field = value
+            .parse()
+            .map_err(|_| format!("expected a number, got `{}`", word))?;
+    }
+    Ok(output)
+}
+
+/// Read a string quoted by `{:?}`
+fn unquote(text: &str) -> std::result::Result<String, String> {
+    let bad = || format!("bad string {}", text);
+    let inner = text
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(bad)?;
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        out.push(match chars.next().ok_or_else(bad)? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            'u' => {
+                let rest = chars.as_str();
+                let end = rest.find('}').ok_or_else(bad)?;
+                let code = rest
+                    .strip_prefix('{')
+                    .and_then(|_| u32::from_str_radix(&rest[1..end], 16).ok())
+                    .and_then(char::from_u32)
+                    .ok_or_else(bad)?;
+                chars = rest[end + 1..].chars();
+                code
+            }
+            c @ ('\\' | '"' | '\'') => c,
+            _ => return Err(bad()),
+        });
+    }
+    Ok(out)
+}
+
+fn parse_hex(text: &str) -> std::result::Result<Vec<u8>, String> {
+    text.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .filter(|b| b.len() == 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .ok_or_else(|| "`state` must be hex bytes".to_string())
+        })
+        .collect()
+}
+
+impl Engine {
+    /// Start recording keys (replacing a trace being recorded)
+    pub fn start_trace(&mut self) {
+        let state = self.snapshot();
+        let fresh = Engine::new().snapshot();
+        self.trace = Some(Trace {
+            config: self.config(),
+            state: (state != fresh).then_some(state),
+            steps: Vec::new(),
+        });
+    }
+
+    /// Stop recording, returning the trace (None if not recording)
+    pub fn stop_trace(&mut self) -> Option<Trace> {
+        self.trace.take()
+    }
+
+    /// Trace being recorded
+    pub fn trace(&self) -> Option<&Trace> {
+        self.trace.as_ref()
+    }
+
+    /// Log a key and its result, and options changed since the last key
+    pub(super) fn record(&mut self, input: KeyInput, result: &Result) {
+        if self.trace.is_none() {
+            return;
+        }
+        let config = self.config();
+        let Some(trace) = self.trace.as_mut() else {
+            return;
+        };
+        let last = trace
+            .steps
+            .iter()
+            .rev()
+            .find_map(|step| match step {
+                Step::Config(c) => Some(c),
+                Step::Key(..) => None,
+            })
+            .unwrap_or(&trace.config);
+        if *last != config {
+            trace.steps.push(Step::Config(config));
+        }
+        trace
+            .steps
+            .push(Step::Key(input, Output::from_result(result)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trip() {
+        let text = "\
+            method = vni\n\
+            17 -> send \"t\"\n\
+            1 caps shift -> send bs=1 flags=1 \"Ấ\\n\\u{301}\"\n\
+            free_tone = true\n\
+            9 ctrl U+221A -> none\n";
+        let trace = Trace::parse(text).unwrap();
+        assert_eq!(trace.config.method, 1);
+        assert_eq!(trace.steps.len(), 4);
+        assert_eq!(Trace::parse(&trace.to_string()), Ok(trace));
+    }
+
+    #[test]
+    fn parse_errors_have_lines() {
+        let err = Trace::parse("method = telex\n\n17 -> sent\n").unwrap_err();
+        assert_eq!(err.line, 3);
+        assert!(Trace::parse("17 -> none\nstate = 00\n").is_err());
+        // Hex that isn't a snapshot (or one from another version)
+        let err = Trace::parse("method = telex\nstate = 00ff\n17 -> none\n").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(Trace::parse("17 alt -> none\n").is_err());
+        assert!(Trace::parse("17 -> send \"t\n").is_err());
+    }
+}
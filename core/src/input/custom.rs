@@ -0,0 +1,531 @@
+//! Custom Input Methods
+//!
+//! Data-driven `Method` built from a small keymap spec, for variants like
+//! "Telex without z removal" or personal hybrid schemes:
+//!
+//! ```text
+//! # Telex without z removal
+//! [marks]
+//! s = sac
+//! f = huyen
+//! r = hoi
+//! x = nga
+//! j = nang
+//!
+//! [tones]
+//! a = circumflex a
+//! e = circumflex e
+//! o = circumflex o
+//! w = horn a o u
+//!
+//! [stroke]
+//! d
+//! ```
+//!
+//! - `[marks]`: `<key> = sac|huyen|hoi|nga|nang` (or 1-5)
+//! - `[tones]`: `<key> = circumflex|horn|breve <target vowels>`
+//! - `[stroke]`, `[remove]`: keys separated by whitespace
+//!
+//! Keys are single US-layout characters; letters are case-insensitive and
+//! shifted symbols (`^`, `~`, `?`) are distinct from their base key.
+//! Specs that bind a key twice or otherwise can't be typed unambiguously
+//! are rejected with a line-numbered `SpecError`.
+//!
+//! Registered methods get ids from `CUSTOM_BASE` and are resolved by
+//! `input::get` like the built-in ones.
+
+use super::{Method, ToneType};
+use crate::data::keys;
+use crate::utils::char_to_key_ext;
+use std::sync::{Mutex, OnceLock};
+
+/// First method id used for custom methods (0-2 are built in)
+pub const CUSTOM_BASE: u8 = 16;
+
+/// Maximum number of distinct custom methods per process
+pub const MAX_CUSTOM: usize = 32;
+
+/// Error from parsing or registering a keymap spec
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecError {
+    /// 1-based spec line (0 when the error is about the whole spec)
+    pub line: usize,
+    pub message: String,
+}
+
+impl SpecError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for SpecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}: {}", self.line, self.message)
+        } else {
+            f.write_str(&self.message)
+        }
+    }
+}
+
+/// What a key does
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Mark(u8),
+    Tone(ToneType, &'static [u16]),
+    Stroke,
+    Remove,
+}
+
+/// One key binding (`shift` only matters for non-letters)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Binding {
+    key: u16,
+    shift: bool,
+    action: Action,
+    ch: char,
+    line: usize,
+}
+
+/// Keymap-defined input method
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomMethod {
+    bindings: Vec<Binding>,
+}
+
+/// Tone target vowels in mask order (a=1, e=2, o=4, u=8)
+const TARGET_VOWELS: [(char, u16); 4] = [
+    ('a', keys::A),
+    ('e', keys::E),
+    ('o', keys::O),
+    ('u', keys::U),
+];
+
+/// Every target set as a static slice, indexed by vowel mask
+/// (`Method::tone_targets` returns `&'static`)
+const TARGET_SETS: [&[u16]; 16] = [
+    &[],
+    &[keys::A],
+    &[keys::E],
+    &[keys::A, keys::E],
+    &[keys::O],
+    &[keys::A, keys::O],
+    &[keys::E, keys::O],
+    &[keys::A, keys::E, keys::O],
+    &[keys::U],
+    &[keys::A, keys::U],
+    &[keys::E, keys::U],
+    &[keys::A, keys::E, keys::U],
+    &[keys::O, keys::U],
+    &[keys::A, keys::O, keys::U],
+    &[keys::E, keys::O, keys::U],
+    &[keys::A, keys::E, keys::O, keys::U],
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Marks,
+    Tones,
+    Stroke,
+    Remove,
+}
+
+impl CustomMethod {
+    /// Parse a keymap spec
+    pub fn parse(spec: &str) -> Result<Self, SpecError> {
+        let mut bindings: Vec<Binding> = Vec::new();
+        let mut section = None;
+
+        for (idx, raw) in spec.lines().enumerate() {
+            let line_no = idx + 1;
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = section_header(line) {
+                section = Some(match name {
+                    "marks" => Section::Marks,
+                    "tones" => Section::Tones,
+                    "stroke" => Section::Stroke,
+                    "remove" => Section::Remove,
+                    _ => {
+                        return Err(SpecError::new(
+                            line_no,
+                            format!(
+                                "unknown section [{}] (expected marks, tones, stroke or remove)",
+                                name
+                            ),
+                        ))
+                    }
+                });
+                continue;
+            }
+
+            let Some(section) = section else {
+                return Err(SpecError::new(
+                    line_no,
+                    "expected a section header like [marks] before bindings",
+                ));
+            };
+
+            let entries = match section {
+                Section::Marks | Section::Tones => {
+                    let (ch, value) = split_binding(line).ok_or_else(|| {
+                        SpecError::new(
+                            line_no,
+                            format!("expected `<key> = <value>`, got `{}`", line),
+                        )
+                    })?;
+                    let action = if section == Section::Marks {
+                        parse_mark(value).map_err(|m| SpecError::new(line_no, m))?
+                    } else {
+                        parse_tone(value).map_err(|m| SpecError::new(line_no, m))?
+                    };
+                    vec![(ch, action)]
+                }
+                Section::Stroke | Section::Remove => {
+                    let action = if section == Section::Stroke {
+                        Action::Stroke
+                    } else {
+                        Action::Remove
+                    };
+                    line.split_whitespace()
+                        .map(|token| {
+                            let mut chars = token.chars();
+                            match (chars.next(), chars.next()) {
+                                (Some(ch), None) => Ok((ch, action)),
+                                _ => Err(SpecError::new(
+                                    line_no,
+                                    format!("expected single-character keys, got `{}`", token),
+                                )),
+                            }
+                        })
+                        .collect::<Result<Vec<_>, _>>()?
+                }
+            };
+
+            for (ch, action) in entries {
+                let binding = make_binding(ch, action, line_no)?;
+                check_conflicts(&bindings, &binding)?;
+                bindings.push(binding);
+            }
+        }
+
+        if bindings.is_empty() {
+            return Err(SpecError::new(0, "spec defines no keys"));
+        }
+        Ok(Self { bindings })
+    }
+
+    /// Binding for a key press (shift is ignored for letters)
+    fn find(&self, key: u16, shift: bool) -> Option<&Binding> {
+        self.bindings
+            .iter()
+            .find(|b| b.key == key && (b.shift == shift || keys::is_letter(key)))
+    }
+}
+
+impl Method for CustomMethod {
+    fn mark(&self, key: u16) -> Option<u8> {
+        self.mark_with_shift(key, false)
+    }
+
+    fn tone(&self, key: u16) -> Option<ToneType> {
+        self.tone_with_shift(key, false)
+    }
+
+    fn tone_targets(&self, key: u16) -> &'static [u16] {
+        // At most one tone binding per physical key (checked in parse)
+        self.bindings
+            .iter()
+            .find_map(|b| match b.action {
+                Action::Tone(_, targets) if b.key == key => Some(targets),
+                _ => None,
+            })
+            .unwrap_or(&[])
+    }
+
+    fn stroke(&self, key: u16) -> bool {
+        matches!(self.find(key, false), Some(b) if b.action == Action::Stroke)
+    }
+
+    fn remove(&self, key: u16) -> bool {
+        matches!(self.find(key, false), Some(b) if b.action == Action::Remove)
+    }
+
+    fn mark_with_shift(&self, key: u16, shift: bool) -> Option<u8> {
+        match self.find(key, shift)?.action {
+            Action::Mark(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    fn tone_with_shift(&self, key: u16, shift: bool) -> Option<ToneType> {
+        match self.find(key, shift)?.action {
+            Action::Tone(t, _) => Some(t),
+            _ => None,
+        }
+    }
+}
+
+/// `[name]` → `name` (a line like `[ = horn o` is a binding, not a header)
+fn section_header(line: &str) -> Option<&str> {
+    let name = line.strip_prefix('[')?.strip_suffix(']')?.trim();
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphabetic()) {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+/// `<key> = <value>` → (key, value); the key may itself be `=`
+fn split_binding(line: &str) -> Option<(char, &str)> {
+    let mut chars = line.chars();
+    let key = chars.next()?;
+    let value = chars.as_str().trim_start().strip_prefix('=')?.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some((key, value))
+    }
+}
+
+fn parse_mark(value: &str) -> Result<Action, String> {
+    let mark = match value.to_lowercase().as_str() {
+        "sac" | "sắc" | "1" => 1,
+        "huyen" | "huyền" | "2" => 2,
+        "hoi" | "hỏi" | "3" => 3,
+        "nga" | "ngã" | "4" => 4,
+        "nang" | "nặng" | "5" => 5,
+        _ => {
+            return Err(format!(
+                "unknown mark `{}` (expected sac, huyen, hoi, nga or nang)",
+                value
+            ))
+        }
+    };
+    Ok(Action::Mark(mark))
+}
+
+fn parse_tone(value: &str) -> Result<Action, String> {
+    let mut parts = value.split(|c: char| c.is_whitespace() || c == ',');
+    let name = parts.next().unwrap_or_default().to_lowercase();
+    let (tone, allowed): (ToneType, &[char]) = match name.as_str() {
+        "circumflex" => (ToneType::Circumflex, &['a', 'e', 'o']),
+        "horn" => (ToneType::Horn, &['a', 'o', 'u']),
+        "breve" => (ToneType::Breve, &['a']),
+        _ => {
+            return Err(format!(
+                "unknown tone `{}` (expected circumflex, horn or breve)",
+                name
+            ))
+        }
+    };
+
+    let mut mask = 0;
+    for target in parts.filter(|p| !p.is_empty()) {
+        let mut chars = target.chars();
+        let v = match (chars.next(), chars.next()) {
+            (Some(v), None) => v.to_ascii_lowercase(),
+            _ => return Err(format!("expected single-vowel targets, got `{}`", target)),
+        };
+        if !allowed.contains(&v) {
+            return Err(format!("{} cannot apply to `{}`", name, v));
+        }
+        let bit = TARGET_VOWELS.iter().position(|&(c, _)| c == v).unwrap_or(0);
+        mask |= 1 << bit;
+    }
+    if mask == 0 {
+        return Err(format!("{} needs at least one target vowel", name));
+    }
+    Ok(Action::Tone(tone, TARGET_SETS[mask]))
+}
+
+fn make_binding(ch: char, action: Action, line: usize) -> Result<Binding, SpecError> {
+    let key = if ch.is_ascii_graphic() {
+        char_to_key_ext(ch)
+    } else {
+        None
+    };
+    let Some((key, _, shift)) = key else {
+        return Err(SpecError::new(
+            line,
+            format!("`{}` is not a key on the US layout", ch.escape_default()),
+        ));
+    };
+
+    let is_break = keys::is_break_ext(key, shift);
+    match action {
+        Action::Mark(_) if keys::is_vowel(key) => {
+            return Err(SpecError::new(
+                line,
+                format!("mark key `{}` is a vowel and could not be typed", ch),
+            ))
+        }
+        Action::Stroke | Action::Remove if is_break || keys::is_vowel(key) => {
+            return Err(SpecError::new(
+                line,
+                format!("stroke/remove key `{}` must be a consonant or digit", ch),
+            ))
+        }
+        _ => {}
+    }
+
+    Ok(Binding {
+        key,
+        shift: shift && !keys::is_letter(key),
+        action,
+        ch: ch.to_ascii_lowercase(),
+        line,
+    })
+}
+
+/// Reject a binding that makes a key press ambiguous
+fn check_conflicts(bindings: &[Binding], new: &Binding) -> Result<(), SpecError> {
+    for b in bindings {
+        if b.key == new.key && b.shift == new.shift {
+            return Err(SpecError::new(
+                new.line,
+                format!("key `{}` is already bound on line {}", new.ch, b.line),
+            ));
+        }
+        // tone_targets() has no shift, so `6` and `^` can't both be tones
+        if b.key == new.key
+            && matches!(b.action, Action::Tone(..))
+            && matches!(new.action, Action::Tone(..))
+        {
+            return Err(SpecError::new(
+                new.line,
+                format!(
+                    "tone keys `{}` and `{}` (line {}) share a physical key",
+                    new.ch, b.ch, b.line
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// ============================================================
+// Registry
+// ============================================================
+
+/// Registered methods, immutable once set (lock-free lookup per key)
+static SLOTS: [OnceLock<CustomMethod>; MAX_CUSTOM] = [const { OnceLock::new() }; MAX_CUSTOM];
+
+/// Serializes registration only
+static REGISTER: Mutex<()> = Mutex::new(());
+
+/// Parse a spec and register it, returning its method id
+///
+/// Registering an identical spec again returns the existing id.
+pub fn register(spec: &str) -> Result<u8, SpecError> {
+    let method = CustomMethod::parse(spec)?;
+    let _guard = REGISTER.lock().unwrap_or_else(|e| e.into_inner());
+
+    for (i, slot) in SLOTS.iter().enumerate() {
+        match slot.get() {
+            Some(existing) if *existing == method => return Ok(CUSTOM_BASE + i as u8),
+            Some(_) => continue,
+            None => {
+                let _ = slot.set(method);
+                return Ok(CUSTOM_BASE + i as u8);
+            }
+        }
+    }
+    Err(SpecError::new(
+        0,
+        format!("too many custom methods (max {})", MAX_CUSTOM),
+    ))
+}
+
+/// Look up a registered custom method by id
+pub fn get(id: u8) -> Option<&'static CustomMethod> {
+    let idx = id.checked_sub(CUSTOM_BASE)? as usize;
+    SLOTS.get(idx)?.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TELEX_NO_Z: &str = "
+        # Telex without z removal
+        [marks]
+        s = sac
+        f = huyen
+        r = hoi
+        x = nga
+        j = nang
+
+        [tones]
+        a = circumflex a
+        e = circumflex e
+        o = circumflex o
+        w = horn a o u
+
+        [stroke]
+        d
+    ";
+
+    #[test]
+    fn test_parse_telex_like() {
+        let m = CustomMethod::parse(TELEX_NO_Z).unwrap();
+        assert_eq!(m.mark(keys::S), Some(1));
+        assert_eq!(m.mark(keys::J), Some(5));
+        assert_eq!(m.tone(keys::A), Some(ToneType::Circumflex));
+        assert_eq!(m.tone_targets(keys::W), &[keys::A, keys::O, keys::U]);
+        assert!(m.stroke(keys::D));
+        assert!(!m.remove(keys::Z));
+    }
+
+    #[test]
+    fn test_parse_symbols() {
+        let m =
+            CustomMethod::parse("[tones]\n[ = horn o\n] = horn u\n^ = circumflex a e o").unwrap();
+        assert_eq!(
+            m.tone_with_shift(keys::LBRACKET, false),
+            Some(ToneType::Horn)
+        );
+        assert_eq!(m.tone_targets(keys::RBRACKET), &[keys::U]);
+        assert_eq!(
+            m.tone_with_shift(keys::N6, true),
+            Some(ToneType::Circumflex)
+        );
+        assert_eq!(m.tone_with_shift(keys::N6, false), None);
+    }
+
+    #[test]
+    fn test_rejects_ambiguous_specs() {
+        let err = |spec: &str| CustomMethod::parse(spec).unwrap_err();
+
+        assert_eq!(
+            err("[marks]\ns = sac\n[tones]\ns = horn o").to_string(),
+            "line 4: key `s` is already bound on line 2"
+        );
+        assert_eq!(err("[marks]\nS = sac\ns = hoi").line, 3);
+        assert_eq!(err("[tones]\n6 = horn o\n^ = circumflex a").line, 3);
+        assert_eq!(err("[tones]\nw = circumflex u").line, 2);
+        assert_eq!(err("[marks]\na = sac").line, 2);
+        assert_eq!(err("[stroke]\n-").line, 2);
+        assert_eq!(err("s = sac").line, 1);
+        assert_eq!(err("[keys]").line, 1);
+        assert_eq!(err("[marks]\né = sac").line, 2);
+        assert_eq!(err("# empty").to_string(), "spec defines no keys");
+    }
+
+    #[test]
+    fn test_register_dedupes() {
+        let a = register(TELEX_NO_Z).unwrap();
+        let b = register(TELEX_NO_Z).unwrap();
+        assert_eq!(a, b);
+        assert!(a >= CUSTOM_BASE);
+        assert!(get(a).is_some());
+        assert!(get(CUSTOM_BASE - 1).is_none());
+    }
+}
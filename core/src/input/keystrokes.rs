@@ -0,0 +1,97 @@
+//! Keystrokes - Vietnamese text back to input method keys
+//!
+//! The inverse of the engine: "nguyễn" → `nguyeenx` (Telex) or `nguye6n4`
+//! (VNI). Letters are split with `chars::parse_char`, and each diacritic is
+//! looked up in the method's own key mapping, so VIQR and custom keymaps work
+//! too. Use `round_trips` to check that the engine types the word back.
+
+use super::{get, Method, ToneType};
+use crate::convert;
+use crate::data::chars::{self, mark, tone};
+use crate::data::keys;
+use crate::engine::Engine;
+use crate::utils;
+
+/// Where the mark (sắc, huyền, hỏi, ngã, nặng) key goes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// Mark key at the end of the word: `nguyeenx`, `nguye6n4`
+    MarkAtEnd,
+    /// Mark key right after its vowel: `nguyeexn`, `nguye64n`
+    Inline,
+}
+
+/// Chars tried as modifier keys, in order of preference
+const CANDIDATES: &str = "abcdefghijklmnopqrstuvwxyz0123456789'`?~.^+(*)[]{}-=;,/\\\"!@#$%&_:<>|";
+
+/// Keystrokes that type `word` with input method `method`
+///
+/// Returns None when `word` has characters other than Vietnamese letters, or
+/// the method has no key for one of its diacritics.
+pub fn keystrokes_for(word: &str, method: u8, style: Style) -> Option<String> {
+    let m = get(method);
+    let mut out = String::with_capacity(word.len() * 2);
+    let mut end_mark = None;
+    for c in word.chars() {
+        let p = chars::parse_char(c)?;
+        let base = utils::key_to_char(p.key, p.caps)?;
+        out.push(base);
+        if p.stroke {
+            out.push(cased(find_key(|k, _| m.stroke(k))?, p.caps));
+        }
+        if p.tone != tone::NONE {
+            out.push(cased(tone_key(m, p.key, p.tone)?, p.caps));
+        }
+        if p.mark != mark::NONE {
+            let key = cased(
+                find_key(|k, shift| m.mark_with_shift(k, shift) == Some(p.mark))?,
+                p.caps,
+            );
+            match style {
+                Style::Inline => out.push(key),
+                Style::MarkAtEnd => end_mark = Some(key),
+            }
+        }
+    }
+    out.extend(end_mark);
+    Some(out)
+}
+
+/// Check if typing `keystrokes_for(word, ..)` gives `word` back
+pub fn round_trips(word: &str, method: u8, style: Style) -> bool {
+    let Some(input) = keystrokes_for(word, method, style) else {
+        return false;
+    };
+    let mut e = Engine::new();
+    e.set_method(method);
+    convert::convert_word(&mut e, &input)
+        .into_iter()
+        .eq(word.chars())
+}
+
+/// Key that puts tone `t` (circumflex/horn) on vowel `key`
+fn tone_key(m: &dyn Method, key: u16, t: u8) -> Option<char> {
+    find_key(|k, shift| {
+        m.tone_with_shift(k, shift).is_some_and(|tt| match tt {
+            ToneType::Circumflex => t == tone::CIRCUMFLEX,
+            // ă is stored as horn on a
+            ToneType::Horn | ToneType::Breve => t == tone::HORN,
+        }) && m.tone_targets(k).contains(&key)
+    })
+}
+
+/// First candidate char whose key matches
+fn find_key(matches: impl Fn(u16, bool) -> bool) -> Option<char> {
+    CANDIDATES.chars().find(|&c| {
+        utils::char_to_key_ext(c).is_some_and(|(k, _, shift)| k != keys::SPACE && matches(k, shift))
+    })
+}
+
+/// Modifier letters follow the case of the letter they modify
+fn cased(c: char, caps: bool) -> char {
+    if caps {
+        c.to_ascii_uppercase()
+    } else {
+        c
+    }
+}
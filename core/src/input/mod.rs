@@ -3,10 +3,16 @@
 //! Defines key mappings for Vietnamese input methods.
 //! Engine handles all pattern matching based on buffer scan.
 
+pub mod custom;
+pub mod keystrokes;
 pub mod telex;
+pub mod viqr;
 pub mod vni;
 
+pub use custom::CustomMethod;
+pub use keystrokes::{keystrokes_for, round_trips, Style};
 pub use telex::Telex;
+pub use viqr::Viqr;
 pub use vni::Vni;
 
 use crate::data::chars::tone;
@@ -25,7 +31,7 @@ pub enum ToneType {
     Circumflex,
     /// Horn: ơ, ư (and ă for Telex)
     Horn,
-    /// Breve: ă (VNI only)
+    /// Breve: ă (VNI/VIQR only)
     Breve,
 }
 
@@ -58,16 +64,36 @@ pub trait Method {
 
     /// Check if key removes diacritics
     fn remove(&self, key: u16) -> bool;
+
+    /// Shift-aware `mark` for methods whose modifiers are shifted symbols
+    /// (VIQR: ` is huyền, ~ is ngã). Letter/number methods ignore shift.
+    fn mark_with_shift(&self, key: u16, _shift: bool) -> Option<u8> {
+        self.mark(key)
+    }
+
+    /// Shift-aware `tone` (VIQR: ^ is tone, 6 is a digit)
+    fn tone_with_shift(&self, key: u16, _shift: bool) -> Option<ToneType> {
+        self.tone(key)
+    }
 }
 
 /// Static method instances (zero-sized types, no heap allocation)
 static TELEX: Telex = Telex;
 static VNI: Vni = Vni;
+static VIQR: Viqr = Viqr;
 
 /// Get method by id (returns static reference, no allocation)
+///
+/// 0=Telex, 1=VNI, 2=VIQR, `custom::CUSTOM_BASE`.. for registered keymaps
+/// (unknown ids fall back to Telex)
 pub fn get(id: u8) -> &'static dyn Method {
     match id {
         1 => &VNI,
+        2 => &VIQR,
+        id if id >= custom::CUSTOM_BASE => match custom::get(id) {
+            Some(m) => m,
+            None => &TELEX,
+        },
         _ => &TELEX,
     }
 }
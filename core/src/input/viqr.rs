@@ -0,0 +1,105 @@
+//! VIQR Input Method
+//!
+//! Key mappings (US layout symbols):
+//! - Marks: '=sắc, `=huyền, ?=hỏi, ~=ngã, .=nặng
+//! - Tones: ^=circumflex, + or *=horn, (=breve
+//! - Stroke: dd
+//!
+//! The modifier symbols are break keys in the other methods, so the engine
+//! routes them through a dedicated path: they only transform the word when
+//! there is something to modify, and `?`/`.` only right after a vowel of a
+//! word without a mark (else they end the sentence). `\` right after a word
+//! types the next symbol literally ("nhie^u\?" → "nhiêu?").
+
+use super::{Method, ToneType, BREVE_TARGETS, CIRCUMFLEX_TARGETS, HORN_TARGETS_VNI};
+use crate::data::keys;
+
+pub struct Viqr;
+
+impl Method for Viqr {
+    fn mark(&self, key: u16) -> Option<u8> {
+        self.mark_with_shift(key, false)
+    }
+
+    fn tone(&self, key: u16) -> Option<ToneType> {
+        self.tone_with_shift(key, false)
+    }
+
+    fn tone_targets(&self, key: u16) -> &'static [u16] {
+        match key {
+            keys::N6 => CIRCUMFLEX_TARGETS,
+            keys::EQUAL | keys::N8 => HORN_TARGETS_VNI,
+            keys::N9 => BREVE_TARGETS,
+            _ => &[],
+        }
+    }
+
+    fn stroke(&self, key: u16) -> bool {
+        key == keys::D
+    }
+
+    fn remove(&self, _key: u16) -> bool {
+        false
+    }
+
+    fn mark_with_shift(&self, key: u16, shift: bool) -> Option<u8> {
+        match (key, shift) {
+            (keys::QUOTE, false) => Some(1),     // ' sắc
+            (keys::BACKQUOTE, false) => Some(2), // ` huyền
+            (keys::SLASH, true) => Some(3),      // ? hỏi
+            (keys::BACKQUOTE, true) => Some(4),  // ~ ngã
+            (keys::DOT, false) => Some(5),       // . nặng
+            _ => None,
+        }
+    }
+
+    fn tone_with_shift(&self, key: u16, shift: bool) -> Option<ToneType> {
+        if !shift {
+            return None;
+        }
+        match key {
+            keys::N6 => Some(ToneType::Circumflex),         // ^
+            keys::EQUAL | keys::N8 => Some(ToneType::Horn), // + *
+            keys::N9 => Some(ToneType::Breve),              // (
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marks() {
+        let v = Viqr;
+        assert_eq!(v.mark_with_shift(keys::QUOTE, false), Some(1));
+        assert_eq!(v.mark_with_shift(keys::BACKQUOTE, false), Some(2));
+        assert_eq!(v.mark_with_shift(keys::SLASH, true), Some(3));
+        assert_eq!(v.mark_with_shift(keys::BACKQUOTE, true), Some(4));
+        assert_eq!(v.mark_with_shift(keys::DOT, false), Some(5));
+        assert_eq!(v.mark_with_shift(keys::SLASH, false), None); // '/'
+        assert_eq!(v.mark(keys::S), None);
+    }
+
+    #[test]
+    fn test_tones() {
+        let v = Viqr;
+        assert_eq!(
+            v.tone_with_shift(keys::N6, true),
+            Some(ToneType::Circumflex)
+        );
+        assert_eq!(v.tone_with_shift(keys::EQUAL, true), Some(ToneType::Horn));
+        assert_eq!(v.tone_with_shift(keys::N8, true), Some(ToneType::Horn));
+        assert_eq!(v.tone_with_shift(keys::N9, true), Some(ToneType::Breve));
+        assert_eq!(v.tone_with_shift(keys::N6, false), None); // '6'
+        assert_eq!(v.tone_with_shift(keys::EQUAL, false), None); // '='
+    }
+
+    #[test]
+    fn test_stroke() {
+        let v = Viqr;
+        assert!(v.stroke(keys::D));
+        assert!(!v.stroke(keys::N9));
+    }
+}
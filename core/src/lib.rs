@@ -2,12 +2,15 @@
 //!
 //! Simple Vietnamese input method engine supporting Telex and VNI.
 //!
+//! The C declarations for everything below live in `core/include/gonhanh.h`,
+//! generated from these definitions (see [`abi`]).
+//!
 //! # FFI Usage
 //!
 //! ```c
 //! // Initialize once at app start
 //! ime_init();
-//! ime_method(0);  // 0=Telex, 1=VNI
+//! ime_method(0);  // 0=Telex, 1=VNI, 2=VIQR
 //!
 //! // Process each keystroke
 //! ImeResult* r = ime_key(keycode, is_shift, is_ctrl);
@@ -19,14 +22,32 @@
 //! // Clean up on word boundary
 //! ime_clear();
 //! ```
+//!
+//! # Multiple Instances
+//!
+//! The `ime_*` functions above drive one global engine. To keep separate
+//! state per input context (window, text field), create engine handles:
+//!
+//! ```c
+//! void* h = ime_engine_new();
+//! ime_engine_method(h, 0);
+//! ImeResult* r = ime_engine_key_ext(h, keycode, caps, ctrl, shift);
+//! ime_free(r);
+//! ime_engine_free(h);
+//! ```
 
+pub mod abi;
+pub mod convert;
 pub mod data;
 pub mod engine;
 pub mod input;
+pub mod text;
 pub mod updater;
 pub mod utils;
 
-use engine::{Engine, Result};
+use data::charset::Charset;
+use data::user_dict::{self, WordList};
+use engine::{config::EngineConfig, preedit::Preedit, Engine, Result};
 use std::sync::Mutex;
 
 // Global engine instance (thread-safe via Mutex)
@@ -37,6 +58,26 @@ fn lock_engine() -> std::sync::MutexGuard<'static, Option<Engine>> {
     ENGINE.lock().unwrap_or_else(|e| e.into_inner())
 }
 
+/// Borrow a C string argument as UTF-8 (None for null or invalid UTF-8)
+///
+/// # Safety
+/// `ptr` must be null or a valid null-terminated string that outlives `'a`.
+unsafe fn c_str<'a>(ptr: *const std::os::raw::c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    std::ffi::CStr::from_ptr(ptr).to_str().ok()
+}
+
+/// Convert FFI char code to char (0 means "no character")
+fn char_from_code(char_code: u32) -> Option<char> {
+    if char_code > 0 {
+        char::from_u32(char_code)
+    } else {
+        None
+    }
+}
+
 // ============================================================
 // FFI Interface
 // ============================================================
@@ -54,6 +95,30 @@ pub extern "C" fn ime_init() {
     *guard = Some(Engine::new());
 }
 
+/// Get the ABI version of this library.
+///
+/// Compare with `GONHANH_ABI_VERSION` from `gonhanh.h` at startup and refuse
+/// to use the engine on mismatch (stale library or stale header).
+#[no_mangle]
+pub extern "C" fn ime_abi_version() -> u32 {
+    abi::ABI_VERSION
+}
+
+/// Get `sizeof(Result)` as laid out by this library.
+///
+/// Compare with `sizeof(ImeResult)` on the platform side to detect
+/// a struct declared with the wrong layout.
+#[no_mangle]
+pub extern "C" fn ime_result_size() -> u32 {
+    std::mem::size_of::<Result>() as u32
+}
+
+/// Get `sizeof(Preedit)` as laid out by this library. See `ime_result_size`.
+#[no_mangle]
+pub extern "C" fn ime_preedit_size() -> u32 {
+    std::mem::size_of::<Preedit>() as u32
+}
+
 /// Process a key event and return the result.
 ///
 /// # Arguments
@@ -114,6 +179,33 @@ pub extern "C" fn ime_key_ext(key: u16, caps: bool, ctrl: bool, shift: bool) ->
     }
 }
 
+/// Process a key event in preedit mode.
+///
+/// Same arguments as `ime_key_ext`. Instead of backspaces + chars, the
+/// result holds the composing string to draw inline (`preedit`, `cursor`),
+/// text to commit when the word ends (`commit`), and `delete_before` chars
+/// of committed text to remove first (plus the word tail after the caret if
+/// `flags` has `IME_FLAG_DELETE_TAIL`). If `flags` lacks
+/// `IME_FLAG_KEY_CONSUMED`, pass the key on to the app after committing.
+/// With `ime_suggest_on_invalid`, `suggestion` holds a correction for the
+/// last `suggestion_backspace` chars of the committed word.
+///
+/// Don't mix with `ime_key*` on the same engine.
+///
+/// # Returns
+/// * Pointer to `Preedit` struct (caller must free with `ime_free_preedit`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_key_preedit(key: u16, caps: bool, ctrl: bool, shift: bool) -> *mut Preedit {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        let p = e.on_key_preedit(key, caps, ctrl, shift);
+        Box::into_raw(Box::new(p))
+    } else {
+        std::ptr::null_mut()
+    }
+}
+
 /// Process a key event with the actual Unicode character.
 ///
 /// Used for Option-modified keys on macOS where the keycode doesn't change
@@ -146,22 +238,49 @@ pub extern "C" fn ime_key_with_char(
 ) -> *mut Result {
     let mut guard = lock_engine();
     if let Some(ref mut e) = *guard {
-        let ch = if char_code > 0 {
-            char::from_u32(char_code)
-        } else {
-            None
-        };
-        let r = e.on_key_with_char(key, caps, ctrl, shift, ch);
+        let r = e.on_key_with_char(key, caps, ctrl, shift, char_from_code(char_code));
         Box::into_raw(Box::new(r))
     } else {
         std::ptr::null_mut()
     }
 }
 
+/// Process a character produced by the OS keyboard layout.
+///
+/// Layout-independent alternative to `ime_key_ext`: pass the character the
+/// user's layout (AZERTY, Dvorak, Colemak, ...) produced instead of a macOS
+/// keycode. Caps/shift are derived from the character.
+///
+/// # Arguments
+/// * `char_code` - Unicode character (UTF-32). Control chars: 0x08 backspace,
+///   0x0D/0x0A Enter, 0x09 Tab, 0x1B ESC
+/// * `ctrl` - true if Cmd/Ctrl/Alt is pressed (bypasses IME)
+///
+/// # Returns
+/// * Pointer to `Result` struct (caller must free with `ime_free`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_char(char_code: u32, ctrl: bool) -> *mut Result {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        Box::into_raw(Box::new(process_char(e, char_code, ctrl)))
+    } else {
+        std::ptr::null_mut()
+    }
+}
+
+/// Process an FFI char code (invalid codepoints pass through)
+fn process_char(e: &mut Engine, char_code: u32, ctrl: bool) -> Result {
+    match char_from_code(char_code) {
+        Some(ch) => e.on_char(ch, ctrl),
+        None => Result::none(),
+    }
+}
+
 /// Set the input method.
 ///
 /// # Arguments
-/// * `method` - 0 for Telex, 1 for VNI
+/// * `method` - 0 for Telex, 1 for VNI, 2 for VIQR
 ///
 /// No-op if engine not initialized.
 #[no_mangle]
@@ -172,6 +291,73 @@ pub extern "C" fn ime_method(method: u8) {
     }
 }
 
+/// Register a custom input method from a keymap spec and switch to it.
+///
+/// See `input::custom` for the spec format. Registering the same spec again
+/// reuses its method id.
+///
+/// # Arguments
+/// * `spec` - C string keymap spec (UTF-8)
+///
+/// # Returns
+/// * `null` on success (also if engine not initialized)
+/// * Error message such as "line 4: key `s` is already bound on line 2"
+///   (caller must free with `ime_free_string`)
+///
+/// # Safety
+/// `spec` must be null or a valid null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_method_custom(
+    spec: *const std::os::raw::c_char,
+) -> *mut std::os::raw::c_char {
+    let mut guard = lock_engine();
+    set_method_custom(guard.as_mut(), spec)
+}
+
+/// Register `spec` and apply it to `e` (returns an FFI error string or null)
+///
+/// # Safety
+/// `spec` must be null or a valid null-terminated string.
+unsafe fn set_method_custom(
+    e: Option<&mut Engine>,
+    spec: *const std::os::raw::c_char,
+) -> *mut std::os::raw::c_char {
+    let Some(spec) = c_str(spec) else {
+        return error_string("spec is null or not valid UTF-8");
+    };
+    match input::custom::register(spec) {
+        Ok(id) => {
+            if let Some(e) = e {
+                e.set_method(id);
+            }
+            std::ptr::null_mut()
+        }
+        Err(err) => error_string(err),
+    }
+}
+
+/// Allocate an error message for FFI (freed with `ime_free_string`)
+fn error_string(message: impl std::fmt::Display) -> *mut std::os::raw::c_char {
+    owned_string(message)
+}
+
+/// Allocate a string for FFI (freed with `ime_free_string`)
+fn owned_string(text: impl std::fmt::Display) -> *mut std::os::raw::c_char {
+    let text = text.to_string().replace('\0', " ");
+    std::ffi::CString::new(text).unwrap_or_default().into_raw()
+}
+
+/// Free a string returned by the engine.
+///
+/// # Safety
+/// `s` must be null or a string returned by an `ime_*` function, freed once.
+#[no_mangle]
+pub unsafe extern "C" fn ime_free_string(s: *mut std::os::raw::c_char) {
+    if !s.is_null() {
+        drop(std::ffi::CString::from_raw(s));
+    }
+}
+
 /// Enable or disable the engine.
 ///
 /// When disabled, `ime_key` returns action=0 (pass through).
@@ -277,196 +463,1368 @@ pub extern "C" fn ime_auto_capitalize(enabled: bool) {
     }
 }
 
-/// Enable/disable foreign consonants (z, w, j, f) as valid initial consonants.
+/// Enable/disable foreign consonants (z, w, j, f) as valid initial consonants.
+///
+/// When `enabled` is true, allows z, w, j, f as valid Vietnamese consonants
+/// for typing loanwords while still getting Vietnamese diacritics.
+/// When `enabled` is false (default), these letters are treated as invalid initials.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_allow_foreign_consonants(enabled: bool) {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.set_allow_foreign_consonants(enabled);
+    }
+}
+
+/// Enable/disable suggestions for misspelled words.
+///
+/// When enabled, a break key (space, punctuation) that commits a word with
+/// diacritics failing the spell check returns action 3 (Suggest): `chars`
+/// holds the top correction for the `backspace` chars before the key, and the
+/// key itself is passed through as usual. Disabled by default.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_suggest_on_invalid(enabled: bool) {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.set_suggest_on_invalid(enabled);
+    }
+}
+
+/// Set output charset.
+///
+/// - 0: Unicode (default)
+/// - 1: TCVN3 (ABC)
+/// - 2: VNI-Windows
+/// - 3: VISCII
+/// - 4: Unicode NFD (base letter + combining marks)
+///
+/// Legacy charsets put one byte per `chars` element (U+0000-U+00FF).
+/// `backspace` counts chars on screen (VNI-Windows "ệ" = 2, NFD "ệ" = 3);
+/// Backspace over such a letter returns a Send that deletes all of its chars.
+/// No-op if engine not initialized or `charset` is unknown.
+#[no_mangle]
+pub extern "C" fn ime_charset(charset: u8) {
+    let mut guard = lock_engine();
+    if let (Some(ref mut e), Some(c)) = (&mut *guard, Charset::from_u8(charset)) {
+        e.set_charset(c);
+    }
+}
+
+/// Set options from a configuration text.
+///
+/// One `key = value` per line, as written by `ime_get_config` (see
+/// `engine::config`); keys not mentioned keep their value. Lines with an
+/// unknown key or a bad value are reported, the other lines still apply.
+///
+/// # Returns
+/// * `null` if every line applied (also if engine not initialized)
+/// * One error per line such as "line 2: unknown key `modern`"
+///   (caller must free with `ime_free_string`)
+///
+/// # Safety
+/// `config` must be null or a valid null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_configure(
+    config: *const std::os::raw::c_char,
+) -> *mut std::os::raw::c_char {
+    let mut guard = lock_engine();
+    configure(guard.as_mut(), config)
+}
+
+/// Apply `config` to `e`, or only check it without an engine (returns an
+/// FFI error string or null)
+///
+/// # Safety
+/// `config` must be null or a valid null-terminated string.
+unsafe fn configure(
+    e: Option<&mut Engine>,
+    config: *const std::os::raw::c_char,
+) -> *mut std::os::raw::c_char {
+    let Some(config) = c_str(config) else {
+        return error_string("config is null or not valid UTF-8");
+    };
+    let errors = match e {
+        Some(e) => e.configure(config),
+        None => EngineConfig::default().update(config),
+    };
+    if errors.is_empty() {
+        return std::ptr::null_mut();
+    }
+    let lines: Vec<String> = errors.iter().map(|err| err.to_string()).collect();
+    error_string(lines.join("\n"))
+}
+
+/// Get all options as configuration text, one `key = value` per line.
+///
+/// # Returns
+/// String accepted by `ime_configure` (caller must free with
+/// `ime_free_string`), or null if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_get_config() -> *mut std::os::raw::c_char {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => owned_string(e.config()),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Clear the input buffer.
+///
+/// Call on word boundaries (space, punctuation).
+/// Preserves word history for backspace-after-space feature.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_clear() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.clear();
+    }
+}
+
+/// Clear everything including word history.
+///
+/// Call when cursor position changes (mouse click, arrow keys, focus change).
+/// This prevents accidental restore from stale history.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_clear_all() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.clear_all();
+    }
+}
+
+/// Get the full composed buffer as UTF-32 codepoints.
+///
+/// Used for "Select All + Replace" injection method where the entire
+/// buffer content is needed instead of incremental backspace + chars.
+///
+/// # Arguments
+/// * `out` - Pointer to output buffer for UTF-32 codepoints
+/// * `max_len` - Maximum number of codepoints to write
+///
+/// # Returns
+/// Number of codepoints written to `out`.
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_get_buffer(out: *mut u32, max_len: i64) -> i64 {
+    if out.is_null() || max_len <= 0 {
+        return 0;
+    }
+
+    let guard = lock_engine();
+    if let Some(ref e) = *guard {
+        copy_buffer(e, out, max_len)
+    } else {
+        0
+    }
+}
+
+/// Copy the engine's composed buffer into `out` as UTF-32 codepoints.
+///
+/// # Safety
+/// `out` must be non-null and valid for `max_len` (> 0) writes.
+unsafe fn copy_buffer(e: &Engine, out: *mut u32, max_len: i64) -> i64 {
+    let full = e.get_buffer_string();
+    let utf32: Vec<u32> = full.chars().map(|c| c as u32).collect();
+    let len = utf32.len().min(max_len as usize);
+    std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
+    len as i64
+}
+
+/// Save the typing state: the word in progress, word history and pending
+/// flags (not options or shortcuts).
+///
+/// Park an input context with this and resume it later, even in another
+/// process, with `ime_restore_snapshot`. Call with null `out` to get the
+/// size first.
+///
+/// # Returns
+/// Size of the snapshot in bytes. `out` is only written if `max_len` is at
+/// least that size. 0 if engine not initialized.
+///
+/// # Safety
+/// `out` must be null or point to valid memory of at least `max_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_snapshot(out: *mut u8, max_len: i64) -> i64 {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => copy_bytes(&e.snapshot(), out, max_len),
+        None => 0,
+    }
+}
+
+/// Restore the typing state saved by `ime_snapshot`.
+///
+/// # Returns
+/// true on success; false (state unchanged) if the data is malformed, from
+/// another version, or the engine not initialized.
+///
+/// # Safety
+/// `data` must be null or point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_restore_snapshot(data: *const u8, len: i64) -> bool {
+    let mut guard = lock_engine();
+    match (guard.as_mut(), byte_slice(data, len)) {
+        (Some(e), Some(bytes)) => e.restore_snapshot(bytes),
+        _ => false,
+    }
+}
+
+/// Start recording a keystroke trace.
+///
+/// Every key passed to `ime_key*` (preedit included) and `ime_char` is
+/// logged with its result, after the options in effect. Attach the trace to
+/// a bug report; the `gonhanh-replay` tool reruns it. Restarts the trace if
+/// already recording.
+#[no_mangle]
+pub extern "C" fn ime_trace_start() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.start_trace();
+    }
+}
+
+/// Stop recording and get the trace.
+///
+/// # Returns
+/// Trace text (caller must free with `ime_free_string`), or null if not
+/// recording or engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_trace_stop() -> *mut std::os::raw::c_char {
+    let mut guard = lock_engine();
+    match guard.as_mut().and_then(|e| e.stop_trace()) {
+        Some(trace) => owned_string(trace),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Free a result pointer returned by `ime_key`.
+///
+/// # Safety
+/// * `r` must be a pointer returned by `ime_key`, or null
+/// * Must be called exactly once per non-null `ime_key` return
+/// * Do not use `r` after calling this function
+#[no_mangle]
+pub unsafe extern "C" fn ime_free(r: *mut Result) {
+    if !r.is_null() {
+        drop(Box::from_raw(r));
+    }
+}
+
+/// Free a preedit result returned by `ime_key_preedit`.
+///
+/// # Safety
+/// * `p` must be a pointer returned by `ime_key_preedit` or
+///   `ime_engine_key_preedit`, or null
+/// * Must be called exactly once per non-null return
+#[no_mangle]
+pub unsafe extern "C" fn ime_free_preedit(p: *mut Preedit) {
+    if !p.is_null() {
+        drop(Box::from_raw(p));
+    }
+}
+
+// ============================================================
+// Shortcut FFI
+// ============================================================
+
+/// Add a shortcut to the engine.
+///
+/// # Arguments
+/// * `trigger` - C string for trigger (e.g., "vn")
+/// * `replacement` - C string for replacement (e.g., "Việt Nam")
+///
+/// # Safety
+/// Both pointers must be valid null-terminated UTF-8 strings.
+#[no_mangle]
+pub unsafe extern "C" fn ime_add_shortcut(
+    trigger: *const std::os::raw::c_char,
+    replacement: *const std::os::raw::c_char,
+) {
+    if trigger.is_null() || replacement.is_null() {
+        return;
+    }
+
+    let Some(trigger_str) = c_str(trigger) else {
+        return;
+    };
+    let Some(replacement_str) = c_str(replacement) else {
+        return;
+    };
+
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        add_shortcut(e, trigger_str, replacement_str);
+    }
+}
+
+/// Add a shortcut, auto-detecting its trigger condition.
+///
+/// - If trigger contains only non-letter chars (like "->", "=>"), use immediate trigger
+/// - Otherwise use word boundary trigger (traditional abbreviations like "vn" → "Việt Nam")
+fn add_shortcut(e: &mut Engine, trigger: &str, replacement: &str) {
+    let is_symbol_trigger = trigger.chars().all(|c| !c.is_alphabetic());
+    let shortcut = if is_symbol_trigger {
+        engine::shortcut::Shortcut::immediate(trigger, replacement)
+    } else {
+        engine::shortcut::Shortcut::new(trigger, replacement)
+    };
+    e.shortcuts_mut().add(shortcut);
+}
+
+/// Remove a shortcut from the engine.
+///
+/// # Arguments
+/// * `trigger` - C string for trigger to remove
+///
+/// # Safety
+/// Pointer must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_remove_shortcut(trigger: *const std::os::raw::c_char) {
+    if trigger.is_null() {
+        return;
+    }
+
+    let Some(trigger_str) = c_str(trigger) else {
+        return;
+    };
+
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.shortcuts_mut().remove(trigger_str);
+    }
+}
+
+/// Clear all shortcuts from the engine.
+#[no_mangle]
+pub extern "C" fn ime_clear_shortcuts() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.shortcuts_mut().clear();
+    }
+}
+
+// ============================================================
+// Word Restore FFI
+// ============================================================
+
+/// Restore buffer from a Vietnamese word string.
+///
+/// Used when native app detects cursor at word boundary and user
+/// wants to continue editing (e.g., backspace into previous word).
+/// Parses Vietnamese characters back to buffer components.
+///
+/// # Arguments
+/// * `word` - C string containing the Vietnamese word to restore (NFC or NFD)
+///
+/// # Safety
+/// Pointer must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_restore_word(word: *const std::os::raw::c_char) {
+    if word.is_null() {
+        return;
+    }
+    let Some(word_str) = c_str(word) else {
+        return;
+    };
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.restore_word(word_str);
+    }
+}
+
+/// Restore buffer from the word around the caret.
+///
+/// Used when the platform reports surrounding text (IBus, Wayland
+/// text-input). The engine finds the word touching the caret, so a mark or
+/// tone key typed there edits it: "Viet|" + j → "Việt".
+///
+/// # Arguments
+/// * `before` - C string with text before the caret
+/// * `after` - C string with text after the caret
+///
+/// # Returns
+/// Chars of `after` that belong to the word. Delete that many chars after
+/// the caret before applying a result with `IME_FLAG_DELETE_TAIL`.
+///
+/// # Safety
+/// Pointers must be valid null-terminated UTF-8 strings.
+#[no_mangle]
+pub unsafe extern "C" fn ime_restore_surrounding(
+    before: *const std::os::raw::c_char,
+    after: *const std::os::raw::c_char,
+) -> u32 {
+    let (Some(before), Some(after)) = (c_str(before), c_str(after)) else {
+        return 0;
+    };
+    let mut guard = lock_engine();
+    match *guard {
+        Some(ref mut e) => e.restore_surrounding(before, after) as u32,
+        None => 0,
+    }
+}
+
+// ============================================================
+// User Dictionary FFI
+// ============================================================
+//
+// User words are shared by all engines. `list` selects the word list:
+// 0 = Vietnamese words (IME_DICT_VIETNAMESE), 1 = keep list (IME_DICT_KEEP),
+// Vietnamese results never auto-restored to English, 2 = English words
+// (IME_DICT_ENGLISH), 3 = raw keystrokes never auto-restored
+// (IME_DICT_NEVER_RESTORE). Entries like "re-" or "-ing" are prefix/suffix
+// rules: inflected forms of listed words count too.
+
+/// Add a word (or "re-"/"-ing" affix rule) to a user dictionary
+/// (case-insensitive).
+///
+/// # Returns
+/// true if the word was added, false if it was already there or `list`/`word`
+/// is invalid.
+///
+/// # Safety
+/// `word` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_dict_add(list: u8, word: *const std::os::raw::c_char) -> bool {
+    match (WordList::from_u8(list), c_str(word)) {
+        (Some(list), Some(word)) => user_dict::add(list, word),
+        _ => false,
+    }
+}
+
+/// Remove a word from a dictionary, hiding it if it is built in.
+///
+/// # Returns
+/// true if the word was removed, false if it was already removed or
+/// `list`/`word` is invalid.
+///
+/// # Safety
+/// `word` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_dict_remove(list: u8, word: *const std::os::raw::c_char) -> bool {
+    match (WordList::from_u8(list), c_str(word)) {
+        (Some(list), Some(word)) => user_dict::remove(list, word),
+        _ => false,
+    }
+}
+
+/// Add every word of a dictionary text (one word per line).
+///
+/// Blank lines, `#` comments and a leading word count are skipped.
+///
+/// # Returns
+/// Number of new words, or -1 if `list`/`content` is invalid.
+///
+/// # Safety
+/// `content` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_dict_load(list: u8, content: *const std::os::raw::c_char) -> i64 {
+    match (WordList::from_u8(list), c_str(content)) {
+        (Some(list), Some(content)) => user_dict::load(list, content) as i64,
+        _ => -1,
+    }
+}
+
+/// Add every word of a dictionary file. See `ime_dict_load`.
+///
+/// # Returns
+/// Number of new words, or -1 if the file can't be read or `list` is invalid.
+///
+/// # Safety
+/// `path` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_dict_load_file(list: u8, path: *const std::os::raw::c_char) -> i64 {
+    match (WordList::from_u8(list), c_str(path)) {
+        (Some(list), Some(path)) => user_dict::load_file(list, path).map_or(-1, |n| n as i64),
+        _ => -1,
+    }
+}
+
+/// Drop all user changes to a dictionary, restoring the built-in words.
+#[no_mangle]
+pub extern "C" fn ime_dict_clear(list: u8) {
+    if let Some(list) = WordList::from_u8(list) {
+        user_dict::clear(list);
+    }
+}
+
+// ============================================================
+// Learning FFI
+// ============================================================
+//
+// Opt-in auto-restore learning: ESC restores, manual mark reverts ("mixx")
+// and backspace right after an auto-restored word are counted per word, and
+// after `threshold` corrections the word is always (or never) restored. The
+// learned table is per engine; platforms persist it with export/import.
+
+/// Enable/disable learning from the user's corrections.
+///
+/// Learned decisions only apply while English auto-restore is enabled.
+/// Disabled by default. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_learn_auto_restore(enabled: bool) {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.set_learn_auto_restore(enabled);
+    }
+}
+
+/// Set the corrections needed before a word is always (or never) restored.
+///
+/// Default 3, minimum 1. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_learn_threshold(threshold: u8) {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.learning_mut().set_threshold(threshold);
+    }
+}
+
+/// Export the learned table as bytes.
+///
+/// Call with null `out` to get the size first.
+///
+/// # Returns
+/// Size of the table in bytes. `out` is only written if `max_len` is at least
+/// that size. 0 if engine not initialized.
+///
+/// # Safety
+/// `out` must be null or point to valid memory of at least `max_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_learned_export(out: *mut u8, max_len: i64) -> i64 {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => copy_bytes(&e.learning().export(), out, max_len),
+        None => 0,
+    }
+}
+
+/// Copy `bytes` into `out` if it fits, returning their length.
+///
+/// # Safety
+/// `out` must be null or valid for `max_len` writes.
+unsafe fn copy_bytes(bytes: &[u8], out: *mut u8, max_len: i64) -> i64 {
+    if !out.is_null() && max_len >= bytes.len() as i64 {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len());
+    }
+    bytes.len() as i64
+}
+
+/// Borrow `len` bytes at `data` (None for null or a negative length).
+///
+/// # Safety
+/// `data` must be null or point to `len` readable bytes.
+unsafe fn byte_slice<'a>(data: *const u8, len: i64) -> Option<&'a [u8]> {
+    if data.is_null() || len < 0 {
+        return None;
+    }
+    Some(std::slice::from_raw_parts(data, len as usize))
+}
+
+/// Replace the learned table with bytes from `ime_learned_export`.
+///
+/// # Returns
+/// true on success; false (table unchanged) if the data is malformed or the
+/// engine not initialized.
+///
+/// # Safety
+/// `data` must be null or point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_learned_import(data: *const u8, len: i64) -> bool {
+    let mut guard = lock_engine();
+    match *guard {
+        Some(ref mut e) => import_learned(e, data, len),
+        None => false,
+    }
+}
+
+/// # Safety
+/// `data` must be null or point to `len` readable bytes.
+unsafe fn import_learned(e: &mut Engine, data: *const u8, len: i64) -> bool {
+    byte_slice(data, len).is_some_and(|bytes| e.learning_mut().import(bytes).is_some())
+}
+
+/// Forget everything learned.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_learned_clear() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.learning_mut().clear();
+    }
+}
+
+// ============================================================
+// Profiles FFI
+// ============================================================
+//
+// A profile bundles every engine setting (method, enabled, options, charset,
+// shortcuts) under a name. Define one by configuring the engine with the
+// usual setters and saving it; apps (bundle id, process name, window class)
+// are assigned to profiles by name, and `ime_profile_switch_app` on focus
+// change applies the app's profile. Apps without one use the "default"
+// profile. Settings changed while an app is active are kept in its profile.
+
+/// Save the current settings (including shortcuts) as a profile.
+///
+/// Replaces a profile with the same name.
+///
+/// # Returns
+/// false if engine not initialized or `name` is invalid.
+///
+/// # Safety
+/// `name` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_profile_save(name: *const std::os::raw::c_char) -> bool {
+    let mut guard = lock_engine();
+    match (guard.as_mut(), c_str(name)) {
+        (Some(e), Some(name)) => {
+            e.save_profile(name);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Remove a profile; apps assigned to it go back to the default profile.
+///
+/// # Returns
+/// true if the profile existed.
+///
+/// # Safety
+/// `name` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_profile_remove(name: *const std::os::raw::c_char) -> bool {
+    let mut guard = lock_engine();
+    match (guard.as_mut(), c_str(name)) {
+        (Some(e), Some(name)) => e.profiles_mut().remove(name).is_some(),
+        _ => false,
+    }
+}
+
+/// Assign an app to a profile (the profile may be saved later).
+///
+/// # Returns
+/// false if engine not initialized or a string is invalid.
+///
+/// # Safety
+/// `app` and `name` must be null or valid null-terminated UTF-8 strings.
+#[no_mangle]
+pub unsafe extern "C" fn ime_profile_assign(
+    app: *const std::os::raw::c_char,
+    name: *const std::os::raw::c_char,
+) -> bool {
+    let mut guard = lock_engine();
+    match (guard.as_mut(), c_str(app), c_str(name)) {
+        (Some(e), Some(app), Some(name)) => {
+            e.profiles_mut().assign(app, name);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Drop an app's assignment: it goes back to the default profile.
+///
+/// # Returns
+/// true if the app was assigned.
+///
+/// # Safety
+/// `app` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_profile_unassign(app: *const std::os::raw::c_char) -> bool {
+    let mut guard = lock_engine();
+    match (guard.as_mut(), c_str(app)) {
+        (Some(e), Some(app)) => e.profiles_mut().unassign(app),
+        _ => false,
+    }
+}
+
+/// Switch to an app's profile. Call when the focused app changes.
+///
+/// Saves the current settings into the profile being left, applies the
+/// app's profile and clears the buffer and word history. Before a "default"
+/// profile is saved, the settings at the first switch become the default.
+///
+/// # Returns
+/// true if a profile was applied; false if the app's profile isn't defined
+/// (settings unchanged), engine not initialized or `app` is invalid.
+///
+/// # Safety
+/// `app` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_profile_switch_app(app: *const std::os::raw::c_char) -> bool {
+    let mut guard = lock_engine();
+    match (guard.as_mut(), c_str(app)) {
+        (Some(e), Some(app)) => e.switch_app(app),
+        _ => false,
+    }
+}
+
+/// Remove all profiles and app assignments. Current settings are kept.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_profile_clear() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.profiles_mut().clear();
+    }
+}
+
+// ============================================================
+// Engine Handle FFI (multi-instance)
+// ============================================================
+//
+// Each handle owns an independent `Engine` (buffer, word history, options,
+// shortcuts). Platforms keep one handle per input context or window so that
+// focus changes don't clobber the word being typed elsewhere.
+//
+// Handles are not synchronized: a handle must not be used from two threads
+// at the same time. All functions are no-ops (or return null/0) for a null
+// handle.
+
+/// Borrow the engine behind a handle (None for null)
+///
+/// # Safety
+/// `handle` must be null or a live pointer returned by `ime_engine_new`.
+unsafe fn engine_mut<'a>(handle: *mut Engine) -> Option<&'a mut Engine> {
+    handle.as_mut()
+}
+
+/// Create a new engine instance.
+///
+/// # Returns
+/// Opaque handle (caller must free with `ime_engine_free`).
+#[no_mangle]
+pub extern "C" fn ime_engine_new() -> *mut Engine {
+    Box::into_raw(Box::new(Engine::new()))
+}
+
+/// Free an engine instance created by `ime_engine_new`.
+///
+/// # Safety
+/// * `handle` must be a pointer returned by `ime_engine_new`, or null
+/// * Must be called exactly once per handle
+/// * Do not use `handle` after calling this function
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_free(handle: *mut Engine) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Process a key event on an engine instance. See `ime_key`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_key(
+    handle: *mut Engine,
+    key: u16,
+    caps: bool,
+    ctrl: bool,
+) -> *mut Result {
+    match engine_mut(handle) {
+        Some(e) => Box::into_raw(Box::new(e.on_key(key, caps, ctrl))),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Process a key event with shift state on an engine instance. See `ime_key_ext`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_key_ext(
+    handle: *mut Engine,
+    key: u16,
+    caps: bool,
+    ctrl: bool,
+    shift: bool,
+) -> *mut Result {
+    match engine_mut(handle) {
+        Some(e) => Box::into_raw(Box::new(e.on_key_ext(key, caps, ctrl, shift))),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Process a key event in preedit mode on an engine instance. See `ime_key_preedit`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_key_preedit(
+    handle: *mut Engine,
+    key: u16,
+    caps: bool,
+    ctrl: bool,
+    shift: bool,
+) -> *mut Preedit {
+    match engine_mut(handle) {
+        Some(e) => Box::into_raw(Box::new(e.on_key_preedit(key, caps, ctrl, shift))),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Process a key event with the actual Unicode character on an engine instance.
+/// See `ime_key_with_char`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_key_with_char(
+    handle: *mut Engine,
+    key: u16,
+    caps: bool,
+    ctrl: bool,
+    shift: bool,
+    char_code: u32,
+) -> *mut Result {
+    match engine_mut(handle) {
+        Some(e) => {
+            let r = e.on_key_with_char(key, caps, ctrl, shift, char_from_code(char_code));
+            Box::into_raw(Box::new(r))
+        }
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Process a layout-produced character on an engine instance. See `ime_char`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_char(
+    handle: *mut Engine,
+    char_code: u32,
+    ctrl: bool,
+) -> *mut Result {
+    match engine_mut(handle) {
+        Some(e) => Box::into_raw(Box::new(process_char(e, char_code, ctrl))),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Set the input method on an engine instance (0=Telex, 1=VNI, 2=VIQR).
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_method(handle: *mut Engine, method: u8) {
+    if let Some(e) = engine_mut(handle) {
+        e.set_method(method);
+    }
+}
+
+/// Register a custom input method on an engine instance. See `ime_method_custom`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`;
+/// `spec` must be null or a valid null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_method_custom(
+    handle: *mut Engine,
+    spec: *const std::os::raw::c_char,
+) -> *mut std::os::raw::c_char {
+    set_method_custom(engine_mut(handle), spec)
+}
+
+/// Enable or disable an engine instance. See `ime_enabled`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_enabled(handle: *mut Engine, enabled: bool) {
+    if let Some(e) = engine_mut(handle) {
+        e.set_enabled(enabled);
+    }
+}
+
+/// Set whether to skip w→ư shortcut on an engine instance. See `ime_skip_w_shortcut`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_skip_w_shortcut(handle: *mut Engine, skip: bool) {
+    if let Some(e) = engine_mut(handle) {
+        e.set_skip_w_shortcut(skip);
+    }
+}
+
+/// Set bracket shortcuts on an engine instance. See `ime_bracket_shortcut`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_bracket_shortcut(handle: *mut Engine, enabled: bool) {
+    if let Some(e) = engine_mut(handle) {
+        e.set_bracket_shortcut(enabled);
+    }
+}
+
+/// Set ESC restore on an engine instance. See `ime_esc_restore`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_esc_restore(handle: *mut Engine, enabled: bool) {
+    if let Some(e) = engine_mut(handle) {
+        e.set_esc_restore(enabled);
+    }
+}
+
+/// Set free tone placement on an engine instance. See `ime_free_tone`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_free_tone(handle: *mut Engine, enabled: bool) {
+    if let Some(e) = engine_mut(handle) {
+        e.set_free_tone(enabled);
+    }
+}
+
+/// Set modern tone placement on an engine instance. See `ime_modern`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_modern(handle: *mut Engine, modern: bool) {
+    if let Some(e) = engine_mut(handle) {
+        e.set_modern_tone(modern);
+    }
+}
+
+/// Set English auto-restore on an engine instance. See `ime_english_auto_restore`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_english_auto_restore(handle: *mut Engine, enabled: bool) {
+    if let Some(e) = engine_mut(handle) {
+        e.set_english_auto_restore(enabled);
+    }
+}
+
+/// Set auto-capitalize on an engine instance. See `ime_auto_capitalize`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_auto_capitalize(handle: *mut Engine, enabled: bool) {
+    if let Some(e) = engine_mut(handle) {
+        e.set_auto_capitalize(enabled);
+    }
+}
+
+/// Set foreign consonants on an engine instance. See `ime_allow_foreign_consonants`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_allow_foreign_consonants(handle: *mut Engine, enabled: bool) {
+    if let Some(e) = engine_mut(handle) {
+        e.set_allow_foreign_consonants(enabled);
+    }
+}
+
+/// Set suggestions on an engine instance. See `ime_suggest_on_invalid`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_suggest_on_invalid(handle: *mut Engine, enabled: bool) {
+    if let Some(e) = engine_mut(handle) {
+        e.set_suggest_on_invalid(enabled);
+    }
+}
+
+/// Set output charset on an engine instance. See `ime_charset`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_charset(handle: *mut Engine, charset: u8) {
+    if let (Some(e), Some(c)) = (engine_mut(handle), Charset::from_u8(charset)) {
+        e.set_charset(c);
+    }
+}
+
+/// Set options of an engine instance from a configuration text.
+/// See `ime_configure`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `config` must be null or a valid null-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_configure(
+    handle: *mut Engine,
+    config: *const std::os::raw::c_char,
+) -> *mut std::os::raw::c_char {
+    configure(engine_mut(handle), config)
+}
+
+/// Get all options of an engine instance. See `ime_get_config`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_get_config(handle: *mut Engine) -> *mut std::os::raw::c_char {
+    match engine_mut(handle) {
+        Some(e) => owned_string(e.config()),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Clear the input buffer of an engine instance. See `ime_clear`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_clear(handle: *mut Engine) {
+    if let Some(e) = engine_mut(handle) {
+        e.clear();
+    }
+}
+
+/// Clear everything including word history of an engine instance. See `ime_clear_all`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_clear_all(handle: *mut Engine) {
+    if let Some(e) = engine_mut(handle) {
+        e.clear_all();
+    }
+}
+
+/// Get the composed buffer of an engine instance. See `ime_get_buffer`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_get_buffer(
+    handle: *mut Engine,
+    out: *mut u32,
+    max_len: i64,
+) -> i64 {
+    if out.is_null() || max_len <= 0 {
+        return 0;
+    }
+    match engine_mut(handle) {
+        Some(e) => copy_buffer(e, out, max_len),
+        None => 0,
+    }
+}
+
+/// Save the typing state of an engine instance. See `ime_snapshot`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `out` must be null or point to valid memory of at least `max_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_snapshot(
+    handle: *mut Engine,
+    out: *mut u8,
+    max_len: i64,
+) -> i64 {
+    match engine_mut(handle) {
+        Some(e) => copy_bytes(&e.snapshot(), out, max_len),
+        None => 0,
+    }
+}
+
+/// Restore the typing state of an engine instance. See `ime_restore_snapshot`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `data` must be null or point to `len` readable bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_restore_snapshot(
+    handle: *mut Engine,
+    data: *const u8,
+    len: i64,
+) -> bool {
+    match (engine_mut(handle), byte_slice(data, len)) {
+        (Some(e), Some(bytes)) => e.restore_snapshot(bytes),
+        _ => false,
+    }
+}
+
+/// Start recording a keystroke trace on an engine instance. See
+/// `ime_trace_start`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_trace_start(handle: *mut Engine) {
+    if let Some(e) = engine_mut(handle) {
+        e.start_trace();
+    }
+}
+
+/// Stop recording on an engine instance and get the trace. See
+/// `ime_trace_stop`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_trace_stop(handle: *mut Engine) -> *mut std::os::raw::c_char {
+    match engine_mut(handle).and_then(|e| e.stop_trace()) {
+        Some(trace) => owned_string(trace),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Add a shortcut to an engine instance. See `ime_add_shortcut`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * Both strings must be valid null-terminated UTF-8 strings
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_add_shortcut(
+    handle: *mut Engine,
+    trigger: *const std::os::raw::c_char,
+    replacement: *const std::os::raw::c_char,
+) {
+    let (Some(e), Some(trigger_str), Some(replacement_str)) =
+        (engine_mut(handle), c_str(trigger), c_str(replacement))
+    else {
+        return;
+    };
+    add_shortcut(e, trigger_str, replacement_str);
+}
+
+/// Remove a shortcut from an engine instance. See `ime_remove_shortcut`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `trigger` must be a valid null-terminated UTF-8 string
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_remove_shortcut(
+    handle: *mut Engine,
+    trigger: *const std::os::raw::c_char,
+) {
+    let (Some(e), Some(trigger_str)) = (engine_mut(handle), c_str(trigger)) else {
+        return;
+    };
+    e.shortcuts_mut().remove(trigger_str);
+}
+
+/// Clear all shortcuts of an engine instance. See `ime_clear_shortcuts`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_clear_shortcuts(handle: *mut Engine) {
+    if let Some(e) = engine_mut(handle) {
+        e.shortcuts_mut().clear();
+    }
+}
+
+/// Restore an engine instance's buffer from a word. See `ime_restore_word`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `word` must be a valid null-terminated UTF-8 string
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_restore_word(
+    handle: *mut Engine,
+    word: *const std::os::raw::c_char,
+) {
+    let (Some(e), Some(word_str)) = (engine_mut(handle), c_str(word)) else {
+        return;
+    };
+    e.restore_word(word_str);
+}
+
+/// Restore an engine instance's buffer from the word around the caret.
+/// See `ime_restore_surrounding`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `before` / `after` must be valid null-terminated UTF-8 strings
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_restore_surrounding(
+    handle: *mut Engine,
+    before: *const std::os::raw::c_char,
+    after: *const std::os::raw::c_char,
+) -> u32 {
+    let (Some(e), Some(before), Some(after)) = (engine_mut(handle), c_str(before), c_str(after))
+    else {
+        return 0;
+    };
+    e.restore_surrounding(before, after) as u32
+}
+
+/// Set learning on an engine instance. See `ime_learn_auto_restore`.
 ///
-/// When `enabled` is true, allows z, w, j, f as valid Vietnamese consonants
-/// for typing loanwords while still getting Vietnamese diacritics.
-/// When `enabled` is false (default), these letters are treated as invalid initials.
-/// No-op if engine not initialized.
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
 #[no_mangle]
-pub extern "C" fn ime_allow_foreign_consonants(enabled: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_allow_foreign_consonants(enabled);
+pub unsafe extern "C" fn ime_engine_learn_auto_restore(handle: *mut Engine, enabled: bool) {
+    if let Some(e) = engine_mut(handle) {
+        e.set_learn_auto_restore(enabled);
     }
 }
 
-/// Clear the input buffer.
+/// Set the learning threshold of an engine instance. See `ime_learn_threshold`.
 ///
-/// Call on word boundaries (space, punctuation).
-/// Preserves word history for backspace-after-space feature.
-/// No-op if engine not initialized.
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
 #[no_mangle]
-pub extern "C" fn ime_clear() {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.clear();
+pub unsafe extern "C" fn ime_engine_learn_threshold(handle: *mut Engine, threshold: u8) {
+    if let Some(e) = engine_mut(handle) {
+        e.learning_mut().set_threshold(threshold);
     }
 }
 
-/// Clear everything including word history.
+/// Export the learned table of an engine instance. See `ime_learned_export`.
 ///
-/// Call when cursor position changes (mouse click, arrow keys, focus change).
-/// This prevents accidental restore from stale history.
-/// No-op if engine not initialized.
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `out` must be null or point to valid memory of at least `max_len` bytes
 #[no_mangle]
-pub extern "C" fn ime_clear_all() {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.clear_all();
+pub unsafe extern "C" fn ime_engine_learned_export(
+    handle: *mut Engine,
+    out: *mut u8,
+    max_len: i64,
+) -> i64 {
+    match engine_mut(handle) {
+        Some(e) => copy_bytes(&e.learning().export(), out, max_len),
+        None => 0,
     }
 }
 
-/// Get the full composed buffer as UTF-32 codepoints.
-///
-/// Used for "Select All + Replace" injection method where the entire
-/// buffer content is needed instead of incremental backspace + chars.
-///
-/// # Arguments
-/// * `out` - Pointer to output buffer for UTF-32 codepoints
-/// * `max_len` - Maximum number of codepoints to write
-///
-/// # Returns
-/// Number of codepoints written to `out`.
+/// Import a learned table into an engine instance. See `ime_learned_import`.
 ///
 /// # Safety
-/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `data` must be null or point to `len` readable bytes
 #[no_mangle]
-pub unsafe extern "C" fn ime_get_buffer(out: *mut u32, max_len: i64) -> i64 {
-    if out.is_null() || max_len <= 0 {
-        return 0;
-    }
-
-    let guard = lock_engine();
-    if let Some(ref e) = *guard {
-        let full = e.get_buffer_string();
-        let utf32: Vec<u32> = full.chars().map(|c| c as u32).collect();
-        let len = utf32.len().min(max_len as usize);
-        std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
-        len as i64
-    } else {
-        0
+pub unsafe extern "C" fn ime_engine_learned_import(
+    handle: *mut Engine,
+    data: *const u8,
+    len: i64,
+) -> bool {
+    match engine_mut(handle) {
+        Some(e) => import_learned(e, data, len),
+        None => false,
     }
 }
 
-/// Free a result pointer returned by `ime_key`.
+/// Forget everything an engine instance learned. See `ime_learned_clear`.
 ///
 /// # Safety
-/// * `r` must be a pointer returned by `ime_key`, or null
-/// * Must be called exactly once per non-null `ime_key` return
-/// * Do not use `r` after calling this function
+/// `handle` must be null or a live handle from `ime_engine_new`.
 #[no_mangle]
-pub unsafe extern "C" fn ime_free(r: *mut Result) {
-    if !r.is_null() {
-        drop(Box::from_raw(r));
+pub unsafe extern "C" fn ime_engine_learned_clear(handle: *mut Engine) {
+    if let Some(e) = engine_mut(handle) {
+        e.learning_mut().clear();
     }
 }
 
-// ============================================================
-// Shortcut FFI
-// ============================================================
-
-/// Add a shortcut to the engine.
-///
-/// # Arguments
-/// * `trigger` - C string for trigger (e.g., "vn")
-/// * `replacement` - C string for replacement (e.g., "Việt Nam")
+/// Save the settings of an engine instance as a profile. See `ime_profile_save`.
 ///
 /// # Safety
-/// Both pointers must be valid null-terminated UTF-8 strings.
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `name` must be null or a valid null-terminated UTF-8 string
 #[no_mangle]
-pub unsafe extern "C" fn ime_add_shortcut(
-    trigger: *const std::os::raw::c_char,
-    replacement: *const std::os::raw::c_char,
-) {
-    if trigger.is_null() || replacement.is_null() {
-        return;
+pub unsafe extern "C" fn ime_engine_profile_save(
+    handle: *mut Engine,
+    name: *const std::os::raw::c_char,
+) -> bool {
+    match (engine_mut(handle), c_str(name)) {
+        (Some(e), Some(name)) => {
+            e.save_profile(name);
+            true
+        }
+        _ => false,
     }
+}
 
-    let trigger_str = match std::ffi::CStr::from_ptr(trigger).to_str() {
-        Ok(s) => s,
-        Err(_) => return,
-    };
-    let replacement_str = match std::ffi::CStr::from_ptr(replacement).to_str() {
-        Ok(s) => s,
-        Err(_) => return,
-    };
-
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        // Auto-detect shortcut type:
-        // - If trigger contains only non-letter chars (like "->", "=>"), use immediate trigger
-        // - Otherwise use word boundary trigger (traditional abbreviations like "vn" → "Việt Nam")
-        let is_symbol_trigger = trigger_str.chars().all(|c| !c.is_alphabetic());
-        let shortcut = if is_symbol_trigger {
-            engine::shortcut::Shortcut::immediate(trigger_str, replacement_str)
-        } else {
-            engine::shortcut::Shortcut::new(trigger_str, replacement_str)
-        };
-        e.shortcuts_mut().add(shortcut);
+/// Remove a profile of an engine instance. See `ime_profile_remove`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `name` must be null or a valid null-terminated UTF-8 string
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_profile_remove(
+    handle: *mut Engine,
+    name: *const std::os::raw::c_char,
+) -> bool {
+    match (engine_mut(handle), c_str(name)) {
+        (Some(e), Some(name)) => e.profiles_mut().remove(name).is_some(),
+        _ => false,
     }
 }
 
-/// Remove a shortcut from the engine.
-///
-/// # Arguments
-/// * `trigger` - C string for trigger to remove
+/// Assign an app to a profile of an engine instance. See `ime_profile_assign`.
 ///
 /// # Safety
-/// Pointer must be a valid null-terminated UTF-8 string.
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `app` / `name` must be null or valid null-terminated UTF-8 strings
 #[no_mangle]
-pub unsafe extern "C" fn ime_remove_shortcut(trigger: *const std::os::raw::c_char) {
-    if trigger.is_null() {
-        return;
+pub unsafe extern "C" fn ime_engine_profile_assign(
+    handle: *mut Engine,
+    app: *const std::os::raw::c_char,
+    name: *const std::os::raw::c_char,
+) -> bool {
+    match (engine_mut(handle), c_str(app), c_str(name)) {
+        (Some(e), Some(app), Some(name)) => {
+            e.profiles_mut().assign(app, name);
+            true
+        }
+        _ => false,
     }
+}
 
-    let trigger_str = match std::ffi::CStr::from_ptr(trigger).to_str() {
-        Ok(s) => s,
-        Err(_) => return,
-    };
-
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.shortcuts_mut().remove(trigger_str);
+/// Drop an app's assignment on an engine instance. See `ime_profile_unassign`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `app` must be null or a valid null-terminated UTF-8 string
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_profile_unassign(
+    handle: *mut Engine,
+    app: *const std::os::raw::c_char,
+) -> bool {
+    match (engine_mut(handle), c_str(app)) {
+        (Some(e), Some(app)) => e.profiles_mut().unassign(app),
+        _ => false,
     }
 }
 
-/// Clear all shortcuts from the engine.
+/// Switch an engine instance to an app's profile. See `ime_profile_switch_app`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `app` must be null or a valid null-terminated UTF-8 string
 #[no_mangle]
-pub extern "C" fn ime_clear_shortcuts() {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.shortcuts_mut().clear();
+pub unsafe extern "C" fn ime_engine_profile_switch_app(
+    handle: *mut Engine,
+    app: *const std::os::raw::c_char,
+) -> bool {
+    match (engine_mut(handle), c_str(app)) {
+        (Some(e), Some(app)) => e.switch_app(app),
+        _ => false,
     }
 }
 
-// ============================================================
-// Word Restore FFI
-// ============================================================
-
-/// Restore buffer from a Vietnamese word string.
-///
-/// Used when native app detects cursor at word boundary and user
-/// wants to continue editing (e.g., backspace into previous word).
-/// Parses Vietnamese characters back to buffer components.
-///
-/// # Arguments
-/// * `word` - C string containing the Vietnamese word to restore
+/// Remove all profiles of an engine instance. See `ime_profile_clear`.
 ///
 /// # Safety
-/// Pointer must be a valid null-terminated UTF-8 string.
+/// `handle` must be null or a live handle from `ime_engine_new`.
 #[no_mangle]
-pub unsafe extern "C" fn ime_restore_word(word: *const std::os::raw::c_char) {
-    if word.is_null() {
-        return;
-    }
-    let word_str = match std::ffi::CStr::from_ptr(word).to_str() {
-        Ok(s) => s,
-        Err(_) => return,
-    };
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.restore_word(word_str);
+pub unsafe extern "C" fn ime_engine_profile_clear(handle: *mut Engine) {
+    if let Some(e) = engine_mut(handle) {
+        e.profiles_mut().clear();
     }
 }
 
@@ -478,6 +1836,7 @@ pub unsafe extern "C" fn ime_restore_word(word: *const std::os::raw::c_char) {
 mod tests {
     use super::*;
     use crate::data::keys;
+    use crate::engine::trace::Trace;
     use serial_test::serial;
     use std::ffi::CString;
 
@@ -913,4 +2272,398 @@ mod tests {
         ime_clear_shortcuts();
         ime_clear();
     }
+
+    #[test]
+    #[serial]
+    fn test_char_ffi() {
+        ime_init();
+        ime_method(0); // Telex
+
+        let r = ime_char('a' as u32, false);
+        assert!(!r.is_null());
+        unsafe { ime_free(r) };
+
+        let r = ime_char('s' as u32, false);
+        unsafe {
+            assert_eq!((*r).action, engine::Action::Send as u8);
+            assert_eq!((*r).chars[0], 'á' as u32);
+            ime_free(r);
+        }
+
+        // Invalid codepoint (surrogate) passes through
+        let r = ime_char(0xD800, false);
+        unsafe {
+            assert_eq!((*r).action, engine::Action::None as u8);
+            ime_free(r);
+        }
+
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_method_custom_ffi() {
+        ime_init();
+        let spec =
+            CString::new("[marks]\ns = sac\n[tones]\na = circumflex a\n[stroke]\nd").unwrap();
+        let err = unsafe { ime_method_custom(spec.as_ptr()) };
+        assert!(err.is_null());
+
+        // "as" → "á" with the custom method, and no z removal
+        let r = ime_key(keys::A, false, false);
+        unsafe { ime_free(r) };
+        let r = ime_key(keys::S, false, false);
+        unsafe {
+            assert_eq!((*r).chars[0], 'á' as u32);
+            ime_free(r);
+        }
+        ime_clear();
+
+        let bad = CString::new("[marks]\ns = sac\ns = hoi").unwrap();
+        let err = unsafe { ime_method_custom(bad.as_ptr()) };
+        assert!(!err.is_null());
+        let msg = unsafe { std::ffi::CStr::from_ptr(err) }.to_str().unwrap();
+        assert_eq!(msg, "line 3: key `s` is already bound on line 2");
+        unsafe { ime_free_string(err) };
+
+        ime_method(0);
+    }
+
+    #[test]
+    fn test_engine_configure_ffi() {
+        let h = ime_engine_new();
+        let config = CString::new("method = vni\nmodern = false\nfree_tone = 1").unwrap();
+        unsafe {
+            let err = ime_engine_configure(h, config.as_ptr());
+            assert!(!err.is_null());
+            let msg = std::ffi::CStr::from_ptr(err).to_str().unwrap();
+            assert_eq!(
+                msg,
+                "line 2: unknown key `modern`\n\
+                 line 3: `free_tone` must be true or false, got `1`"
+            );
+            ime_free_string(err);
+
+            // Round trip through the written config
+            let text = ime_engine_get_config(h);
+            let written = std::ffi::CStr::from_ptr(text).to_owned();
+            ime_free_string(text);
+            assert!(written.to_str().unwrap().starts_with("method = vni\n"));
+            let other = ime_engine_new();
+            assert!(ime_engine_configure(other, written.as_ptr()).is_null());
+            assert_eq!((*other).config(), (*h).config());
+
+            assert!(ime_engine_get_config(std::ptr::null_mut()).is_null());
+            ime_engine_free(other);
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_engine_trace_ffi() {
+        let h = ime_engine_new();
+        unsafe {
+            assert!(ime_engine_trace_stop(h).is_null());
+            ime_engine_trace_start(h);
+            for key in [keys::V, keys::I, keys::E, keys::E] {
+                ime_free(ime_engine_key(h, key, false, false));
+            }
+            let text = ime_engine_trace_stop(h);
+            assert!(!text.is_null());
+            let trace = std::ffi::CStr::from_ptr(text).to_str().unwrap().to_string();
+            ime_free_string(text);
+            assert!(trace.ends_with("14 -> send bs=1 \"ê\"\n"), "{}", trace);
+
+            let trace = Trace::parse(&trace).unwrap();
+            assert_eq!(trace.keys().count(), 4);
+            assert!(trace.mismatches().is_empty());
+
+            ime_engine_trace_start(std::ptr::null_mut());
+            assert!(ime_engine_trace_stop(std::ptr::null_mut()).is_null());
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_engine_charset_ffi() {
+        let h = ime_engine_new();
+        unsafe {
+            ime_engine_charset(h, 2); // VNI-Windows
+            for key in [keys::V, keys::I, keys::E, keys::E] {
+                ime_free(ime_engine_key(h, key, false, false));
+            }
+            // "ê" is 2 chars on screen, so "ệ" replaces both
+            let r = ime_engine_key(h, keys::J, false, false);
+            assert_eq!((*r).backspace, 2);
+            assert_eq!((*r).count, 2);
+            assert_eq!([(*r).chars[0], (*r).chars[1]], [b'e' as u32, 0xE4]);
+            ime_free(r);
+
+            // Unknown charset is ignored
+            ime_engine_charset(h, 9);
+            assert_eq!((*h).charset(), Charset::VniWindows);
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_engine_preedit_ffi() {
+        let h = ime_engine_new();
+        unsafe {
+            for key in [keys::A, keys::A] {
+                ime_free_preedit(ime_engine_key_preedit(h, key, false, false, false));
+            }
+            let p = ime_engine_key_preedit(h, keys::S, false, false, false);
+            assert_eq!((*p).preedit_len, 1);
+            assert_eq!((*p).preedit[0], 'ấ' as u32);
+            assert_eq!((*p).commit_len, 0);
+            ime_free_preedit(p);
+
+            let p = ime_engine_key_preedit(h, keys::SPACE, false, false, false);
+            assert_eq!((*p).preedit_len, 0);
+            assert_eq!((*p).commit_string(), "ấ ");
+            ime_free_preedit(p);
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_engine_restore_surrounding_ffi() {
+        let h = ime_engine_new();
+        let before = CString::new("(Vi").unwrap();
+        let after = CString::new("et) nam").unwrap();
+        unsafe {
+            assert_eq!(
+                ime_engine_restore_surrounding(h, before.as_ptr(), after.as_ptr()),
+                2
+            );
+            let r = ime_engine_key(h, keys::J, false, false);
+            assert_eq!((*r).action, 1);
+            assert_ne!((*r).flags & engine::FLAG_DELETE_TAIL, 0);
+            ime_free(r);
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_engine_suggest_ffi() {
+        let h = ime_engine_new();
+        unsafe {
+            ime_engine_suggest_on_invalid(h, true);
+            // "mậng" (typo of "mạng") isn't a word
+            for key in [keys::M, keys::A, keys::A, keys::N, keys::G, keys::J] {
+                ime_free(ime_engine_key(h, key, false, false));
+            }
+            let r = ime_engine_key(h, keys::SPACE, false, false);
+            assert_eq!((*r).action, 3);
+            assert_eq!((*r).backspace, 4);
+            assert!((*r).count > 0);
+            ime_free(r);
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_dict_ffi() {
+        // Word unique to this test: user dictionaries are global
+        let word = CString::new("gonhanhffi").unwrap();
+        let content = CString::new("2\ngonhanhffi\ngonhanhffi2\n").unwrap();
+        unsafe {
+            assert!(ime_dict_add(0, word.as_ptr()));
+            assert!(!ime_dict_add(0, word.as_ptr()));
+            assert!(data::dictionary::is_vietnamese("gonhanhffi", false));
+            assert!(ime_dict_remove(0, word.as_ptr()));
+            assert!(!data::dictionary::is_vietnamese("gonhanhffi", false));
+            assert_eq!(ime_dict_load(0, content.as_ptr()), 2);
+            assert!(data::dictionary::is_vietnamese("gonhanhffi2", false));
+
+            // Unknown list or null string
+            assert!(!ime_dict_add(9, word.as_ptr()));
+            assert!(!ime_dict_add(0, std::ptr::null()));
+            assert_eq!(ime_dict_load(9, content.as_ptr()), -1);
+            assert_eq!(ime_dict_load_file(0, word.as_ptr()), -1);
+
+            // English list, with the default "-s" rule
+            assert!(ime_dict_add(2, word.as_ptr()));
+            assert!(data::english_dict::is_english_word("gonhanhffis"));
+        }
+        for w in ["gonhanhffi", "gonhanhffi2"] {
+            user_dict::remove(WordList::Vietnamese, w);
+        }
+        user_dict::remove(WordList::English, "gonhanhffi");
+    }
+
+    #[test]
+    fn test_abi_exports() {
+        assert_eq!(ime_abi_version(), abi::ABI_VERSION);
+        assert_eq!(
+            ime_result_size() as usize,
+            std::mem::size_of::<engine::Result>()
+        );
+        assert_eq!(
+            ime_preedit_size() as usize,
+            std::mem::size_of::<engine::preedit::Preedit>()
+        );
+    }
+
+    #[test]
+    fn test_engine_handles_are_independent() {
+        let telex = ime_engine_new();
+        let vni = ime_engine_new();
+        unsafe {
+            ime_engine_method(vni, 1);
+
+            // Interleave keystrokes: "as" on the Telex handle, "a1" on the VNI handle
+            ime_free(ime_engine_key(telex, keys::A, false, false));
+            ime_free(ime_engine_key(vni, keys::A, false, false));
+
+            let r = ime_engine_key(telex, keys::S, false, false);
+            assert_eq!((*r).chars[0], 'á' as u32);
+            ime_free(r);
+
+            let r = ime_engine_key(vni, keys::N1, false, false);
+            assert_eq!((*r).chars[0], 'á' as u32);
+            ime_free(r);
+
+            // Clearing one handle leaves the other's buffer intact
+            ime_engine_clear(vni);
+            let mut out = [0u32; 8];
+            assert_eq!(ime_engine_get_buffer(telex, out.as_mut_ptr(), 8), 1);
+            assert_eq!(out[0], 'á' as u32);
+            assert_eq!(ime_engine_get_buffer(vni, out.as_mut_ptr(), 8), 0);
+
+            ime_engine_free(telex);
+            ime_engine_free(vni);
+        }
+    }
+
+    #[test]
+    fn test_engine_handle_shortcuts_are_independent() {
+        let a = ime_engine_new();
+        let b = ime_engine_new();
+        let trigger = CString::new("vn").unwrap();
+        let replacement = CString::new("Việt Nam").unwrap();
+        unsafe {
+            ime_engine_clear_shortcuts(a);
+            ime_engine_clear_shortcuts(b);
+            ime_engine_add_shortcut(a, trigger.as_ptr(), replacement.as_ptr());
+            assert_eq!((*a).shortcuts().len(), 1);
+            assert_eq!((*b).shortcuts().len(), 0);
+
+            ime_engine_remove_shortcut(a, trigger.as_ptr());
+            assert_eq!((*a).shortcuts().len(), 0);
+
+            ime_engine_free(a);
+            ime_engine_free(b);
+        }
+    }
+
+    #[test]
+    fn test_engine_handle_learned_export_import() {
+        let a = ime_engine_new();
+        let b = ime_engine_new();
+        unsafe {
+            ime_engine_english_auto_restore(a, true);
+            ime_engine_esc_restore(a, true);
+            ime_engine_learn_auto_restore(a, true);
+            ime_engine_learn_threshold(a, 1);
+            // "box" → "bõ", ESC → "box": one correction
+            for key in [keys::B, keys::O, keys::X, keys::ESC] {
+                ime_free(ime_engine_key(a, key, false, false));
+            }
+
+            // Size query, too-small buffer, then the real copy
+            let len = ime_engine_learned_export(a, std::ptr::null_mut(), 0);
+            assert!(len > 0);
+            let mut out = vec![0u8; len as usize];
+            assert_eq!(ime_engine_learned_export(a, out.as_mut_ptr(), len - 1), len);
+            assert!(out.iter().all(|&b| b == 0));
+            assert_eq!(ime_engine_learned_export(a, out.as_mut_ptr(), len), len);
+
+            assert!(ime_engine_learned_import(b, out.as_ptr(), len));
+            assert_eq!((*b).learning().len(), 1);
+            assert!(!ime_engine_learned_import(b, out.as_ptr(), 2));
+            assert!(!ime_engine_learned_import(b, std::ptr::null(), len));
+            assert_eq!((*b).learning().len(), 1);
+
+            ime_engine_learned_clear(b);
+            assert!((*b).learning().is_empty());
+
+            ime_engine_free(a);
+            ime_engine_free(b);
+        }
+    }
+
+    #[test]
+    fn test_engine_handle_profiles() {
+        let e = ime_engine_new();
+        let vni = CString::new("vni").unwrap();
+        let word = CString::new("com.microsoft.Word").unwrap();
+        let other = CString::new("other").unwrap();
+        unsafe {
+            ime_engine_method(e, 1);
+            assert!(ime_engine_profile_save(e, vni.as_ptr()));
+            ime_engine_method(e, 0);
+            assert!(ime_engine_profile_assign(e, word.as_ptr(), vni.as_ptr()));
+
+            assert!(ime_engine_profile_switch_app(e, word.as_ptr()));
+            assert_eq!((*e).config().method, 1);
+            // The default profile is the settings before the first switch
+            assert!(ime_engine_profile_switch_app(e, other.as_ptr()));
+            assert_eq!((*e).config().method, 0);
+
+            assert!(ime_engine_profile_unassign(e, word.as_ptr()));
+            assert!(!ime_engine_profile_unassign(e, word.as_ptr()));
+            assert!(ime_engine_profile_remove(e, vni.as_ptr()));
+            assert!(!ime_engine_profile_save(e, std::ptr::null()));
+            ime_engine_profile_clear(e);
+            assert!((*e).profiles().is_empty());
+
+            ime_engine_free(e);
+        }
+    }
+
+    #[test]
+    fn test_engine_handle_snapshot() {
+        let a = ime_engine_new();
+        let b = ime_engine_new();
+        unsafe {
+            for key in [keys::V, keys::I, keys::E, keys::E] {
+                ime_free(ime_engine_key(a, key, false, false));
+            }
+            let len = ime_engine_snapshot(a, std::ptr::null_mut(), 0);
+            let mut out = vec![0u8; len as usize];
+            assert_eq!(ime_engine_snapshot(a, out.as_mut_ptr(), len), len);
+            assert!(ime_engine_restore_snapshot(b, out.as_ptr(), len));
+            assert!(!ime_engine_restore_snapshot(b, out.as_ptr(), len - 1));
+
+            let mut buf = [0u32; 8];
+            assert_eq!(ime_engine_get_buffer(b, buf.as_mut_ptr(), 8), 3);
+            assert_eq!(buf[2], 'ê' as u32);
+
+            ime_engine_free(a);
+            ime_engine_free(b);
+        }
+    }
+
+    #[test]
+    fn test_engine_handle_null_safety() {
+        let null = std::ptr::null_mut();
+        unsafe {
+            assert!(ime_engine_key(null, keys::A, false, false).is_null());
+            assert!(ime_engine_key_ext(null, keys::A, false, false, false).is_null());
+            ime_engine_method(null, 1);
+            ime_engine_clear(null);
+            ime_engine_restore_word(null, std::ptr::null());
+            ime_engine_add_shortcut(null, std::ptr::null(), std::ptr::null());
+            let mut out = [0u32; 4];
+            assert_eq!(ime_engine_get_buffer(null, out.as_mut_ptr(), 4), 0);
+            assert_eq!(ime_engine_learned_export(null, std::ptr::null_mut(), 0), 0);
+            assert!(!ime_engine_learned_import(null, std::ptr::null(), 0));
+            assert!(!ime_engine_profile_switch_app(null, std::ptr::null()));
+            assert_eq!(ime_engine_snapshot(null, std::ptr::null_mut(), 0), 0);
+            assert!(!ime_engine_restore_snapshot(null, std::ptr::null(), 0));
+            ime_engine_free(null);
+        }
+    }
 }
@@ -0,0 +1,125 @@
+//! Collation - Vietnamese alphabetical order
+//!
+//! Byte order puts "Đ" after "Z" and scatters accented vowels. Vietnamese
+//! dictionaries sort by letter first (a ă â b c d đ e ê …), then by mark
+//! (ngang, huyền, hỏi, ngã, sắc, nặng), then by case. Letters are split into
+//! key/tone/mark/stroke with `chars::parse_grapheme`, so NFC and NFD text
+//! sort the same.
+
+use std::cmp::Ordering;
+
+use super::is_word_char;
+use crate::data::chars::{self, combining, mark, tone};
+use crate::data::keys;
+
+/// Vietnamese alphabet with f, j, w, z at their Latin positions
+const ALPHABET: &[(u16, u8, bool)] = &[
+    (keys::A, tone::NONE, false),
+    (keys::A, tone::HORN, false), // ă
+    (keys::A, tone::CIRCUMFLEX, false),
+    (keys::B, tone::NONE, false),
+    (keys::C, tone::NONE, false),
+    (keys::D, tone::NONE, false),
+    (keys::D, tone::NONE, true), // đ
+    (keys::E, tone::NONE, false),
+    (keys::E, tone::CIRCUMFLEX, false),
+    (keys::F, tone::NONE, false),
+    (keys::G, tone::NONE, false),
+    (keys::H, tone::NONE, false),
+    (keys::I, tone::NONE, false),
+    (keys::J, tone::NONE, false),
+    (keys::K, tone::NONE, false),
+    (keys::L, tone::NONE, false),
+    (keys::M, tone::NONE, false),
+    (keys::N, tone::NONE, false),
+    (keys::O, tone::NONE, false),
+    (keys::O, tone::CIRCUMFLEX, false),
+    (keys::O, tone::HORN, false),
+    (keys::P, tone::NONE, false),
+    (keys::Q, tone::NONE, false),
+    (keys::R, tone::NONE, false),
+    (keys::S, tone::NONE, false),
+    (keys::T, tone::NONE, false),
+    (keys::U, tone::NONE, false),
+    (keys::U, tone::HORN, false),
+    (keys::V, tone::NONE, false),
+    (keys::W, tone::NONE, false),
+    (keys::X, tone::NONE, false),
+    (keys::Y, tone::NONE, false),
+    (keys::Z, tone::NONE, false),
+];
+
+/// Letters weigh more than ASCII punctuation/digits, less than other chars
+const LETTER_BASE: u32 = 0x80;
+const OTHER_BASE: u32 = 0x100;
+/// Ends a level; lower than any weight so shorter strings sort first
+const LEVEL_END: u32 = 0;
+
+/// Mark order: ngang, huyền, hỏi, ngã, sắc, nặng
+fn mark_weight(m: u8) -> u32 {
+    match m {
+        mark::HUYEN => 1,
+        mark::HOI => 2,
+        mark::NGA => 3,
+        mark::SAC => 4,
+        mark::NANG => 5,
+        _ => 0,
+    }
+}
+
+/// Sort key: compare keys to get Vietnamese alphabetical order
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortKey(Vec<u32>);
+
+impl SortKey {
+    /// Weights: letters, then marks, then case, each level ending with 0
+    pub fn weights(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+/// Build the collation key of `text`
+///
+/// Levels: letter (ă after a, đ after d), then mark, then case (lowercase
+/// first). Digits and ASCII punctuation sort before letters, other chars
+/// after them by code point.
+pub fn sort_key(text: &str) -> SortKey {
+    let mut primary = Vec::with_capacity(text.len());
+    let mut marks = Vec::with_capacity(text.len());
+    let mut case = Vec::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let (weight, m, caps, len) = match chars::parse_grapheme(rest).filter(|_| is_word_char(c)) {
+            Some((p, len)) => {
+                let letter = ALPHABET
+                    .iter()
+                    .position(|&(k, t, s)| k == p.key && t == p.tone && s == p.stroke)
+                    .unwrap_or(0);
+                (LETTER_BASE + letter as u32, p.mark, p.caps, len)
+            }
+            None if combining::is_combining(c) => {
+                // Stray combining mark: ignorable
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+            None if c.is_ascii() => (c as u32 + 1, mark::NONE, false, c.len_utf8()),
+            None => (OTHER_BASE + c as u32, mark::NONE, false, c.len_utf8()),
+        };
+        primary.push(weight);
+        marks.push(mark_weight(m) + 1);
+        case.push(caps as u32 + 1);
+        rest = &rest[len..];
+    }
+
+    let mut key = primary;
+    key.push(LEVEL_END);
+    key.extend(marks);
+    key.push(LEVEL_END);
+    key.extend(case);
+    SortKey(key)
+}
+
+/// Compare two strings in Vietnamese alphabetical order
+pub fn compare(a: &str, b: &str) -> Ordering {
+    sort_key(a).cmp(&sort_key(b))
+}
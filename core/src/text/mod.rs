@@ -0,0 +1,135 @@
+//! Text Utilities - Whole-string Vietnamese text helpers
+//!
+//! Work on finished text rather than keystrokes, using the same character
+//! tables as the engine (`chars::parse_char`), so every precomposed (NFC) or
+//! decomposed (NFD) Vietnamese letter is handled the same way.
+//!
+//! - `strip_diacritics`: "Tiếng Việt" → "Tieng Viet"
+//! - `slugify`: "Đường Lê Lợi, Q.1" → "duong-le-loi-q-1"
+//! - `normalize_tone_placement`: "hòa thúy" ⇄ "hoà thuý"
+//! - `sort_key` / `compare`: Vietnamese alphabetical order
+//! - `spellcheck`: misspelled syllables with byte spans and reasons
+//! - `suggest`: dictionary corrections for a misspelled syllable
+
+pub mod collate;
+pub mod placement;
+pub mod spellcheck;
+pub mod suggest;
+
+pub use collate::{compare, sort_key, SortKey};
+pub use placement::{normalize_tone_placement, Normalized, PlacementChange};
+pub use spellcheck::{spellcheck, Issue, Reason};
+pub use suggest::suggest;
+
+use crate::data::chars::{self, combining};
+
+/// Check if a char belongs to a word (letter or combining mark)
+pub(crate) fn is_word_char(c: char) -> bool {
+    combining::is_combining(c) || chars::parse_char(c).is_some()
+}
+
+/// Words (runs of letters, NFC or NFD) with their byte offsets
+pub(crate) fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut rest = text.char_indices().peekable();
+    std::iter::from_fn(move || {
+        let (start, _) = rest.by_ref().find(|&(_, c)| is_word_char(c))?;
+        let mut end = text.len();
+        while let Some(&(i, c)) = rest.peek() {
+            if !is_word_char(c) {
+                end = i;
+                break;
+            }
+            rest.next();
+        }
+        Some((start, &text[start..end]))
+    })
+}
+
+/// Options for `strip_diacritics`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StripOptions {
+    /// Keep đ/Đ instead of mapping them to d/D
+    pub keep_d: bool,
+}
+
+/// Remove Vietnamese tone marks and vowel diacritics
+///
+/// "Đường phố" → "Duong pho" (or "Đuong pho" with `keep_d`). Other text,
+/// including non-Vietnamese letters (ñ, ü), is left unchanged; stray
+/// Vietnamese combining marks are dropped.
+pub fn strip_diacritics(text: &str, options: StripOptions) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in chars::compose(text).chars() {
+        if c.is_ascii() {
+            out.push(c);
+            continue;
+        }
+        if combining::is_combining(c) {
+            continue;
+        }
+        match chars::parse_char(c) {
+            Some(p) if p.stroke && options.keep_d => out.push(chars::get_d(p.caps)),
+            Some(p) => out.extend(chars::to_char(p.key, p.caps, 0, 0)),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// Options for `slugify`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SlugOptions {
+    /// Separator between words
+    pub separator: char,
+    /// Lowercase the slug (otherwise letter case is kept)
+    pub lowercase: bool,
+}
+
+impl Default for SlugOptions {
+    fn default() -> Self {
+        Self {
+            separator: '-',
+            lowercase: true,
+        }
+    }
+}
+
+/// Build a URL/file-name slug: ASCII letters and digits joined by a separator
+///
+/// "Đường Lê Lợi, Q.1" → "duong-le-loi-q-1". Diacritics are stripped (đ → d),
+/// every other run of non-alphanumeric chars becomes one separator, and
+/// separators at the ends are trimmed.
+pub fn slugify(text: &str, options: SlugOptions) -> String {
+    let stripped = strip_diacritics(text, StripOptions::default());
+    let mut out = String::with_capacity(stripped.len());
+    let mut pending_separator = false;
+    for c in stripped.chars() {
+        if !c.is_ascii_alphanumeric() {
+            pending_separator = !out.is_empty();
+            continue;
+        }
+        if pending_separator {
+            out.push(options.separator);
+            pending_separator = false;
+        }
+        out.push(if options.lowercase {
+            c.to_ascii_lowercase()
+        } else {
+            c
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_every_vietnamese_letter() {
+        for c in crate::data::charset::vietnamese_letters() {
+            let s = strip_diacritics(&c.to_string(), StripOptions::default());
+            assert!(s.len() == 1 && s.is_ascii(), "'{}' → '{}'", c, s);
+        }
+    }
+}
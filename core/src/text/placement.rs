@@ -0,0 +1,117 @@
+//! Tone Placement - Rewrite existing text to old-style or new-style marks
+//!
+//! `Engine::set_modern_tone` only affects newly typed text, so documents end
+//! up mixing "hòa"/"hoà" and "thúy"/"thuý". Each word is parsed back into
+//! buffer chars, checked with the engine's syllable validation, and its mark
+//! is moved with `Phonology::find_tone_position`, the same rule the engine
+//! applies while typing.
+
+use std::ops::Range;
+
+use super::words;
+use crate::data::chars::{self, combining, mark};
+use crate::data::vowel::Phonology;
+use crate::engine::buffer::{Buffer, Char};
+use crate::engine::{syllable, validation};
+use crate::utils;
+
+/// Longest Vietnamese syllable is 7 letters ("nghiêng"); skip anything longer
+const MAX_SYLLABLE: usize = 7;
+
+/// A word whose mark was moved
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlacementChange {
+    /// Byte range of the word in the original text
+    pub range: Range<usize>,
+    pub before: String,
+    pub after: String,
+}
+
+/// Result of `normalize_tone_placement`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Normalized {
+    pub text: String,
+    /// Changed words, in text order
+    pub changes: Vec<PlacementChange>,
+}
+
+/// Rewrite every word to modern (hoà, thuý) or traditional (hòa, thúy) placement
+///
+/// Words that don't parse as Vietnamese syllables are left alone, as is all
+/// text between words. Decomposed (NFD) words stay decomposed.
+pub fn normalize_tone_placement(text: &str, modern: bool) -> Normalized {
+    let mut out = String::with_capacity(text.len());
+    let mut changes = Vec::new();
+    let mut last = 0;
+    for (start, word) in words(text) {
+        let Some(after) = normalize_word(word, modern) else {
+            continue;
+        };
+        let range = start..start + word.len();
+        out.push_str(&text[last..range.start]);
+        out.push_str(&after);
+        last = range.end;
+        changes.push(PlacementChange {
+            range,
+            before: word.to_string(),
+            after,
+        });
+    }
+    out.push_str(&text[last..]);
+    Normalized { text: out, changes }
+}
+
+/// Move the mark of one word; None if it is not a Vietnamese syllable or
+/// already follows the convention
+pub fn normalize_word(word: &str, modern: bool) -> Option<String> {
+    let mut buf = Buffer::new();
+    let mut rest = word;
+    while !rest.is_empty() {
+        let (p, len) = chars::parse_grapheme(rest)?;
+        if buf.len() == MAX_SYLLABLE {
+            return None;
+        }
+        let mut c = Char::new(p.key, p.caps);
+        c.tone = p.tone;
+        c.mark = p.mark;
+        c.stroke = p.stroke;
+        buf.push(c);
+        rest = &rest[len..];
+    }
+
+    let keys: Vec<u16> = buf.iter().map(|c| c.key).collect();
+    let tones: Vec<u8> = buf.iter().map(|c| c.tone).collect();
+    if syllable::parse(&keys).is_empty() || !validation::is_valid_with_tones(&keys, &tones) {
+        return None;
+    }
+    let mut marked = buf.iter().enumerate().filter(|(_, c)| c.mark != mark::NONE);
+    let (old_pos, mark_value) = match (marked.next(), marked.next()) {
+        (Some((pos, c)), None) => (pos, c.mark),
+        _ => return None,
+    };
+
+    let vowels = utils::collect_vowels(&buf);
+    let last_vowel_pos = vowels.last()?.pos;
+    let has_final = utils::has_final_consonant(&buf, last_vowel_pos);
+    let has_qu = utils::has_qu_initial(&buf);
+    let has_gi = utils::has_gi_initial(&buf);
+    let new_pos = Phonology::find_tone_position(&vowels, has_final, modern, has_qu, has_gi);
+    if new_pos == old_pos {
+        return None;
+    }
+    buf.get_mut(old_pos)?.mark = mark::NONE;
+    buf.get_mut(new_pos)?.mark = mark_value;
+
+    let out = buf.to_full_string();
+    if !word.chars().any(combining::is_combining) {
+        return Some(out);
+    }
+    Some(
+        out.chars()
+            .flat_map(|c| match chars::decompose(c) {
+                Some((parts, len)) => parts[..len].to_vec(),
+                None => vec![c],
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,112 @@
+//! Spell Checking - Lint text with the typing engine's syllable rules
+//!
+//! Words in `vi.dic` (including loanwords and acronyms like "email", "HTML")
+//! are accepted. Anything else is checked like a typed syllable
+//! (`validation::validate`), then against rules that only make sense for
+//! finished text (marks on stop finals); the first failing check explains
+//! the issue, or `NotInDictionary` if the syllable is well-formed.
+
+use std::ops::Range;
+
+use super::words;
+use crate::data::chars::{self, mark};
+use crate::data::{constants, dictionary, keys};
+use crate::engine::syllable;
+use crate::engine::validation::{self, BufferSnapshot, ValidationResult};
+
+/// Why a syllable was rejected
+#[derive(Clone, Debug, PartialEq)]
+pub enum Reason {
+    /// Not a Vietnamese syllable: bad initial, final or vowel pattern
+    Invalid(ValidationResult),
+    /// Broken spelling rule from `SPELLING_RULES` ("c before e/i/y", "ng before e/i", ...)
+    Spelling(&'static str),
+    /// Stop finals (p, t, c, ch) only take sắc or nặng
+    StopFinalMark,
+    /// Well-formed, but not a word in `vi.dic`
+    NotInDictionary,
+}
+
+/// A misspelled syllable
+#[derive(Clone, Debug, PartialEq)]
+pub struct Issue {
+    /// Byte range of the syllable in the checked text
+    pub span: Range<usize>,
+    pub reason: Reason,
+}
+
+/// Check every syllable of `text`, NFC or NFD
+///
+/// Words are runs of letters; digits, punctuation and spaces separate them.
+pub fn spellcheck(text: &str) -> Vec<Issue> {
+    words(text)
+        .filter_map(|(start, word)| {
+            check_syllable(word).map(|reason| Issue {
+                span: start..start + word.len(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// Check one syllable; None if it is correct
+pub fn check_syllable(word: &str) -> Option<Reason> {
+    let composed = chars::compose(word);
+    if dictionary::contains(&composed) {
+        return None;
+    }
+    let mut keys = Vec::with_capacity(composed.len());
+    let mut tones = Vec::with_capacity(composed.len());
+    let mut marks = Vec::with_capacity(composed.len());
+    for c in composed.chars() {
+        // Stray combining mark: can't be a dictionary word either
+        let Some(p) = chars::parse_char(c) else {
+            return Some(Reason::NotInDictionary);
+        };
+        keys.push(p.key);
+        tones.push(p.tone);
+        marks.push(p.mark);
+    }
+
+    let snap = BufferSnapshot {
+        keys,
+        tones,
+        has_tone_info: true,
+        allow_foreign_consonants: false,
+    };
+    match validation::validate(&snap) {
+        ValidationResult::Valid => {}
+        ValidationResult::InvalidSpelling => {
+            return Some(Reason::Spelling(spelling_rule(&snap.keys)));
+        }
+        other => return Some(Reason::Invalid(other)),
+    }
+
+    if has_stop_final(&snap.keys) && !marks.iter().any(|&m| m == mark::SAC || m == mark::NANG) {
+        return Some(Reason::StopFinalMark);
+    }
+    Some(Reason::NotInDictionary)
+}
+
+/// Message of the spelling rule `keys` breaks
+fn spelling_rule(keys: &[u16]) -> &'static str {
+    let s = syllable::parse(keys);
+    let initial: Vec<u16> = s.initial.iter().map(|&i| keys[i]).collect();
+    let first_vowel = s.glide.or(s.vowel.first().copied()).map(|i| keys[i]);
+    constants::SPELLING_RULES
+        .iter()
+        .find(|(consonant, vowels, _)| {
+            initial == *consonant && first_vowel.is_some_and(|v| vowels.contains(&v))
+        })
+        .map_or("spelling", |&(_, _, msg)| msg)
+}
+
+/// Check if the syllable ends in p, t, c or ch
+fn has_stop_final(keys: &[u16]) -> bool {
+    let s = syllable::parse(keys);
+    let final_c: Vec<u16> = s.final_c.iter().map(|&i| keys[i]).collect();
+    matches!(
+        final_c.as_slice(),
+        [keys::P] | [keys::T] | [keys::C] | [keys::C, keys::H]
+    )
+}
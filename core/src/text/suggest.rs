@@ -0,0 +1,199 @@
+//! Suggestions - Corrections for invalid Vietnamese syllables
+//!
+//! Candidates are generated by edits on the buffer representation (key,
+//! tone, mark, stroke) rather than on characters, so one edit is one typing
+//! mistake: a missing circumflex, the wrong mark, ch/tr, s/x or d/gi mixed
+//! up, c/t or n/ng at the end. Up to two edits are tried; candidates in
+//! `vi.dic` are ranked by edit count, then by how common the mistake is.
+
+use std::collections::HashMap;
+
+use crate::data::chars::{self, mark, tone};
+use crate::data::{dictionary, keys};
+use crate::engine::buffer::{Buffer, Char};
+
+/// Edits applied per candidate (more gives noisy suggestions)
+const MAX_EDITS: u32 = 2;
+
+/// Initials commonly confused with each other (spelling rules, dialects)
+const INITIAL_GROUPS: &[&[&[u16]]] = &[
+    &[&[keys::C], &[keys::K], &[keys::Q]],
+    &[&[keys::G], &[keys::G, keys::H]],
+    &[&[keys::N, keys::G], &[keys::N, keys::G, keys::H]],
+    &[&[keys::C, keys::H], &[keys::T, keys::R]],
+    &[&[keys::S], &[keys::X]],
+    &[&[keys::D], &[keys::G, keys::I], &[keys::R]],
+    &[&[keys::L], &[keys::N]],
+];
+
+/// Finals commonly confused with each other
+const FINAL_GROUPS: &[&[&[u16]]] = &[
+    &[&[keys::C], &[keys::T], &[keys::C, keys::H]],
+    &[&[keys::N], &[keys::N, keys::G], &[keys::N, keys::H]],
+];
+
+/// Cost of an edit: marks and diacritics are the most common slips
+#[derive(Clone, Copy)]
+enum Edit {
+    Mark = 2,
+    Tone = 3,
+    Stroke = 4,
+    Initial = 5,
+    Final = 6,
+}
+
+/// Up to `n` dictionary words close to `word`, best first
+///
+/// Returns nothing for text that isn't made of Vietnamese letters. `word`
+/// itself is never suggested.
+pub fn suggest(word: &str, n: usize) -> Vec<String> {
+    let composed = chars::compose(word);
+    let mut start = Vec::with_capacity(composed.len());
+    for c in composed.chars() {
+        let Some(p) = chars::parse_char(c) else {
+            return Vec::new();
+        };
+        let mut ch = Char::new(p.key, p.caps);
+        ch.tone = p.tone;
+        ch.mark = p.mark;
+        ch.stroke = p.stroke;
+        start.push(ch);
+    }
+    if start.is_empty() || n == 0 {
+        return Vec::new();
+    }
+
+    // Best (edits, cost) per candidate spelling
+    let mut seen: HashMap<String, (u32, u32)> = HashMap::new();
+    let mut frontier = vec![(start, 0u32)];
+    for edits in 1..=MAX_EDITS {
+        let mut next = Vec::new();
+        for (chars, cost) in &frontier {
+            for (candidate, edit) in neighbours(chars) {
+                let cost = cost + edit as u32;
+                let text = render(&candidate);
+                let best = seen.entry(text).or_insert((edits, cost));
+                if (edits, cost) < *best {
+                    *best = (edits, cost);
+                }
+                if edits < MAX_EDITS {
+                    next.push((candidate, cost));
+                }
+            }
+        }
+        frontier = next;
+    }
+
+    let mut ranked: Vec<(String, (u32, u32))> = seen
+        .into_iter()
+        .filter(|(text, _)| *text != composed && dictionary::contains(text))
+        .collect();
+    ranked.sort_by(|(a, ka), (b, kb)| ka.cmp(kb).then_with(|| super::compare(a, b)));
+    ranked.into_iter().take(n).map(|(text, _)| text).collect()
+}
+
+/// Every single-edit variant of `word`
+fn neighbours(word: &[Char]) -> Vec<(Vec<Char>, Edit)> {
+    let mut out = Vec::new();
+    let vowels: Vec<usize> = (0..word.len())
+        .filter(|&i| keys::is_vowel(word[i].key))
+        .collect();
+
+    // Mark: any mark (or none) on any vowel, replacing the current one
+    let current = word.iter().map(|c| c.mark).find(|&m| m != mark::NONE);
+    for &pos in &vowels {
+        for m in mark::NONE..=mark::NANG {
+            let mut w = word.to_vec();
+            w.iter_mut().for_each(|c| c.mark = mark::NONE);
+            w[pos].mark = m;
+            if m == mark::NONE && current.is_none() {
+                continue;
+            }
+            out.push((w, Edit::Mark));
+        }
+    }
+
+    // Tone: circumflex/horn/breve added, removed or swapped
+    for &pos in &vowels {
+        let options: &[u8] = match word[pos].key {
+            keys::A | keys::O => &[tone::NONE, tone::CIRCUMFLEX, tone::HORN],
+            keys::E => &[tone::NONE, tone::CIRCUMFLEX],
+            keys::U => &[tone::NONE, tone::HORN],
+            _ => &[],
+        };
+        for &t in options.iter().filter(|&&t| t != word[pos].tone) {
+            let mut w = word.to_vec();
+            w[pos].tone = t;
+            out.push((w, Edit::Tone));
+        }
+    }
+
+    // Stroke: d ⇄ đ
+    for (pos, c) in word.iter().enumerate().filter(|(_, c)| c.key == keys::D) {
+        let mut w = word.to_vec();
+        w[pos].stroke = !c.stroke;
+        out.push((w, Edit::Stroke));
+    }
+
+    // "gi" + vowel: gi is the initial ("gia" ⇄ "da")
+    let first_vowel = match (word.first(), word.get(1)) {
+        (Some(g), Some(i)) if g.key == keys::G && i.key == keys::I && vowels.len() > 1 => 2,
+        _ => vowels.first().copied().unwrap_or(word.len()),
+    };
+    let last_vowel = vowels.last().map_or(word.len(), |&i| i + 1);
+    swap_groups(
+        word,
+        0..first_vowel,
+        INITIAL_GROUPS,
+        Edit::Initial,
+        &mut out,
+    );
+    swap_groups(
+        word,
+        last_vowel..word.len(),
+        FINAL_GROUPS,
+        Edit::Final,
+        &mut out,
+    );
+    out
+}
+
+/// Replace the consonant cluster in `range` with others from its group
+fn swap_groups(
+    word: &[Char],
+    range: std::ops::Range<usize>,
+    groups: &[&[&[u16]]],
+    edit: Edit,
+    out: &mut Vec<(Vec<Char>, Edit)>,
+) {
+    let mut cluster: Vec<u16> = word[range.clone()].iter().map(|c| c.key).collect();
+    // "gì": the i is both part of "gi" and the vowel, keep it as the vowel
+    let next_is_i = word.get(range.end).is_some_and(|c| c.key == keys::I);
+    if next_is_i && cluster == [keys::G] {
+        cluster.push(keys::I);
+    }
+    let caps = word.get(range.start).is_some_and(|c| c.caps);
+    let all_caps = caps && word[range.clone()].iter().all(|c| c.caps);
+    for group in groups.iter().filter(|g| g.contains(&cluster.as_slice())) {
+        for &other in group.iter().filter(|&&o| o != cluster.as_slice()) {
+            let other = match other.split_last() {
+                Some((&keys::I, rest)) if next_is_i => rest,
+                _ => other,
+            };
+            let mut w = word[..range.start].to_vec();
+            for (i, &key) in other.iter().enumerate() {
+                w.push(Char::new(key, if i == 0 { caps } else { all_caps }));
+            }
+            w.extend_from_slice(&word[range.end..]);
+            out.push((w, edit));
+        }
+    }
+}
+
+fn render(word: &[Char]) -> String {
+    let mut buf = Buffer::new();
+    for &c in word {
+        buf.push(c);
+    }
+    buf.to_full_string()
+}
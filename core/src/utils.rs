@@ -56,6 +56,7 @@ pub fn key_to_char(key: u16, caps: bool) -> Option<char> {
 
 /// Convert key code to character with shift state support
 /// Handles shifted symbols like @ (Shift+2), # (Shift+3), etc.
+/// and unshifted punctuation (recorded for VIQR modifiers and Telex brackets)
 pub fn key_to_char_ext(key: u16, caps: bool, shift: bool) -> Option<char> {
     // If shift is pressed, check for shifted symbols first
     if shift {
@@ -84,7 +85,113 @@ pub fn key_to_char_ext(key: u16, caps: bool, shift: bool) -> Option<char> {
             _ => key_to_char(key, caps),
         };
     }
-    key_to_char(key, caps)
+    match key {
+        keys::MINUS => Some('-'),
+        keys::EQUAL => Some('='),
+        keys::SEMICOLON => Some(';'),
+        keys::QUOTE => Some('\''),
+        keys::COMMA => Some(','),
+        keys::DOT => Some('.'),
+        keys::SLASH => Some('/'),
+        keys::BACKSLASH => Some('\\'),
+        keys::LBRACKET => Some('['),
+        keys::RBRACKET => Some(']'),
+        keys::BACKQUOTE => Some('`'),
+        _ => key_to_char(key, caps),
+    }
+}
+
+/// Convert a character to `(key, caps, shift)` on the engine's key model
+///
+/// Inverse of `key_to_char_ext`: letters map to their key with caps from the
+/// letter case, digits to number keys, and US-layout symbols to their base key
+/// with shift. Whitespace/control characters map to SPACE, TAB, RETURN, DELETE
+/// (backspace) and ESC.
+///
+/// Returns None for characters outside the key model (é, √, ñ, ...).
+pub fn char_to_key_ext(ch: char) -> Option<(u16, bool, bool)> {
+    let key = match ch.to_ascii_lowercase() {
+        'a' => keys::A,
+        'b' => keys::B,
+        'c' => keys::C,
+        'd' => keys::D,
+        'e' => keys::E,
+        'f' => keys::F,
+        'g' => keys::G,
+        'h' => keys::H,
+        'i' => keys::I,
+        'j' => keys::J,
+        'k' => keys::K,
+        'l' => keys::L,
+        'm' => keys::M,
+        'n' => keys::N,
+        'o' => keys::O,
+        'p' => keys::P,
+        'q' => keys::Q,
+        'r' => keys::R,
+        's' => keys::S,
+        't' => keys::T,
+        'u' => keys::U,
+        'v' => keys::V,
+        'w' => keys::W,
+        'x' => keys::X,
+        'y' => keys::Y,
+        'z' => keys::Z,
+        _ => {
+            let (key, shift) = match ch {
+                '0' => (keys::N0, false),
+                '1' => (keys::N1, false),
+                '2' => (keys::N2, false),
+                '3' => (keys::N3, false),
+                '4' => (keys::N4, false),
+                '5' => (keys::N5, false),
+                '6' => (keys::N6, false),
+                '7' => (keys::N7, false),
+                '8' => (keys::N8, false),
+                '9' => (keys::N9, false),
+                ' ' => (keys::SPACE, false),
+                '\t' => (keys::TAB, false),
+                '\r' | '\n' => (keys::RETURN, false),
+                '\u{8}' | '\u{7f}' => (keys::DELETE, false),
+                '\u{1b}' => (keys::ESC, false),
+                '-' => (keys::MINUS, false),
+                '=' => (keys::EQUAL, false),
+                ';' => (keys::SEMICOLON, false),
+                '\'' => (keys::QUOTE, false),
+                ',' => (keys::COMMA, false),
+                '.' => (keys::DOT, false),
+                '/' => (keys::SLASH, false),
+                '\\' => (keys::BACKSLASH, false),
+                '[' => (keys::LBRACKET, false),
+                ']' => (keys::RBRACKET, false),
+                '`' => (keys::BACKQUOTE, false),
+                '!' => (keys::N1, true),
+                '@' => (keys::N2, true),
+                '#' => (keys::N3, true),
+                '$' => (keys::N4, true),
+                '%' => (keys::N5, true),
+                '^' => (keys::N6, true),
+                '&' => (keys::N7, true),
+                '*' => (keys::N8, true),
+                '(' => (keys::N9, true),
+                ')' => (keys::N0, true),
+                '_' => (keys::MINUS, true),
+                '+' => (keys::EQUAL, true),
+                ':' => (keys::SEMICOLON, true),
+                '"' => (keys::QUOTE, true),
+                '<' => (keys::COMMA, true),
+                '>' => (keys::DOT, true),
+                '?' => (keys::SLASH, true),
+                '|' => (keys::BACKSLASH, true),
+                '{' => (keys::LBRACKET, true),
+                '}' => (keys::RBRACKET, true),
+                '~' => (keys::BACKQUOTE, true),
+                _ => return None,
+            };
+            return Some((key, false, shift));
+        }
+    };
+    Some((key, ch.is_ascii_uppercase(), false))
 }
 
 /// Collect vowels from buffer with phonological info
@@ -395,6 +502,16 @@ mod test_utils {
         }
     }
 
+    /// Run VIQR test cases
+    pub fn viqr(cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            let mut e = Engine::new();
+            e.set_method(2);
+            let result = type_word(&mut e, input);
+            assert_eq!(result, *expected, "[VIQR] '{}' → '{}'", input, result);
+        }
+    }
+
     /// Run Telex test cases with traditional tone placement (hòa, thúy style)
     pub fn telex_traditional(cases: &[(&str, &str)]) {
         for (input, expected) in cases {
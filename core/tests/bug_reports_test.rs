@@ -3,6 +3,7 @@
 
 mod common;
 use common::{telex, telex_auto_restore, vni};
+use gonhanh_core::engine::trace::Trace;
 use gonhanh_core::engine::Engine;
 use gonhanh_core::utils::type_word;
 
@@ -1941,3 +1942,42 @@ fn bug_sess_auto_restore() {
         ("hiss ", "his "), // hiss → his (exception)
     ]);
 }
+
+// =============================================================================
+// Recorded traces: tests/data/traces/*.trace
+// Traces attached to bug reports (`ime_trace_start` / `ime_trace_stop`), with
+// the results edited to the expected output once the bug is fixed.
+// Check one with `cargo run --bin gonhanh-replay -- <file>`.
+// =============================================================================
+
+#[test]
+fn bug_report_traces() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/traces");
+    let mut replayed = 0;
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension() != Some("trace".as_ref()) {
+            continue;
+        }
+        let text = std::fs::read_to_string(&path).unwrap();
+        let trace = Trace::parse(&text).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        let mismatches: Vec<String> = trace
+            .mismatches()
+            .iter()
+            .map(|m| {
+                format!(
+                    "key {} ({}): expected {}, got {}",
+                    m.index, m.input, m.expected, m.actual
+                )
+            })
+            .collect();
+        assert!(
+            mismatches.is_empty(),
+            "{}:\n{}",
+            path.display(),
+            mismatches.join("\n")
+        );
+        replayed += 1;
+    }
+    assert!(replayed > 0);
+}
@@ -0,0 +1,111 @@
+//! Character Input Tests - Layout-independent `Engine::on_char`
+//!
+//! Platforms on non-QWERTY layouts pass the character the OS produced
+//! instead of a macOS keycode. Results must match keycode-based typing.
+
+mod common;
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::shortcut::Shortcut;
+use gonhanh_core::engine::{Action, Engine};
+use gonhanh_core::utils::{char_to_key_ext, key_to_char_ext};
+
+/// Type a string through `on_char` and return the simulated screen
+fn type_chars(e: &mut Engine, input: &str) -> String {
+    let mut screen = String::new();
+    for c in input.chars() {
+        let r = e.on_char(c, false);
+        if r.action == Action::Send as u8 {
+            for _ in 0..r.backspace {
+                screen.pop();
+            }
+            for i in 0..r.count as usize {
+                if let Some(ch) = char::from_u32(r.chars[i]) {
+                    screen.push(ch);
+                }
+            }
+        } else if c == '\u{8}' {
+            screen.pop();
+        } else {
+            screen.push(c);
+        }
+    }
+    screen
+}
+
+fn run(method: u8, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        e.set_method(method);
+        let result = type_chars(&mut e, input);
+        assert_eq!(result, *expected, "[on_char m={}] '{}'", method, input);
+    }
+}
+
+// ============================================================
+// TYPING
+// ============================================================
+
+#[test]
+fn telex_via_chars() {
+    run(
+        0,
+        &[
+            ("vieejt", "việt"),
+            ("nam", "nam"),
+            ("dduowngf", "đường"),
+            ("Vieejt Nam", "Việt Nam"),
+            ("VIEEJT", "VIỆT"),
+        ],
+    );
+}
+
+#[test]
+fn vni_via_chars() {
+    run(1, &[("vie65t", "việt"), ("d9u7o7ng2", "đường")]);
+}
+
+#[test]
+fn vni_shifted_digit_is_symbol() {
+    // '!' is Shift+1 on QWERTY; it must not act as the sắc mark
+    run(1, &[("a!", "a!"), ("a1", "á")]);
+}
+
+#[test]
+fn backspace_via_control_char() {
+    run(0, &[("vieej\u{8}t", "vit"), ("ab\u{8}\u{8}", "")]);
+}
+
+// ============================================================
+// MAPPING
+// ============================================================
+
+#[test]
+fn char_mapping_round_trips() {
+    // Characters `key_to_char_ext` can produce (letters, digits, shifted symbols)
+    let printable = "abcxyzABCXYZ0123456789!@#$%^&*()_+:\"<>?|{}~";
+    for c in printable.chars() {
+        let (key, caps, shift) = char_to_key_ext(c).expect("mapped");
+        assert_eq!(key_to_char_ext(key, caps, shift), Some(c), "'{}'", c);
+    }
+}
+
+#[test]
+fn control_chars_map_to_keys() {
+    assert_eq!(char_to_key_ext('\u{8}'), Some((keys::DELETE, false, false)));
+    assert_eq!(char_to_key_ext('\r'), Some((keys::RETURN, false, false)));
+    assert_eq!(char_to_key_ext('\u{1b}'), Some((keys::ESC, false, false)));
+    assert_eq!(char_to_key_ext('é'), None);
+}
+
+#[test]
+fn unmapped_char_matches_shortcut() {
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::immediate("√", "căn"));
+    let r = e.on_char('√', false);
+    assert_eq!(r.action, Action::Send as u8);
+    let out: String = r.chars[..r.count as usize]
+        .iter()
+        .filter_map(|&c| char::from_u32(c))
+        .collect();
+    assert_eq!(out, "căn");
+}
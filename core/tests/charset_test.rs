@@ -0,0 +1,212 @@
+//! Charset Tests - Legacy output encodings (TCVN3, VNI-Windows, VISCII)
+//! and decomposed Unicode (NFD)
+//!
+//! Types into an editor that stores the encoded chars, then decodes the
+//! screen and compares it with the Unicode result of the same keystrokes.
+
+mod common;
+use gonhanh_core::data::charset::{self, Charset};
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::{Action, Engine};
+use gonhanh_core::utils::{char_to_key_ext, type_word};
+
+const ENCODED: [Charset; 4] = [
+    Charset::Tcvn3,
+    Charset::VniWindows,
+    Charset::Viscii,
+    Charset::UnicodeNfd,
+];
+
+/// Type via `on_char` into a char screen ('<' = backspace, like `type_word`)
+fn type_encoded(e: &mut Engine, input: &str) -> Vec<char> {
+    let mut screen: Vec<char> = Vec::new();
+    for c in input.chars() {
+        let c = if c == '<' { '\u{8}' } else { c };
+        let (key, _, shift) = char_to_key_ext(c).expect("typeable char");
+        let r = e.on_char(c, false);
+        if r.action == Action::Send as u8 {
+            for _ in 0..r.backspace {
+                screen.pop();
+            }
+            for &code in &r.chars[..r.count as usize] {
+                screen.push(char::from_u32(code).unwrap());
+            }
+            if key != keys::SPACE && keys::is_break_ext(key, shift) && !r.key_consumed() {
+                screen.push(c);
+            }
+        } else if key == keys::DELETE {
+            screen.pop();
+        } else {
+            screen.push(c);
+        }
+    }
+    screen
+}
+
+/// Screen chars as bytes in `cs` (legacy output is one byte per char)
+fn to_bytes(screen: &[char], cs: Charset) -> Vec<u8> {
+    if cs == Charset::UnicodeNfd {
+        return screen.iter().collect::<String>().into_bytes();
+    }
+    screen
+        .iter()
+        .map(|&c| u8::try_from(c as u32).expect("legacy output is one byte per char"))
+        .collect()
+}
+
+fn assert_same_as_unicode(inputs: &[&str], charsets: &[Charset]) {
+    for input in inputs {
+        let expected = type_word(&mut Engine::new(), input);
+        for &cs in charsets {
+            let mut e = Engine::new();
+            e.set_charset(cs);
+            let bytes = to_bytes(&type_encoded(&mut e, input), cs);
+            assert_eq!(
+                charset::decode(&bytes, cs),
+                expected,
+                "[{:?}] '{}' → {:02X?}",
+                cs,
+                input,
+                bytes
+            );
+        }
+    }
+}
+
+#[test]
+fn words_match_unicode() {
+    assert_same_as_unicode(
+        &[
+            "vieejt nam",
+            "tieesng vieejt",
+            "dduwowngf phoos",
+            "nguyeexn",
+            "khoong bieest",
+            "awn cowm",
+        ],
+        &ENCODED,
+    );
+}
+
+#[test]
+fn mark_changes_and_reverts() {
+    // Replacing a 2-byte VNI-Windows letter must delete both bytes
+    assert_same_as_unicode(&["asf", "vieejtj", "ass", "dddd", "toasn"], &ENCODED);
+}
+
+#[test]
+fn backspace_over_legacy_letters() {
+    assert_same_as_unicode(
+        &["vieejt<<", "dduwowng<<f", "vieejt <s", "tooi <<a"],
+        &ENCODED,
+    );
+}
+
+#[test]
+fn punctuation_after_word() {
+    assert_same_as_unicode(&["vieejt, nam.", "ddi!"], &ENCODED);
+}
+
+#[test]
+fn uppercase() {
+    // TCVN3 has no separate bytes for uppercase toned letters
+    assert_same_as_unicode(
+        &["VIEEJT NAM", "DDUWOWNGF", "Ddaays"],
+        &[Charset::VniWindows, Charset::Viscii, Charset::UnicodeNfd],
+    );
+}
+
+#[test]
+fn vni_windows_backspace_counts() {
+    let mut e = Engine::new();
+    e.set_charset(Charset::VniWindows);
+    for c in "vieej".chars() {
+        e.on_char(c, false);
+    }
+    // "ệ" is 2 chars on screen: e + nặng-circumflex byte
+    let r = e.on_char('\u{8}', false);
+    assert_eq!(r.action, Action::Send as u8);
+    assert_eq!(r.backspace, 2);
+    assert_eq!(r.count, 0);
+    assert!(r.key_consumed());
+
+    // 1-byte letters use the native backspace
+    let r = e.on_char('\u{8}', false);
+    assert_eq!(r.action, Action::None as u8);
+}
+
+#[test]
+fn suggestion_is_encoded() {
+    for cs in [Charset::VniWindows, Charset::Tcvn3] {
+        let mut e = Engine::new();
+        e.set_charset(cs);
+        e.set_suggest_on_invalid(true);
+        let screen = type_encoded(&mut e, "tiengs");
+        let r = e.on_char(' ', false);
+        assert_eq!(r.action, Action::Suggest as u8);
+        // Backspace covers "tiéng" as it sits on screen
+        assert_eq!(r.backspace as usize, screen.len(), "[{:?}]", cs);
+        let chars: Vec<char> = r.chars[..r.count as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        assert_eq!(charset::decode(&to_bytes(&chars, cs), cs), "tiếng");
+    }
+}
+
+#[test]
+fn unicode_is_default() {
+    let mut e = Engine::new();
+    assert_eq!(e.charset(), Charset::Unicode);
+    assert_eq!(type_word(&mut e, "vieejt"), "việt");
+}
+
+#[test]
+fn nfd_output() {
+    let mut e = Engine::new();
+    e.set_charset(Charset::UnicodeNfd);
+    let screen: String = type_encoded(&mut e, "vieejt").into_iter().collect();
+    assert_eq!(screen, "vie\u{0323}\u{0302}t");
+}
+
+#[test]
+fn nfd_mark_change_counts_combining_chars() {
+    let mut e = Engine::new();
+    e.set_charset(Charset::UnicodeNfd);
+    for c in "tooi".chars() {
+        e.on_char(c, false);
+    }
+    // "tôi" on screen is t, o, U+0302, i
+    let r = e.on_char('j', false);
+    let out: String = r.chars[..r.count as usize]
+        .iter()
+        .filter_map(|&c| char::from_u32(c))
+        .collect();
+    // Replacing "ôi" (3 chars) with "ội"
+    assert_eq!(r.backspace, 3);
+    assert_eq!(out, "o\u{0323}\u{0302}i");
+}
+
+#[test]
+fn restore_nfd_word() {
+    let mut e = Engine::new();
+    e.set_charset(Charset::UnicodeNfd);
+    e.restore_word("vie\u{0323}\u{0302}t");
+    assert_eq!(e.get_buffer_string(), "việt");
+
+    // Changing the mark deletes every combining char of "ệt"
+    let r = e.on_char('s', false);
+    let out: String = r.chars[..r.count as usize]
+        .iter()
+        .filter_map(|&c| char::from_u32(c))
+        .collect();
+    assert_eq!(r.backspace, 4);
+    assert_eq!(out, "e\u{0302}\u{0301}t");
+}
+
+#[test]
+fn restore_nfd_word_in_unicode_mode() {
+    let mut e = Engine::new();
+    e.restore_word("đu\u{031B}o\u{031B}\u{0300}ng");
+    assert_eq!(e.get_buffer_string(), "đường");
+}
@@ -0,0 +1,87 @@
+//! Collation Tests - Vietnamese alphabetical order
+
+mod common;
+use gonhanh_core::text::{compare, sort_key};
+use std::cmp::Ordering;
+
+fn sorted(words: &[&str]) -> Vec<String> {
+    let mut v: Vec<String> = words.iter().map(|s| s.to_string()).collect();
+    v.sort_by(|a, b| compare(a, b));
+    v
+}
+
+#[test]
+fn alphabet_order() {
+    let alphabet = [
+        "a", "ă", "â", "b", "c", "d", "đ", "e", "ê", "g", "h", "i", "k", "l", "m", "n", "o", "ô",
+        "ơ", "p", "q", "r", "s", "t", "u", "ư", "v", "x", "y",
+    ];
+    let mut shuffled = alphabet;
+    shuffled.reverse();
+    assert_eq!(sorted(&shuffled), alphabet);
+}
+
+#[test]
+fn marks_are_secondary() {
+    // ngang, huyền, hỏi, ngã, sắc, nặng
+    let order = ["ma", "mà", "mả", "mã", "má", "mạ"];
+    let mut shuffled = order;
+    shuffled.rotate_left(3);
+    assert_eq!(sorted(&shuffled), order);
+
+    // Letters decide before marks: "má" < "mai" although á > a
+    assert_eq!(compare("má", "mai"), Ordering::Less);
+    assert_eq!(compare("mạnh", "măng"), Ordering::Less);
+}
+
+#[test]
+fn d_stroke_after_d() {
+    assert_eq!(
+        sorted(&["Đức", "Zoe", "Dũng", "Bình", "An"]),
+        ["An", "Bình", "Dũng", "Đức", "Zoe"]
+    );
+}
+
+#[test]
+fn names() {
+    assert_eq!(
+        sorted(&[
+            "Nguyễn Văn Anh",
+            "Nguyễn An",
+            "Ngô Bảo Châu",
+            "Nguyễn Ánh",
+            "Lê Lợi",
+        ]),
+        [
+            "Lê Lợi",
+            "Ngô Bảo Châu",
+            "Nguyễn An",
+            "Nguyễn Ánh",
+            "Nguyễn Văn Anh",
+        ]
+    );
+}
+
+#[test]
+fn case_is_tertiary() {
+    assert_eq!(compare("an", "An"), Ordering::Less);
+    assert_eq!(compare("An", "anh"), Ordering::Less);
+    assert_eq!(compare("Đà", "đà"), Ordering::Greater);
+}
+
+#[test]
+fn nfd_sorts_like_nfc() {
+    assert_eq!(sort_key("vie\u{0323}\u{0302}t"), sort_key("việt"));
+    assert_eq!(
+        compare("đu\u{031B}o\u{031B}\u{0300}ng", "đường"),
+        Ordering::Equal
+    );
+}
+
+#[test]
+fn digits_and_punctuation_before_letters() {
+    assert_eq!(
+        sorted(&["b", "2", "a", "-", "ß"]),
+        ["-", "2", "a", "b", "ß"]
+    );
+}
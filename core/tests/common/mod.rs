@@ -8,7 +8,7 @@
 
 // Re-export core test utilities
 pub use gonhanh_core::utils::{
-    telex, telex_auto_capitalize, telex_auto_restore, telex_traditional, type_word, vni,
+    telex, telex_auto_capitalize, telex_auto_restore, telex_traditional, type_word, viqr, vni,
     vni_traditional,
 };
 
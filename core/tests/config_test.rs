@@ -0,0 +1,85 @@
+//! Config Tests - Serializable engine options
+
+mod common;
+use gonhanh_core::data::charset::Charset;
+use gonhanh_core::engine::config::EngineConfig;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::input::custom;
+use gonhanh_core::utils::type_word;
+
+#[test]
+fn configure_applies_options() {
+    let mut e = Engine::new();
+    let errors = e.configure(
+        "# Terminal\n\
+         method = vni\n\
+         english_auto_restore = true\n\
+         modern_tone = false\n",
+    );
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(type_word(&mut e, "hoa2 "), "hòa ");
+
+    e.configure("method = telex");
+    assert_eq!(type_word(&mut e, "text "), "text ");
+    // Keys not mentioned keep their value
+    assert!(!e.config().modern_tone);
+}
+
+#[test]
+fn written_config_round_trips() {
+    let mut e = Engine::new();
+    e.set_method(2);
+    e.set_esc_restore(true);
+    e.set_charset(Charset::VniWindows);
+    e.learning_mut().set_threshold(5);
+    let text = e.config().to_string();
+    assert!(text.contains("method = viqr\n"));
+    assert!(text.contains("charset = vni_windows\n"));
+
+    let mut other = Engine::new();
+    assert!(other.configure(&text).is_empty());
+    assert_eq!(other.config(), e.config());
+    assert_eq!(EngineConfig::parse(&text), Ok(e.config()));
+}
+
+#[test]
+fn errors_are_reported_per_line() {
+    let mut e = Engine::new();
+    let errors = e.configure(
+        "method = vni\n\
+         modern = false\n\
+         free_tone = yes\n\
+         charset = utf8\n\
+         learn_threshold = 0\n\
+         esc_restore\n\
+         method = dvorak\n\
+         bracket_shortcut = true\n",
+    );
+    let lines: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        lines,
+        [
+            "line 2: unknown key `modern`",
+            "line 3: `free_tone` must be true or false, got `yes`",
+            "line 4: unknown charset `utf8` (expected unicode, tcvn3, vni_windows, viscii or unicode_nfd)",
+            "line 5: `learn_threshold` must be 1-127, got `0`",
+            "line 6: expected `key = value`, got `esc_restore`",
+            "line 7: unknown method `dvorak` (expected telex, vni, viqr or a custom method id)",
+        ]
+    );
+    // Valid lines still apply
+    let config = e.config();
+    assert_eq!(config.method, 1);
+    assert!(config.bracket_shortcut);
+    assert!(!config.free_tone);
+}
+
+#[test]
+fn custom_method_by_id() {
+    let id = custom::register("[marks]\ns = sac\n").unwrap();
+    let mut e = Engine::new();
+    assert!(e.configure(&format!("method = {}", id)).is_empty());
+    assert_eq!(e.config().get("method"), Some(id.to_string()));
+    // Unregistered ids are rejected
+    assert_eq!(e.configure("method = 200").len(), 1);
+}
@@ -0,0 +1,70 @@
+//! Context Auto-Restore Tests - Ambiguous words follow the neighbouring words
+//!
+//! "as" is English and "á" Vietnamese: alone the word stays "á", but after
+//! two English words it is restored. "refer" alone is restored, but after
+//! two Vietnamese words it stays "rể". ASCII words that aren't Vietnamese
+//! count as English, dictionary words or not.
+
+mod common;
+use common::telex_auto_restore;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+#[test]
+fn english_context_restores_ambiguous_word() {
+    telex_auto_restore(&[
+        ("as ", "á "),
+        ("play music as ", "play music as "),
+        ("play music mix ", "play music mix "),
+        ("play server six ", "play server six "),
+        // "the" could be either language: skipped, "music" alone isn't enough
+        ("music the as ", "music the á "),
+        // Not dictionary words, but typed without Vietnamese transforms
+        ("deploy the server as ", "deploy the server as "),
+        ("kubectl apply as ", "kubectl apply as "),
+    ]);
+}
+
+#[test]
+fn vietnamese_context_keeps_ambiguous_word() {
+    telex_auto_restore(&[
+        ("refer ", "refer "),
+        ("anh aays laf refer ", "anh ấy là rể "),
+        ("anhs sangs lose ", "ánh sáng loé "),
+        // One Vietnamese word is not enough
+        ("sangs lose ", "sáng lose "),
+    ]);
+}
+
+#[test]
+fn mixed_context_decides_alone() {
+    telex_auto_restore(&[
+        ("server nhieeuf as ", "server nhiều á "),
+        ("thaays server refer ", "thấy server refer "),
+    ]);
+}
+
+#[test]
+fn punctuation_ends_context() {
+    telex_auto_restore(&[("play music, as ", "play music, á ")]);
+}
+
+#[test]
+fn english_context_keeps_reverts() {
+    // Only ambiguous words follow the context: a manual revert still collapses
+    telex_auto_restore(&[
+        ("play music tooo ", "play music too "),
+        ("play music noww ", "play music now "),
+    ]);
+}
+
+#[test]
+fn backspace_into_previous_word_drops_it_from_context() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    // "server" is un-committed and retyped as "nhiều": context is mixed again
+    assert_eq!(
+        type_word(&mut e, "deploy server <<<<<<<nhieeuf as "),
+        "deploy nhiều á "
+    );
+}
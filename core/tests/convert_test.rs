@@ -0,0 +1,76 @@
+//! Convert Tests - Whole-text Telex/VNI conversion
+
+mod common;
+use gonhanh_core::convert::{telex_to_unicode, vni_to_unicode, Options};
+
+#[test]
+fn telex_sentence() {
+    assert_eq!(
+        telex_to_unicode(
+            "Tieengs Vieejt laf ngoon ngwx cuar nguwowif Vieejt Nam.",
+            Options::default()
+        ),
+        "Tiếng Việt là ngôn ngữ của người Việt Nam."
+    );
+}
+
+#[test]
+fn vni_sentence() {
+    assert_eq!(
+        vni_to_unicode("Tie61ng Vie65t la2 ngo6n ngu74.", Options::default()),
+        "Tiếng Việt là ngôn ngữ."
+    );
+}
+
+#[test]
+fn uppercase_words() {
+    assert_eq!(
+        telex_to_unicode("VIEEJT NAM, Ddaf Nawngx", Options::default()),
+        "VIỆT NAM, Đà Nẵng"
+    );
+}
+
+#[test]
+fn non_letter_runs_are_preserved() {
+    let opts = Options::default();
+    // No ASCII letters: nothing to convert
+    for text in [
+        "  \t\r\n",
+        "2024-01-01 | 10:30 → 11:00",
+        "„quotes” «guillemets» — 🇻🇳",
+        "đường",
+    ] {
+        assert_eq!(telex_to_unicode(text, opts), text);
+    }
+    assert_eq!(
+        telex_to_unicode("(xin chaof) -- \"ddi\"!", opts),
+        "(xin chào) -- \"đi\"!"
+    );
+}
+
+#[test]
+fn tone_placement_option() {
+    let modern = Options::default();
+    let traditional = Options {
+        modern_tone: false,
+        ..modern
+    };
+    assert_eq!(telex_to_unicode("hoaf thuys", modern), "hoà thuý");
+    assert_eq!(telex_to_unicode("hoaf thuys", traditional), "hòa thúy");
+}
+
+#[test]
+fn english_auto_restore_option() {
+    let restore = Options {
+        english_auto_restore: true,
+        ..Options::default()
+    };
+    assert_eq!(telex_to_unicode("text file", restore), "text file");
+    assert_eq!(telex_to_unicode("vieejt text", restore), "việt text");
+}
+
+#[test]
+fn words_are_independent() {
+    // Each word starts fresh: no state leaks across the boundary
+    assert_eq!(telex_to_unicode("as as,as", Options::default()), "á á,á");
+}
@@ -0,0 +1,123 @@
+//! Custom Method Tests - Keymap-defined input methods
+
+mod common;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::input::custom;
+use gonhanh_core::utils::type_word;
+
+const TELEX_NO_Z: &str = "
+# Telex without z removal
+[marks]
+s = sac
+f = huyen
+r = hoi
+x = nga
+j = nang
+
+[tones]
+a = circumflex a
+e = circumflex e
+o = circumflex o
+w = horn a o u
+
+[stroke]
+d
+";
+
+const TELEX_BRACKETS: &str = "
+# Telex marks, brackets for horn
+[marks]
+s = sac
+f = huyen
+r = hoi
+x = nga
+j = nang
+
+[tones]
+a = circumflex a
+e = circumflex e
+o = circumflex o
+[ = horn o
+] = horn u
+( = breve a
+
+[stroke]
+d
+";
+
+/// Hybrid: VNI digits for marks, Telex letters for tones
+const HYBRID: &str = "
+[marks]
+1 = sac
+2 = huyen
+3 = hoi
+4 = nga
+5 = nang
+
+[tones]
+a = circumflex a
+e = circumflex e
+o = circumflex o
+w = horn a o u
+
+[stroke]
+d
+";
+
+fn run(spec: &str, cases: &[(&str, &str)]) {
+    let id = custom::register(spec).expect("valid spec");
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        e.set_method(id);
+        let result = type_word(&mut e, input);
+        assert_eq!(result, *expected, "[Custom] '{}' → '{}'", input, result);
+    }
+}
+
+#[test]
+fn telex_without_z_removal() {
+    run(
+        TELEX_NO_Z,
+        &[
+            ("vieejt", "việt"),
+            ("ddaays", "đấy"),
+            ("as", "á"),
+            // z is a plain letter: no diacritic removal
+            ("asz", "áz"),
+        ],
+    );
+}
+
+#[test]
+fn telex_with_brackets_for_horn() {
+    run(
+        TELEX_BRACKETS,
+        &[
+            ("o[", "ơ"),
+            ("u]", "ư"),
+            ("tu]o[f", "tườ"),
+            ("ddu]o[ngf", "đường"),
+            ("a(", "ă"),
+            ("a(n", "ăn"),
+            // w is a plain letter here
+            ("aw", "aw"),
+            // Nothing to modify: bracket stays punctuation
+            ("x[", "x["),
+        ],
+    );
+}
+
+#[test]
+fn hybrid_digits_and_letters() {
+    run(
+        HYBRID,
+        &[("vieet5", "việt"), ("duwowng2", "dường"), ("ddaay1", "đấy")],
+    );
+}
+
+#[test]
+fn ambiguous_spec_is_rejected() {
+    let err = custom::register("[marks]\ns = sac\n[stroke]\ns").unwrap_err();
+    assert_eq!(err.line, 4);
+    assert!(err.to_string().contains("already bound"), "{}", err);
+}
@@ -0,0 +1,153 @@
+//! Keystrokes Tests - Vietnamese text back to Telex/VNI/VIQR keys
+
+mod common;
+use gonhanh_core::input::{custom, keystrokes_for, round_trips, Style};
+
+const TELEX: u8 = 0;
+const VNI: u8 = 1;
+const VIQR: u8 = 2;
+
+fn check(method: u8, style: Style, cases: &[(&str, &str)]) {
+    for (word, expected) in cases {
+        let keys = keystrokes_for(word, method, style);
+        assert_eq!(keys.as_deref(), Some(*expected), "[{}] '{}'", method, word);
+        assert!(round_trips(word, method, style), "[{}] '{}'", method, word);
+    }
+}
+
+#[test]
+fn telex_mark_at_end() {
+    check(
+        TELEX,
+        Style::MarkAtEnd,
+        &[
+            ("nguyễn", "nguyeenx"),
+            ("việt", "vieetj"),
+            ("đường", "dduwowngf"),
+            ("ăn", "awn"),
+            ("khoẻ", "khoer"),
+            ("tôi", "tooi"),
+        ],
+    );
+}
+
+#[test]
+fn telex_inline() {
+    check(
+        TELEX,
+        Style::Inline,
+        &[
+            ("nguyễn", "nguyeexn"),
+            ("việt", "vieejt"),
+            ("đường", "dduwowfng"),
+            ("mắm", "mawsm"),
+        ],
+    );
+}
+
+#[test]
+fn vni() {
+    check(
+        VNI,
+        Style::MarkAtEnd,
+        &[
+            ("nguyễn", "nguye6n4"),
+            ("đường", "d9u7o7ng2"),
+            ("ăn", "a8n"),
+        ],
+    );
+    check(
+        VNI,
+        Style::Inline,
+        &[("nguyễn", "nguye64n"), ("việt", "vie65t")],
+    );
+}
+
+#[test]
+fn viqr_inline() {
+    check(
+        VIQR,
+        Style::Inline,
+        &[
+            ("nguyễn", "nguye^~n"),
+            ("đường", "ddu+o+`ng"),
+            ("ăn", "a(n"),
+        ],
+    );
+}
+
+#[test]
+fn uppercase_follows_letter() {
+    check(
+        TELEX,
+        Style::MarkAtEnd,
+        &[("Nguyễn", "Nguyeenx"), ("VIỆT", "VIEETJ"), ("Đà", "DDaf")],
+    );
+    check(VNI, Style::MarkAtEnd, &[("VIỆT", "VIE6T5")]);
+}
+
+#[test]
+fn custom_keymap() {
+    let id = custom::register(
+        "[marks]\n1 = sac\n[tones]\n[ = horn o\nq = circumflex a e o\n[stroke]\nd",
+    )
+    .expect("valid spec");
+    assert_eq!(
+        keystrokes_for("đốn", id, Style::MarkAtEnd).as_deref(),
+        Some("ddoqn1")
+    );
+    // No key for huyền in this keymap
+    assert_eq!(keystrokes_for("đò", id, Style::MarkAtEnd), None);
+}
+
+#[test]
+fn non_letters_rejected() {
+    assert_eq!(keystrokes_for("việt nam", TELEX, Style::MarkAtEnd), None);
+    assert_eq!(keystrokes_for("a1", TELEX, Style::MarkAtEnd), None);
+    assert_eq!(
+        keystrokes_for("", TELEX, Style::MarkAtEnd).as_deref(),
+        Some("")
+    );
+}
+
+#[test]
+fn traditional_placement_does_not_round_trip() {
+    // The engine places marks in modern style (hoá) by default
+    assert_eq!(
+        keystrokes_for("hóa", TELEX, Style::MarkAtEnd).as_deref(),
+        Some("hoas")
+    );
+    assert!(!round_trips("hóa", TELEX, Style::MarkAtEnd));
+    assert!(round_trips("hoá", TELEX, Style::MarkAtEnd));
+}
+
+#[test]
+fn dictionary_round_trip() {
+    let content = include_str!("data/vietnamese_22k.txt");
+    let cases = [
+        (TELEX, Style::MarkAtEnd),
+        (TELEX, Style::Inline),
+        (VNI, Style::MarkAtEnd),
+        (VNI, Style::Inline),
+        (VIQR, Style::Inline),
+    ];
+    for (method, style) in cases {
+        let mut total = 0;
+        let mut passed = 0;
+        for word in content.lines().flat_map(str::split_whitespace) {
+            total += 1;
+            if round_trips(word, method, style) {
+                passed += 1;
+            }
+        }
+        // Failures are traditional mark placement (hóa, thùy)
+        let pass_rate = passed as f64 / total as f64 * 100.0;
+        assert!(
+            pass_rate >= 99.0,
+            "[{} {:?}] round-trip rate {:.2}%",
+            method,
+            style,
+            pass_rate
+        );
+    }
+}
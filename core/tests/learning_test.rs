@@ -0,0 +1,72 @@
+//! Learning Auto-Restore Tests - Adapting auto-restore to user corrections
+
+mod common;
+use common::type_word;
+use gonhanh_core::engine::learning::Learned;
+use gonhanh_core::engine::Engine;
+
+/// Telex engine with auto-restore, ESC restore and learning (threshold 2)
+fn learning_engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    e.set_esc_restore(true);
+    e.set_learn_auto_restore(true);
+    e.learning_mut().set_threshold(2);
+    e
+}
+
+#[test]
+fn esc_corrections_learn_restore() {
+    let mut e = learning_engine();
+    assert_eq!(type_word(&mut e, "box "), "bõ ");
+    assert_eq!(type_word(&mut e, "box\x1b "), "box ");
+    // One correction is below the threshold
+    assert_eq!(type_word(&mut e, "box "), "bõ ");
+    assert_eq!(type_word(&mut e, "box\x1b "), "box ");
+    assert_eq!(e.learning().decision("box"), Some(Learned::Restore));
+    assert_eq!(type_word(&mut e, "box "), "box ");
+}
+
+#[test]
+fn mark_reverts_learn_restore() {
+    let mut e = learning_engine();
+    assert_eq!(type_word(&mut e, "mixx "), "mix ");
+    assert_eq!(type_word(&mut e, "mixx "), "mix ");
+    assert_eq!(type_word(&mut e, "mix "), "mix ");
+}
+
+#[test]
+fn undone_restores_learn_keep() {
+    let mut e = learning_engine();
+    assert_eq!(type_word(&mut e, "boss "), "boss ");
+    for _ in 0..2 {
+        // Backspace after the restored word takes the restore back
+        type_word(&mut e, "boss <");
+        e.clear_all();
+    }
+    assert_eq!(e.learning().decision("boss"), Some(Learned::Keep));
+    assert_eq!(type_word(&mut e, "boss "), "bos ");
+}
+
+#[test]
+fn off_by_default() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    e.set_esc_restore(true);
+    for _ in 0..4 {
+        type_word(&mut e, "box\x1b ");
+    }
+    assert!(e.learning().is_empty());
+    assert_eq!(type_word(&mut e, "box "), "bõ ");
+}
+
+#[test]
+fn learned_table_survives_export() {
+    let mut e = learning_engine();
+    type_word(&mut e, "mixx mixx ");
+    let bytes = e.learning().export();
+
+    let mut other = learning_engine();
+    assert_eq!(other.learning_mut().import(&bytes), Some(1));
+    assert_eq!(type_word(&mut other, "mix "), "mix ");
+}
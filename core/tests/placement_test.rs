@@ -0,0 +1,65 @@
+//! Tone Placement Tests - Normalizing existing text to one convention
+
+mod common;
+use gonhanh_core::text::{normalize_tone_placement, placement::normalize_word};
+
+#[test]
+fn to_modern() {
+    let r = normalize_tone_placement("Hòa bình, thúy và khỏe.", true);
+    assert_eq!(r.text, "Hoà bình, thuý và khoẻ.");
+    let changed: Vec<(&str, &str)> = r
+        .changes
+        .iter()
+        .map(|c| (c.before.as_str(), c.after.as_str()))
+        .collect();
+    assert_eq!(
+        changed,
+        [("Hòa", "Hoà"), ("thúy", "thuý"), ("khỏe", "khoẻ")]
+    );
+}
+
+#[test]
+fn to_traditional() {
+    let r = normalize_tone_placement("hoà thuý khoẻ", false);
+    assert_eq!(r.text, "hòa thúy khỏe");
+    assert_eq!(r.changes.len(), 3);
+}
+
+#[test]
+fn change_ranges_point_into_original() {
+    let text = "xin chào — hòa!";
+    let r = normalize_tone_placement(text, true);
+    assert_eq!(r.changes.len(), 1);
+    assert_eq!(&text[r.changes[0].range.clone()], "hòa");
+}
+
+#[test]
+fn same_in_both_conventions() {
+    // Final consonant, diacritic priority, qu/gi initials: no change
+    for word in ["hoàn", "thuyền", "quý", "giày", "người", "việt", "HOẠCH"] {
+        assert_eq!(normalize_word(word, true), None, "{}", word);
+        assert_eq!(normalize_word(word, false), None, "{}", word);
+    }
+}
+
+#[test]
+fn uppercase() {
+    assert_eq!(normalize_word("HÒA", true).as_deref(), Some("HOÀ"));
+    assert_eq!(normalize_word("Thúy", true).as_deref(), Some("Thuý"));
+    assert_eq!(normalize_word("KHỎE", true).as_deref(), Some("KHOẺ"));
+}
+
+#[test]
+fn non_vietnamese_left_alone() {
+    // Invalid syllables, foreign words, several marks
+    let text = "hóax café tòá Zòa";
+    let r = normalize_tone_placement(text, true);
+    assert_eq!(r.text, text);
+    assert!(r.changes.is_empty());
+}
+
+#[test]
+fn nfd_stays_decomposed() {
+    let r = normalize_tone_placement("ho\u{0300}a", true);
+    assert_eq!(r.text, "hoa\u{0300}");
+}
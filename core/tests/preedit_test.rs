@@ -0,0 +1,172 @@
+//! Preedit Tests - Composition string mode
+//!
+//! Models an IBus/Fcitx input context: committed document text plus an
+//! inline composing string that is replaced on every key.
+
+mod common;
+use gonhanh_core::data::charset::{self, Charset};
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::preedit::Preedit;
+use gonhanh_core::engine::{Engine, FLAG_DELETE_TAIL};
+use gonhanh_core::utils::{char_to_key_ext, type_word};
+
+/// Committed text + composing string after typing `input` ('<' = backspace)
+struct Context {
+    document: String,
+    preedit: String,
+}
+
+fn key(e: &mut Engine, c: char) -> Preedit {
+    let c = if c == '<' { '\u{8}' } else { c };
+    let (key, caps, shift) = char_to_key_ext(c).expect("typeable char");
+    e.on_key_preedit(key, caps, false, shift)
+}
+
+fn type_preedit(e: &mut Engine, input: &str) -> Context {
+    let mut ctx = Context {
+        document: String::new(),
+        preedit: String::new(),
+    };
+    for c in input.chars() {
+        let p = key(e, c);
+        for _ in 0..p.delete_before {
+            ctx.document.pop();
+        }
+        ctx.document.push_str(&p.commit_string());
+        ctx.preedit = p.preedit_string();
+        assert_eq!(p.cursor, p.preedit_len, "caret stays at the end");
+        if !p.key_consumed() {
+            match c {
+                '<' => {
+                    ctx.document.pop();
+                }
+                '\n' => ctx.document.push('\n'),
+                _ => panic!("printable key '{}' passed through", c),
+            }
+        }
+    }
+    ctx
+}
+
+#[test]
+fn matches_backspace_mode() {
+    for input in [
+        "vieejt nam",
+        "tieesng vieejt, ddi!",
+        "dduwowngf phoos ",
+        "asf",
+        "vieejt<<",
+        "toi<<<ooi",
+        "hello world",
+    ] {
+        let mut e = Engine::new();
+        let ctx = type_preedit(&mut e, input);
+        let expected = type_word(&mut Engine::new(), input);
+        assert_eq!(
+            format!("{}{}", ctx.document, ctx.preedit),
+            expected,
+            "'{}'",
+            input
+        );
+    }
+}
+
+#[test]
+fn composing_then_commit_on_space() {
+    let mut e = Engine::new();
+    for c in "viee".chars() {
+        key(&mut e, c);
+    }
+    let p = key(&mut e, 't');
+    assert_eq!(p.preedit_string(), "viêt");
+    assert_eq!(p.commit_string(), "");
+    assert!(p.key_consumed());
+
+    let p = key(&mut e, 'j');
+    assert_eq!(p.preedit_string(), "việt");
+    assert_eq!(p.cursor, 4);
+
+    let p = key(&mut e, ' ');
+    assert_eq!(p.commit_string(), "việt ");
+    assert_eq!(p.preedit_string(), "");
+}
+
+#[test]
+fn enter_commits_and_passes_through() {
+    let mut e = Engine::new();
+    let ctx = type_preedit(&mut e, "xin chaof\n");
+    assert_eq!(ctx.document, "xin chào\n");
+    assert_eq!(ctx.preedit, "");
+}
+
+#[test]
+fn backspace_with_nothing_composing_passes_through() {
+    let mut e = Engine::new();
+    let p = e.on_key_preedit(keys::DELETE, false, false, false);
+    assert!(!p.key_consumed());
+}
+
+#[test]
+fn ctrl_commits() {
+    let mut e = Engine::new();
+    key(&mut e, 'a');
+    key(&mut e, 'a');
+    let p = e.on_key_preedit(keys::C, false, true, false);
+    assert_eq!(p.commit_string(), "â");
+    assert!(!p.key_consumed());
+}
+
+#[test]
+fn backspace_reopens_previous_word() {
+    let mut e = Engine::new();
+    let ctx = type_preedit(&mut e, "vieejt <s");
+    assert_eq!(ctx.document, "");
+    assert_eq!(ctx.preedit, "viết");
+}
+
+#[test]
+fn suggestion_for_committed_word() {
+    let mut e = Engine::new();
+    e.set_suggest_on_invalid(true);
+    for c in "tiengs".chars() {
+        key(&mut e, c);
+    }
+    let p = key(&mut e, ' ');
+    assert_eq!(p.commit_string(), "tiéng ");
+    assert_eq!(p.suggestion_string(), "tiếng");
+    assert_eq!(p.suggestion_backspace, 5);
+}
+
+#[test]
+fn surrounding_word_is_edited_at_caret() {
+    let mut e = Engine::new();
+    assert_eq!(e.restore_surrounding("xin Vi", "et nam"), 2);
+    // The "et" tail is deleted and composed again at the caret
+    let p = key(&mut e, 'j');
+    assert_ne!(p.flags & FLAG_DELETE_TAIL, 0);
+    assert_eq!(p.delete_before, 0);
+    assert_eq!(p.preedit_string(), "ệt");
+
+    let p = key(&mut e, 's');
+    assert_eq!(p.flags & FLAG_DELETE_TAIL, 0);
+    assert_eq!(p.preedit_string(), "ết");
+    let p = key(&mut e, ' ');
+    assert_eq!(p.commit_string(), "ết ");
+}
+
+#[test]
+fn legacy_charset_is_encoded() {
+    let mut e = Engine::new();
+    e.set_charset(Charset::VniWindows);
+    let ctx = type_preedit(&mut e, "vieejt nam");
+    let encoded = |text: &str| -> String {
+        text.chars()
+            .flat_map(|c| match charset::screen_form(c, Charset::VniWindows) {
+                Some((form, len)) => form[..len].to_vec(),
+                None => vec![c],
+            })
+            .collect()
+    };
+    assert_eq!(ctx.document, encoded("việt "));
+    assert_eq!(ctx.preedit, "nam");
+}
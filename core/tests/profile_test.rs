@@ -0,0 +1,105 @@
+//! Profile Tests - Per-application settings
+
+mod common;
+use gonhanh_core::engine::profile::DEFAULT_PROFILE;
+use gonhanh_core::engine::shortcut::Shortcut;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+/// Telex default; VNI for "word"; auto-restore with a shortcut for
+/// "terminal"; disabled for "game"
+fn engine_with_profiles() -> Engine {
+    let mut e = Engine::new();
+    e.save_profile(DEFAULT_PROFILE);
+
+    e.set_method(1);
+    e.save_profile("vni");
+
+    e.set_method(0);
+    e.set_english_auto_restore(true);
+    e.shortcuts_mut().add(Shortcut::new("gst", "git status"));
+    e.save_profile("terminal");
+
+    e.set_enabled(false);
+    e.save_profile("game");
+
+    let profiles = e.profiles_mut();
+    profiles.assign("com.microsoft.Word", "vni");
+    profiles.assign("com.apple.Terminal", "terminal");
+    profiles.assign("steam", "game");
+    e
+}
+
+#[test]
+fn switch_applies_app_profile() {
+    let mut e = engine_with_profiles();
+
+    assert!(e.switch_app("com.microsoft.Word"));
+    assert_eq!(type_word(&mut e, "vie65t "), "việt ");
+
+    assert!(e.switch_app("com.apple.Terminal"));
+    assert_eq!(type_word(&mut e, "text "), "text ");
+    assert_eq!(type_word(&mut e, "gst "), "git status ");
+
+    assert!(e.switch_app("steam"));
+    assert_eq!(type_word(&mut e, "vieetj "), "vieetj ");
+
+    // Unassigned apps use the default profile
+    assert!(e.switch_app("org.mozilla.firefox"));
+    assert_eq!(type_word(&mut e, "vieetj "), "việt ");
+    assert_eq!(type_word(&mut e, "text "), "tẽt ");
+    assert_eq!(type_word(&mut e, "gst "), "gst ");
+}
+
+#[test]
+fn settings_changed_in_app_are_kept() {
+    let mut e = engine_with_profiles();
+    e.switch_app("com.apple.Terminal");
+    e.set_enabled(false);
+
+    e.switch_app("com.microsoft.Word");
+    assert_eq!(type_word(&mut e, "vie65t "), "việt ");
+
+    e.switch_app("com.apple.Terminal");
+    assert_eq!(type_word(&mut e, "vieetj "), "vieetj ");
+    assert!(!e.profiles().get("terminal").unwrap().config.enabled);
+}
+
+#[test]
+fn switch_starts_fresh_word() {
+    let mut e = engine_with_profiles();
+    e.switch_app("org.mozilla.firefox");
+    type_word(&mut e, "vie");
+    e.switch_app("com.apple.Terminal");
+    assert_eq!(e.get_buffer_string(), "");
+    // Backspace can't reopen a word committed in another app
+    type_word(&mut e, "ab ");
+    e.switch_app("org.mozilla.firefox");
+    assert_eq!(type_word(&mut e, "<s"), "s");
+}
+
+#[test]
+fn first_switch_saves_default() {
+    let mut e = Engine::new();
+    e.set_method(1);
+    e.profiles_mut().assign("telex-app", "telex");
+    // "telex" isn't defined yet: settings stay as they are
+    assert!(!e.switch_app("telex-app"));
+    assert_eq!(e.profiles().get(DEFAULT_PROFILE).unwrap().config.method, 1);
+
+    e.set_method(0);
+    assert!(e.switch_app("other"));
+    assert_eq!(type_word(&mut e, "a1 "), "á ");
+    // Leaving saved the settings used in "telex-app"
+    assert!(e.switch_app("telex-app"));
+    assert_eq!(type_word(&mut e, "as "), "á ");
+}
+
+#[test]
+fn removed_profile_falls_back_to_default() {
+    let mut e = engine_with_profiles();
+    e.switch_app("org.mozilla.firefox");
+    assert!(e.profiles_mut().remove("game").is_some());
+    assert!(e.switch_app("steam"));
+    assert_eq!(type_word(&mut e, "vieetj "), "việt ");
+}
@@ -0,0 +1,88 @@
+//! Snapshot Tests - Parking and resuming the typing state
+
+mod common;
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+/// State of `e` moved into a new engine
+fn resume(e: &Engine) -> Engine {
+    let mut resumed = Engine::new();
+    assert!(resumed.restore_snapshot(&e.snapshot()));
+    resumed
+}
+
+#[test]
+fn word_in_progress_resumes() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vieet");
+    let mut resumed = resume(&e);
+    assert_eq!(resumed.get_buffer_string(), "viêt");
+    let r = resumed.on_key(keys::J, false, false);
+    assert_eq!(r.backspace, 2);
+    assert_eq!(resumed.get_buffer_string(), "việt");
+}
+
+#[test]
+fn mark_revert_resumes() {
+    // "tess" reverted the mark: finishing "tesst " must restore "test" the
+    // same way in the resumed engine
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    type_word(&mut e, "tess");
+    let mut resumed = resume(&e);
+    resumed.set_english_auto_restore(true);
+    for key in [keys::T, keys::SPACE] {
+        let expected = e.on_key(key, false, false);
+        let got = resumed.on_key(key, false, false);
+        assert_eq!(
+            (got.action, got.backspace, &got.chars[..got.count as usize]),
+            (
+                expected.action,
+                expected.backspace,
+                &expected.chars[..expected.count as usize]
+            )
+        );
+    }
+}
+
+#[test]
+fn word_history_resumes() {
+    let mut e = Engine::new();
+    type_word(&mut e, "chao ");
+    let mut resumed = resume(&e);
+    // Backspace after the space re-opens "chao" for editing
+    resumed.on_key(keys::DELETE, false, false);
+    resumed.on_key(keys::F, false, false);
+    assert_eq!(resumed.get_buffer_string(), "chào");
+}
+
+#[test]
+fn pending_capitalize_resumes() {
+    let mut e = Engine::new();
+    e.set_auto_capitalize(true);
+    type_word(&mut e, "xin. ");
+    let mut resumed = resume(&e);
+    resumed.set_auto_capitalize(true);
+    assert_eq!(type_word(&mut resumed, "chao"), "Chao");
+}
+
+#[test]
+fn options_are_not_part_of_snapshot() {
+    let mut e = Engine::new();
+    e.set_method(1);
+    let mut other = Engine::new();
+    assert!(other.restore_snapshot(&e.snapshot()));
+    assert_eq!(other.config().method, 0);
+}
+
+#[test]
+fn bad_snapshot_is_rejected() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vie");
+    let before = e.snapshot();
+    assert!(!e.restore_snapshot(b""));
+    assert!(!e.restore_snapshot(b"GNS1\x05"));
+    assert_eq!(e.snapshot(), before);
+    assert_eq!(e.get_buffer_string(), "vie");
+}
@@ -0,0 +1,90 @@
+//! Spellcheck Tests - Syllable linting over arbitrary text
+
+mod common;
+use gonhanh_core::engine::validation::ValidationResult;
+use gonhanh_core::text::spellcheck::check_syllable;
+use gonhanh_core::text::{spellcheck, Reason};
+
+fn reasons(text: &str) -> Vec<(&str, Reason)> {
+    spellcheck(text)
+        .into_iter()
+        .map(|issue| (&text[issue.span], issue.reason))
+        .collect()
+}
+
+#[test]
+fn correct_text_has_no_issues() {
+    assert!(spellcheck("Tiếng Việt là ngôn ngữ của người Việt Nam.").is_empty());
+    assert!(spellcheck("Huế, Đà Nẵng và TP. Hồ Chí Minh — 2024!").is_empty());
+}
+
+#[test]
+fn every_dictionary_word_passes() {
+    let dic = include_str!("../src/data/dictionaries/vi.dic");
+    for word in dic.lines().skip(1) {
+        assert_eq!(check_syllable(word), None, "{}", word);
+    }
+}
+
+#[test]
+fn spans_point_into_text() {
+    let text = "xin chào, tôi tên là Kiên và Cim";
+    let issues = spellcheck(text);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(&text[issues[0].span.clone()], "Cim");
+}
+
+#[test]
+fn spelling_rules() {
+    assert_eq!(
+        reasons("cem kà ghà nghá ngé"),
+        [
+            ("cem", Reason::Spelling("c before e/i/y")),
+            ("kà", Reason::Spelling("k before a/o/u")),
+            ("ghà", Reason::Spelling("gh before a/o/u")),
+            ("nghá", Reason::Spelling("ngh before a/o/u")),
+            ("ngé", Reason::Spelling("ng before e/i")),
+        ]
+    );
+}
+
+#[test]
+fn structural_errors() {
+    assert_eq!(
+        reasons("bla hellô xyz"),
+        [
+            ("bla", Reason::Invalid(ValidationResult::InvalidInitial)),
+            ("hellô", Reason::Invalid(ValidationResult::InvalidFinal)),
+            ("xyz", Reason::Invalid(ValidationResult::InvalidFinal)),
+        ]
+    );
+    assert_eq!(
+        check_syllable("bcd"),
+        Some(Reason::Invalid(ValidationResult::NoVowel))
+    );
+}
+
+#[test]
+fn stop_final_marks() {
+    // p, t, c, ch only take sắc or nặng
+    assert_eq!(
+        reasons("mặt mất mãt màc hỏp"),
+        [
+            ("mãt", Reason::StopFinalMark),
+            ("màc", Reason::StopFinalMark),
+            ("hỏp", Reason::StopFinalMark),
+        ]
+    );
+}
+
+#[test]
+fn well_formed_but_unknown() {
+    assert_eq!(check_syllable("khuỳn"), Some(Reason::NotInDictionary));
+}
+
+#[test]
+fn nfd_text() {
+    assert!(spellcheck("Vie\u{0323}\u{0302}t Nam").is_empty());
+    let text = "ma\u{0303}t";
+    assert_eq!(reasons(text), [(text, Reason::StopFinalMark)]);
+}
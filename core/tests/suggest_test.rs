@@ -0,0 +1,96 @@
+//! Suggest Tests - Corrections for misspelled syllables
+
+mod common;
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::{Action, Engine};
+use gonhanh_core::text::suggest;
+use gonhanh_core::utils::char_to_key_ext;
+
+/// Top suggestion for each (misspelled, expected) pair
+fn top(cases: &[(&str, &str)]) {
+    for (word, expected) in cases {
+        let got = suggest(word, 1);
+        assert_eq!(
+            got.first().map(String::as_str),
+            Some(*expected),
+            "'{}'",
+            word
+        );
+    }
+}
+
+/// `expected` is among the first `n` suggestions
+fn within(n: usize, cases: &[(&str, &str)]) {
+    for (word, expected) in cases {
+        let got = suggest(word, n);
+        assert!(got.iter().any(|s| s == expected), "'{}' → {:?}", word, got);
+    }
+}
+
+#[test]
+fn missing_circumflex() {
+    top(&[("tiéng", "tiếng"), ("Nguyẽn", "Nguyễn")]);
+}
+
+#[test]
+fn spelling_rules() {
+    top(&[("ngĩa", "nghĩa"), ("ghà", "gà"), ("cém", "kém")]);
+}
+
+#[test]
+fn consonant_confusion() {
+    within(5, &[("chường", "trường"), ("sin", "xin"), ("dia", "gia")]);
+}
+
+#[test]
+fn wrong_final() {
+    within(5, &[("Việc", "Việt"), ("mặc", "mặt")]);
+}
+
+#[test]
+fn keeps_case_and_accepts_nfd() {
+    top(&[("TIÉNG", "TIẾNG"), ("tie\u{0301}ng", "tiếng")]);
+}
+
+#[test]
+fn nothing_for_non_letters() {
+    assert!(suggest("", 3).is_empty());
+    assert!(suggest("a1", 3).is_empty());
+    assert!(suggest("tiéng", 0).is_empty());
+    // Never suggests the word itself
+    assert!(!suggest("tiếng", 5).contains(&"tiếng".to_string()));
+}
+
+/// Type `input` then SPACE, returning the SPACE result
+fn commit(e: &mut Engine, input: &str) -> (u8, u8, String) {
+    for c in input.chars() {
+        let (key, caps, shift) = char_to_key_ext(c).unwrap();
+        e.on_key_ext(key, caps, false, shift);
+    }
+    let r = e.on_key_ext(keys::SPACE, false, false, false);
+    let chars = r.chars[..r.count as usize]
+        .iter()
+        .filter_map(|&c| char::from_u32(c))
+        .collect();
+    (r.action, r.backspace, chars)
+}
+
+#[test]
+fn engine_suggests_on_commit() {
+    let mut e = Engine::new();
+    e.set_suggest_on_invalid(true);
+    // "tiéng" typed instead of "tiếng"
+    assert_eq!(
+        commit(&mut e, "tiengs"),
+        (Action::Suggest as u8, 5, "tiếng".to_string())
+    );
+    // Correct words and plain ASCII words get nothing
+    assert_eq!(commit(&mut e, "tieengs").0, Action::None as u8);
+    assert_eq!(commit(&mut e, "hello").0, Action::None as u8);
+}
+
+#[test]
+fn engine_suggestions_off_by_default() {
+    let mut e = Engine::new();
+    assert_eq!(commit(&mut e, "tiengs").0, Action::None as u8);
+}
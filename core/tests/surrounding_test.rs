@@ -0,0 +1,130 @@
+//! Surrounding Text Tests - Editing the word around the caret
+//!
+//! Simulates an editor with a caret: results are applied at the caret, and
+//! `FLAG_DELETE_TAIL` deletes the rest of the word after it first.
+
+mod common;
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::{Action, Engine, FLAG_DELETE_TAIL};
+
+/// Text split at the caret
+struct Editor {
+    before: String,
+    after: String,
+}
+
+impl Editor {
+    /// `text` marks the caret with '|'
+    fn new(text: &str) -> Self {
+        let (before, after) = text.split_once('|').expect("caret marker");
+        Self {
+            before: before.to_string(),
+            after: after.to_string(),
+        }
+    }
+
+    /// Restore from the caret, then type `input` ('<' = backspace)
+    fn type_at_caret(&mut self, e: &mut Engine, input: &str) -> String {
+        let tail = e.restore_surrounding(&self.before, &self.after);
+        for c in input.chars() {
+            let c = if c == '<' { '\u{8}' } else { c };
+            let r = e.on_char(c, false);
+            if r.action == Action::Send as u8 {
+                if r.flags & FLAG_DELETE_TAIL != 0 {
+                    self.after = self.after.chars().skip(tail).collect();
+                }
+                for _ in 0..r.backspace {
+                    self.before.pop();
+                }
+                self.before.extend(
+                    r.chars[..r.count as usize]
+                        .iter()
+                        .filter_map(|&c| char::from_u32(c)),
+                );
+            } else if c == '\u{8}' {
+                self.before.pop();
+            } else {
+                self.before.push(c);
+            }
+        }
+        format!("{}|{}", self.before, self.after)
+    }
+}
+
+fn run(cases: &[(&str, &str, &str)]) {
+    for (text, input, expected) in cases {
+        let mut e = Engine::new();
+        let result = Editor::new(text).type_at_caret(&mut e, input);
+        assert_eq!(result, *expected, "'{}' + '{}'", text, input);
+    }
+}
+
+#[test]
+fn caret_at_word_end() {
+    run(&[
+        ("Viet|", "j", "Việt|"),
+        ("xin chao|", "f", "xin chào|"),
+        ("tieng Viet|", "s", "tieng Viết|"),
+        ("việt|", "s", "viết|"),
+    ]);
+}
+
+#[test]
+fn caret_inside_word() {
+    run(&[
+        // Edit reaches past the caret: the tail is replaced
+        ("Vi|et", "j", "Việt|"),
+        ("ch|ao ban", "f", "chào| ban"),
+        // Edit stays before the caret: the tail is left alone
+        ("to|i", "o", "tô|i"),
+        ("ha|nh", "f", "hà|nh"),
+    ]);
+}
+
+#[test]
+fn next_to_punctuation() {
+    run(&[
+        ("(Viet|)", "j", "(Việt|)"),
+        ("(Vi|et)", "j", "(Việt|)"),
+        ("Viet|, nam", "j", "Việt|, nam"),
+        ("\"chao|\"", "f", "\"chào|\""),
+    ]);
+}
+
+#[test]
+fn consonant_starts_new_word() {
+    // Only mark/tone keys edit the restored word
+    run(&[("Viet|", "n", "Vietn|"), ("Vi|et", "n", "Vin|et")]);
+}
+
+#[test]
+fn no_word_at_caret() {
+    let mut e = Engine::new();
+    assert_eq!(e.restore_surrounding("xin chao ", "ban"), 3);
+    assert_eq!(e.get_buffer_string(), "ban");
+    assert_eq!(e.restore_surrounding("xin ", " ban"), 0);
+    assert_eq!(e.get_buffer_string(), "");
+}
+
+#[test]
+fn returns_tail_length() {
+    let mut e = Engine::new();
+    assert_eq!(e.restore_surrounding("Vi", "ệt nam"), 2);
+    assert_eq!(e.get_buffer_string(), "Việt");
+    // NFD tail counts every combining char
+    assert_eq!(e.restore_surrounding("Vi", "e\u{0323}\u{0302}t"), 4);
+    assert_eq!(e.get_buffer_string(), "Việt");
+}
+
+#[test]
+fn restore_word_does_not_complete_vowels() {
+    // Only surrounding text is completed: `restore_word` keeps the word as is
+    let mut e = Engine::new();
+    e.restore_word("Viet");
+    e.on_key(keys::J, false, false);
+    assert_eq!(e.get_buffer_string(), "Viẹt");
+
+    e.restore_surrounding("Viet", "");
+    e.on_key(keys::J, false, false);
+    assert_eq!(e.get_buffer_string(), "Việt");
+}
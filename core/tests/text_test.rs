@@ -0,0 +1,80 @@
+//! Text Tests - Diacritic removal and slugs
+
+mod common;
+use gonhanh_core::text::{slugify, strip_diacritics, SlugOptions, StripOptions};
+
+#[test]
+fn strip_sentence() {
+    let opts = StripOptions::default();
+    assert_eq!(
+        strip_diacritics("Tiếng Việt có dấu", opts),
+        "Tieng Viet co dau"
+    );
+    assert_eq!(
+        strip_diacritics("ĐƯỜNG PHỐ Hà Nội", opts),
+        "DUONG PHO Ha Noi"
+    );
+}
+
+#[test]
+fn strip_keep_d() {
+    let keep = StripOptions { keep_d: true };
+    assert_eq!(strip_diacritics("Đường đi", keep), "Đuong đi");
+    assert_eq!(
+        strip_diacritics("Đường đi", StripOptions::default()),
+        "Duong di"
+    );
+}
+
+#[test]
+fn strip_nfd_input() {
+    let opts = StripOptions::default();
+    // "việt" and "đường" in decomposed form
+    assert_eq!(strip_diacritics("vie\u{0323}\u{0302}t", opts), "viet");
+    assert_eq!(
+        strip_diacritics("đu\u{031B}o\u{031B}\u{0300}ng", opts),
+        "duong"
+    );
+    // Combining marks in non-canonical order
+    assert_eq!(strip_diacritics("vie\u{0302}\u{0323}t", opts), "viet");
+}
+
+#[test]
+fn strip_leaves_other_text() {
+    let opts = StripOptions::default();
+    assert_eq!(
+        strip_diacritics("piñata über ça 123 → ok", opts),
+        "piñata über ça 123 → ok"
+    );
+    // Stray Vietnamese combining mark on a non-Vietnamese letter
+    assert_eq!(strip_diacritics("n\u{0303}", opts), "n");
+}
+
+#[test]
+fn slug_default() {
+    let opts = SlugOptions::default();
+    assert_eq!(slugify("Đường Lê Lợi, Q.1", opts), "duong-le-loi-q-1");
+    assert_eq!(
+        slugify("  Xin chào -- Việt Nam!  ", opts),
+        "xin-chao-viet-nam"
+    );
+    assert_eq!(slugify("!!!", opts), "");
+}
+
+#[test]
+fn slug_separator_and_case() {
+    let opts = SlugOptions {
+        separator: '_',
+        lowercase: false,
+    };
+    assert_eq!(slugify("Báo cáo Quý 3.pdf", opts), "Bao_cao_Quy_3_pdf");
+}
+
+#[test]
+fn slug_nfd_matches_nfc() {
+    let opts = SlugOptions::default();
+    assert_eq!(
+        slugify("Vie\u{0323}\u{0302}t Nam", opts),
+        slugify("Việt Nam", opts)
+    );
+}
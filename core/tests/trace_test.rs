@@ -0,0 +1,110 @@
+//! Trace Tests - Recording keystrokes and replaying them
+
+mod common;
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::shortcut::Shortcut;
+use gonhanh_core::engine::trace::Trace;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+/// Trace of `text` typed on `e`, written and read back as text
+fn record(e: &mut Engine, text: &str) -> Trace {
+    e.start_trace();
+    type_word(e, text);
+    let trace = e.stop_trace().unwrap();
+    Trace::parse(&trace.to_string()).unwrap()
+}
+
+#[test]
+fn replay_reproduces_recording() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    let trace = record(&mut e, "Vieejt tesst ");
+    assert!(trace.config.english_auto_restore);
+    assert_eq!(trace.state, None);
+    assert_eq!(trace.keys().count(), 13);
+    assert!(trace.mismatches().is_empty());
+}
+
+#[test]
+fn not_recording_by_default() {
+    let mut e = Engine::new();
+    type_word(&mut e, "abc");
+    assert!(e.trace().is_none());
+    assert!(e.stop_trace().is_none());
+}
+
+#[test]
+fn option_changes_are_replayed() {
+    let mut e = Engine::new();
+    e.start_trace();
+    type_word(&mut e, "as ");
+    e.set_method(1);
+    type_word(&mut e, "a1 ");
+    let text = e.stop_trace().unwrap().to_string();
+    assert!(text.contains("\nmethod = vni\n"), "{}", text);
+
+    let trace = Trace::parse(&text).unwrap();
+    assert_eq!(trace.config.method, 0);
+    assert!(trace.mismatches().is_empty());
+}
+
+#[test]
+fn started_mid_word_keeps_state() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vie");
+    let trace = record(&mut e, "ej");
+    assert!(trace.state.is_some());
+    // Replayed without the state, "ej" would type "ẹ"
+    assert!(trace.mismatches().is_empty());
+}
+
+#[test]
+fn unreadable_state_is_an_error() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vie");
+    let text = record(&mut e, "ej").to_string();
+    // A truncated snapshot can't be restored, so the trace can't be replayed
+    let state = text.lines().find(|l| l.starts_with("state = ")).unwrap();
+    let edited = text.replace(state, &state[..state.len() - 2]);
+    let err = Trace::parse(&edited).unwrap_err();
+    assert!(err.message.contains("state"), "{}", err.message);
+}
+
+#[test]
+fn preedit_keys_are_recorded() {
+    let mut e = Engine::new();
+    e.start_trace();
+    for key in [keys::V, keys::I, keys::E, keys::E, keys::J, keys::T] {
+        e.on_key_preedit(key, false, false, false);
+    }
+    let trace = Trace::parse(&e.stop_trace().unwrap().to_string()).unwrap();
+    assert_eq!(trace.keys().count(), 6);
+    assert!(trace.mismatches().is_empty());
+}
+
+#[test]
+fn symbol_chars_are_recorded() {
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::immediate("√√", "✅"));
+    e.start_trace();
+    e.on_key_with_char(keys::V, false, false, false, Some('√'));
+    e.on_char('√', false);
+    let trace = e.stop_trace().unwrap();
+    assert!(trace
+        .to_string()
+        .ends_with(" U+221A -> send bs=1 flags=1 \"✅\"\n"));
+}
+
+#[test]
+fn mismatches_point_at_the_key() {
+    let mut e = Engine::new();
+    let text = record(&mut e, "vieet").to_string();
+    // Pretend the report expected something else for the second 'e'
+    let edited = text.replace("14 -> send bs=1 \"ê\"", "14 -> none");
+    let trace = Trace::parse(&edited).unwrap();
+    let mismatches = trace.mismatches();
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].index, 4);
+    assert_eq!(mismatches[0].actual.chars, "ê");
+}
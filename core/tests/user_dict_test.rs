@@ -0,0 +1,198 @@
+//! User Dictionary Tests - Runtime words merged with the built-in lists
+//!
+//! User dictionaries are global, so every test runs serially and clears them.
+
+mod common;
+use common::type_word;
+use gonhanh_core::data::user_dict::{self, WordList};
+use gonhanh_core::data::{dictionary, english_dict};
+use gonhanh_core::engine::Engine;
+use serial_test::serial;
+
+fn reset() {
+    for list in [
+        WordList::Vietnamese,
+        WordList::Keep,
+        WordList::English,
+        WordList::NeverRestore,
+    ] {
+        user_dict::clear(list);
+    }
+}
+
+fn auto_restore(input: &str) -> String {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    type_word(&mut e, input)
+}
+
+#[test]
+#[serial]
+fn add_and_remove_vietnamese_words() {
+    reset();
+    assert!(!dictionary::is_vietnamese("gonhanh", false));
+    assert!(user_dict::add(WordList::Vietnamese, "GoNhanh"));
+    assert!(!user_dict::add(WordList::Vietnamese, "gonhanh"));
+    assert!(dictionary::is_vietnamese("gonhanh", false));
+    assert!(dictionary::contains("GONHANH"));
+
+    assert!(user_dict::remove(WordList::Vietnamese, "gonhanh"));
+    assert!(!dictionary::is_vietnamese("gonhanh", false));
+
+    // Words past the lookup buffer are rejected, not silently unreachable
+    let long = "ă".repeat(40);
+    assert!(!user_dict::add(WordList::Vietnamese, &long));
+    assert!(!dictionary::contains(&long));
+    reset();
+}
+
+#[test]
+#[serial]
+fn remove_hides_builtin_word() {
+    reset();
+    assert!(dictionary::is_vietnamese("việt", false));
+    user_dict::remove(WordList::Vietnamese, "Việt");
+    assert!(!dictionary::is_vietnamese("việt", false));
+    assert!(!dictionary::contains("Việt"));
+
+    // Adding it back, or clearing, restores it
+    user_dict::add(WordList::Vietnamese, "việt");
+    assert!(dictionary::is_vietnamese("việt", false));
+    user_dict::remove(WordList::Vietnamese, "việt");
+    reset();
+    assert!(dictionary::is_vietnamese("việt", false));
+}
+
+#[test]
+#[serial]
+fn load_dic_content() {
+    reset();
+    let content = "3\n# team words\nzalo\n\nmomo\nviettel\n";
+    assert_eq!(user_dict::load(WordList::Vietnamese, content), 3);
+    for word in ["zalo", "Momo", "viettel"] {
+        assert!(dictionary::contains(word), "{}", word);
+    }
+    // "3" is the header, not a word; loading again adds nothing
+    assert!(!dictionary::contains("3"));
+    assert_eq!(user_dict::load(WordList::Vietnamese, content), 0);
+    reset();
+}
+
+#[test]
+#[serial]
+fn load_file() {
+    reset();
+    let path = std::env::temp_dir().join("gonhanh_user_dict_test.dic");
+    std::fs::write(&path, "sapa\nphanxipăng\n").unwrap();
+    assert_eq!(
+        user_dict::load_file(WordList::Vietnamese, &path).unwrap(),
+        2
+    );
+    assert!(dictionary::is_vietnamese("phanxipăng", false));
+    std::fs::remove_file(&path).unwrap();
+    assert!(user_dict::load_file(WordList::Vietnamese, &path).is_err());
+    reset();
+}
+
+#[test]
+#[serial]
+fn keep_list_blocks_auto_restore() {
+    reset();
+    assert_eq!(auto_restore("mass "), "mass ");
+    user_dict::add(WordList::Keep, "mas");
+    assert!(dictionary::should_keep("MAS"));
+    assert_eq!(auto_restore("mass "), "mas ");
+
+    // Built-in keep words can be removed: "sess" stays English
+    assert_eq!(auto_restore("sess "), "ses ");
+    user_dict::remove(WordList::Keep, "ses");
+    assert_eq!(auto_restore("sess "), "sess ");
+    reset();
+}
+
+#[test]
+#[serial]
+fn vietnamese_word_blocks_auto_restore() {
+    reset();
+    assert_eq!(auto_restore("soon "), "soon ");
+    user_dict::add(WordList::Vietnamese, "sôn");
+    assert_eq!(auto_restore("soon "), "sôn ");
+    reset();
+}
+
+#[test]
+#[serial]
+fn english_words_restore() {
+    reset();
+    assert_eq!(auto_restore("terraform "), "teraform ");
+    assert_eq!(auto_restore("aix "), "ãi ");
+    user_dict::load(WordList::English, "terraform\naix\nkubectl\n");
+    assert!(english_dict::is_english_word("Kubectl"));
+    assert_eq!(auto_restore("terraform "), "terraform ");
+    assert_eq!(auto_restore("aix "), "aix ");
+    reset();
+}
+
+#[test]
+#[serial]
+fn english_words_keep_vietnamese_results() {
+    reset();
+    // "mĩ" and "sĩ" are Vietnamese words: listing "mix"/"six" doesn't
+    // restore them
+    user_dict::add(WordList::English, "mix");
+    user_dict::add(WordList::English, "six");
+    assert_eq!(auto_restore("mix "), "mĩ ");
+    assert_eq!(auto_restore("six "), "sĩ ");
+    reset();
+}
+
+#[test]
+#[serial]
+fn affix_rules() {
+    reset();
+    user_dict::add(WordList::English, "serialize");
+    user_dict::add(WordList::English, "commit");
+    for word in [
+        "serializes",
+        "deserialize",
+        "deserializing",
+        "reserialized",
+        "committed",
+        "commits",
+    ] {
+        assert!(english_dict::is_english_word(word), "{}", word);
+    }
+    assert!(!english_dict::is_english_word("serializationx"));
+
+    // Custom rules; removing a default one stops it matching
+    user_dict::add(WordList::English, "popo");
+    assert!(!english_dict::is_english_word("popos2"));
+    assert!(user_dict::add(WordList::English, "-s2"));
+    assert!(english_dict::is_english_word("popos2"));
+    assert!(user_dict::remove(WordList::English, "de-"));
+    assert!(!english_dict::is_english_word("deserialize"));
+
+    // Clearing restores the default rules
+    reset();
+    user_dict::add(WordList::English, "serialize");
+    assert!(english_dict::is_english_word("deserialize"));
+    reset();
+}
+
+#[test]
+#[serial]
+fn never_restore() {
+    reset();
+    assert_eq!(auto_restore("boss "), "boss ");
+    assert_eq!(auto_restore("bosses "), "bosses ");
+    user_dict::add(WordList::NeverRestore, "boss");
+    assert_eq!(auto_restore("boss "), "bos ");
+    // Inflected forms count too
+    assert_eq!(auto_restore("bosses "), "boses ");
+
+    // Wins over the English list
+    user_dict::add(WordList::English, "aix");
+    user_dict::add(WordList::NeverRestore, "aix");
+    assert_eq!(auto_restore("aix "), "ãi ");
+    reset();
+}
@@ -0,0 +1,149 @@
+//! VIQR Tests - Symbol-based input method (method 2)
+//!
+//! Marks: ' ` ? ~ .  Tones: ^ + * (  Stroke: dd
+
+mod common;
+use common::viqr;
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::Engine;
+
+// ============================================================
+// MARKS & TONES
+// ============================================================
+
+const VIQR_MARKS: &[(&str, &str)] = &[
+    ("a'", "á"),
+    ("a`", "à"),
+    ("a?", "ả"),
+    ("a~", "ã"),
+    ("a.", "ạ"),
+    // Mark placed by Vietnamese rules, not on the preceding char
+    ("hoa'", "hoá"),
+    ("toan'", "toán"),
+    // Changing mark: ` then ~ share a key
+    ("a`~", "ã"),
+    ("a'`", "à"),
+];
+
+const VIQR_TONES: &[(&str, &str)] = &[
+    ("a^", "â"),
+    ("e^", "ê"),
+    ("o^", "ô"),
+    ("o+", "ơ"),
+    ("u*", "ư"),
+    ("a(", "ă"),
+    ("dd", "đ"),
+    ("Dd", "Đ"),
+];
+
+const VIQR_WORDS: &[(&str, &str)] = &[
+    ("Vie^.t Nam", "Việt Nam"),
+    ("ddu+o+`ng", "đường"),
+    ("tie^'ng Vie^.t", "tiếng Việt"),
+    ("nguye^~n", "nguyễn"),
+    ("a(n", "ăn"),
+    ("kho^ng", "không"),
+];
+
+// ============================================================
+// PUNCTUATION
+// ============================================================
+
+const VIQR_PUNCTUATION: &[(&str, &str)] = &[
+    // ? and . are marks only right after a vowel
+    ("ban.", "ban."),
+    ("ban?", "ban?"),
+    ("ba.", "bạ"),
+    ("sao?", "sảo"), // after a vowel, ? is always hỏi
+    // A word that already has a mark ends the sentence
+    ("qua'.", "quá."),
+    ("qua'?", "quá?"),
+    ("nhie^`u?", "nhiều?"),
+    ("Vie^.t.", "Việt."),
+    // Other symbols with nothing to modify stay literal
+    ("'", "'"),
+    ("x^", "x^"),
+    ("hi +", "hi +"),
+    // Plain digits/slash are never modifiers
+    ("a6", "a6"),
+    ("a/", "a/"),
+];
+
+const VIQR_REVERT: &[(&str, &str)] = &[("a''", "a'"), ("o^^", "o^"), ("a..", "a.")];
+
+// `\` after a word types the next symbol literally
+const VIQR_ESCAPE: &[(&str, &str)] = &[
+    ("nhie^u\\?", "nhiêu?"),
+    ("ba\\.", "ba."),
+    ("a\\'", "a'"),
+    ("a\\\\", "a\\"),
+    // Only symbols are escaped, and only right after a word
+    ("a\\b", "a\\b"),
+    ("\\?", "\\?"),
+];
+
+#[test]
+fn viqr_marks() {
+    viqr(VIQR_MARKS);
+}
+
+#[test]
+fn viqr_tones() {
+    viqr(VIQR_TONES);
+}
+
+#[test]
+fn viqr_words() {
+    viqr(VIQR_WORDS);
+}
+
+#[test]
+fn viqr_punctuation() {
+    viqr(VIQR_PUNCTUATION);
+}
+
+#[test]
+fn viqr_revert() {
+    viqr(VIQR_REVERT);
+}
+
+#[test]
+fn viqr_escape() {
+    viqr(VIQR_ESCAPE);
+}
+
+#[test]
+fn viqr_modifier_consumes_key() {
+    let mut e = Engine::new();
+    e.set_method(2);
+    e.on_key(keys::A, false, false);
+    let r = e.on_key_ext(keys::QUOTE, false, false, false);
+    assert!(r.key_consumed(), "applied modifier must not be typed");
+
+    // Punctuation that doesn't modify passes through
+    let r = e.on_key_ext(keys::COMMA, false, false, false);
+    assert!(!r.key_consumed());
+}
+
+#[test]
+fn viqr_esc_restores_symbols() {
+    let mut e = Engine::new();
+    e.set_method(2);
+    e.set_esc_restore(true);
+    for &(key, shift) in &[
+        (keys::V, false),
+        (keys::I, false),
+        (keys::E, false),
+        (keys::N6, true),
+        (keys::DOT, false),
+        (keys::T, false),
+    ] {
+        e.on_key_ext(key, false, false, shift);
+    }
+    let r = e.on_key(keys::ESC, false, false);
+    let out: String = r.chars[..r.count as usize]
+        .iter()
+        .filter_map(|&c| char::from_u32(c))
+        .collect();
+    assert_eq!(out, "vie^.t");
+}
@@ -1,15 +1,26 @@
 #include "RustBridge.h"
 #include <codecvt>
+#include <iostream>
 #include <locale>
 
 bool RustBridge::initialized_ = false;
 
 void RustBridge::initialize() {
     if (initialized_) return;
+    if (!isAbiCompatible()) {
+        std::cerr << "[GoNhanh] Core library ABI mismatch: library v" << ime_abi_version()
+                  << " (ImeResult " << ime_result_size() << " bytes), header v"
+                  << GONHANH_ABI_VERSION << " (" << sizeof(ImeResult) << " bytes)" << std::endl;
+        return;
+    }
     ime_init();
     initialized_ = true;
 }
 
+bool RustBridge::isAbiCompatible() {
+    return ime_abi_version() == GONHANH_ABI_VERSION && ime_result_size() == sizeof(ImeResult);
+}
+
 std::pair<int, std::string> RustBridge::processKey(
     uint16_t keyCode,
     bool caps,
@@ -18,6 +29,9 @@ std::pair<int, std::string> RustBridge::processKey(
 ) {
     if (!initialized_) {
         initialize();
+        if (!initialized_) {
+            return {0, ""};
+        }
     }
 
     ImeResult* result = ime_key_ext(keyCode, caps, ctrl, shift);
@@ -31,7 +45,7 @@ std::pair<int, std::string> RustBridge::processKey(
         output.first = result->backspace;
 
         // Convert UTF-32 chars to UTF-8 string
-        for (uint8_t i = 0; i < result->count && i < 32; ++i) {
+        for (int i = 0; i < result->count && i < GONHANH_MAX_CHARS; ++i) {
             if (result->chars[i] > 0) {
                 output.second += codePointToUtf8(result->chars[i]);
             }
@@ -5,29 +5,9 @@
 #include <string>
 #include <vector>
 
-// FFI Result structure - must match core/src/engine/mod.rs
-// #[repr(C)]
-// pub struct Result {
-//     pub chars: [u32; 32],
-//     pub action: u8,
-//     pub backspace: u8,
-//     pub count: u8,
-//     pub _pad: u8,
-// }
-//
-// Note: Rust #[repr(C)] uses C ABI layout, which matches C++ struct layout
-// for this specific arrangement. The array (128 bytes) is followed by
-// 4 bytes of u8 fields = 132 bytes total with no implicit padding needed.
-struct ImeResult {
-    uint32_t chars[32];  // 128 bytes
-    uint8_t action;      // 1 byte
-    uint8_t backspace;   // 1 byte
-    uint8_t count;       // 1 byte
-    uint8_t _pad;        // 1 byte (explicit padding to 4-byte boundary)
-};
-
-// Verify struct size matches Rust at compile time
-static_assert(sizeof(ImeResult) == 132, "ImeResult size mismatch with Rust core");
+// FFI declarations and ImeResult layout are generated from the Rust core:
+// core/include/gonhanh.h (see core/src/abi.rs). Never redeclare them here.
+#include "gonhanh.h"
 
 // Action types
 enum class ImeAction : uint8_t {
@@ -42,22 +22,17 @@ enum class InputMethod : uint8_t {
     VNI = 1
 };
 
-// FFI function declarations (from core/src/lib.rs)
-extern "C" {
-    void ime_init();
-    ImeResult* ime_key_ext(uint16_t key, bool caps, bool ctrl, bool shift);
-    void ime_method(uint8_t method);
-    void ime_enabled(bool enabled);
-    void ime_clear();
-    void ime_free(ImeResult* result);
-}
-
 // C++ wrapper class for Rust bridge
 class RustBridge {
 public:
     // Initialize the IME engine (call once at startup)
+    // Leaves the bridge uninitialized if the core library ABI doesn't match
     static void initialize();
 
+    // Check that the loaded core library matches gonhanh.h
+    // (ABI version and ImeResult size)
+    static bool isAbiCompatible();
+
     // Process a keystroke and return result
     // Returns: (backspace_count, output_text) or empty if no action needed
     static std::pair<int, std::string> processKey(
@@ -174,6 +174,16 @@ TEST(RustBridgeTest, Utf8CommonVietnameseChars) {
     EXPECT_EQ(RustBridge::codePointToUtf8(0x00F2), "\xC3\xB2");      // ò
 }
 
+// =============================================================================
+// ABI Tests - header (core/include/gonhanh.h) vs loaded core library
+// =============================================================================
+
+TEST(RustBridgeTest, AbiMatchesCoreLibrary) {
+    EXPECT_EQ(ime_abi_version(), static_cast<uint32_t>(GONHANH_ABI_VERSION));
+    EXPECT_EQ(ime_result_size(), sizeof(ImeResult));
+    EXPECT_TRUE(RustBridge::isAbiCompatible());
+}
+
 // =============================================================================
 // Main
 // =============================================================================